@@ -0,0 +1,213 @@
+use crate::frequency::is_rare;
+use crate::persist;
+use crate::stats::current_streak;
+use bincode::{Decode, Encode};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::Bot;
+
+/// Badges a chat can earn while playing
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Badge {
+    FirstWin,
+    LongChain,
+    Pangram,
+    RareWord,
+    WeeklyStreak,
+}
+
+impl Badge {
+    /// All badges in display order
+    const ALL: [Badge; 5] = [
+        Badge::FirstWin,
+        Badge::LongChain,
+        Badge::Pangram,
+        Badge::RareWord,
+        Badge::WeeklyStreak,
+    ];
+
+    /// Display name with emoji
+    fn name(&self) -> &'static str {
+        match self {
+            Badge::FirstWin => "🏆 First Win",
+            Badge::LongChain => "🔗 Chain Builder",
+            Badge::Pangram => "🔤 Pangrammer",
+            Badge::RareWord => "💎 Rare Find",
+            Badge::WeeklyStreak => "🔥 Weekly Streak",
+        }
+    }
+
+    /// What it takes to earn the badge
+    fn description(&self) -> &'static str {
+        match self {
+            Badge::FirstWin => "win a game against the bot",
+            Badge::LongChain => "reach a chain of 10 words",
+            Badge::Pangram => "play words covering every letter of the alphabet",
+            Badge::RareWord => "play a rare word",
+            Badge::WeeklyStreak => "play on 7 days in a row",
+        }
+    }
+}
+
+/// Chain length that earns the Chain Builder badge
+const LONG_CHAIN_LEN: usize = 10;
+/// Daily streak, as /stats shows it, that earns the Weekly Streak badge
+const STREAK_DAYS: u32 = 7;
+
+const ACHIEVEMENTS_PATH: &str = "achievements.bin";
+static ACHIEVEMENTS: OnceLock<Mutex<HashMap<i64, ChatAchievements>>> = OnceLock::new();
+
+/// Achievement progress for a single chat
+#[derive(Encode, Decode, Clone, Default)]
+struct ChatAchievements {
+    badges: Vec<Badge>,
+    /// Distinct letters used across all player words
+    letters: Vec<char>,
+}
+
+impl ChatAchievements {
+    /// Awards a badge, returning it only if it wasn't earned before
+    fn award(&mut self, badge: Badge) -> Option<Badge> {
+        if self.badges.contains(&badge) {
+            None
+        } else {
+            self.badges.push(badge);
+            Some(badge)
+        }
+    }
+}
+
+/// Initializes achievements from disk if available
+pub fn init_achievements() {
+    let entries: Vec<(i64, ChatAchievements)> =
+        persist::load(ACHIEVEMENTS_PATH).unwrap_or_default();
+    log::info!("Loaded achievements for {} chats", entries.len());
+    let _ = ACHIEVEMENTS.set(Mutex::new(entries.into_iter().collect()));
+}
+
+/// Saves achievements to disk
+pub fn save_achievements() -> std::io::Result<()> {
+    let entries: Vec<(i64, ChatAchievements)> = get_achievements()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (*k, v.clone()))
+        .collect();
+    persist::save(&entries, ACHIEVEMENTS_PATH)
+}
+
+fn get_achievements() -> &'static Mutex<HashMap<i64, ChatAchievements>> {
+    ACHIEVEMENTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether the chat has recorded any letters played or badges earned
+#[cfg(test)]
+pub fn has_progress(chat_id: ChatId) -> bool {
+    get_achievements()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .is_some_and(|a| !a.badges.is_empty() || !a.letters.is_empty())
+}
+
+/// Applies an update to a chat's progress and returns the badges it newly earned
+fn update<F>(chat_id: ChatId, f: F) -> Vec<Badge>
+where
+    F: FnOnce(&mut ChatAchievements) -> Vec<Badge>,
+{
+    let mut all = get_achievements().lock().unwrap();
+    f(all.entry(chat_id.0).or_default())
+}
+
+/// Records an accepted player word and announces any badges it earns
+pub async fn on_player_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    word: &str,
+    chain_len: usize,
+) -> ResponseResult<()> {
+    let streak = current_streak(chat_id);
+    let earned = update(chat_id, |a| {
+        for c in word.chars().filter(|c| c.is_ascii_lowercase()) {
+            if !a.letters.contains(&c) {
+                a.letters.push(c);
+            }
+        }
+
+        let mut earned = Vec::new();
+        if chain_len >= LONG_CHAIN_LEN {
+            earned.extend(a.award(Badge::LongChain));
+        }
+        if a.letters.len() == 26 {
+            earned.extend(a.award(Badge::Pangram));
+        }
+        if is_rare(word) {
+            earned.extend(a.award(Badge::RareWord));
+        }
+        if streak >= STREAK_DAYS {
+            earned.extend(a.award(Badge::WeeklyStreak));
+        }
+        earned
+    });
+
+    announce(bot, chat_id, &earned).await
+}
+
+/// Records a win against the bot and announces any badges it earns
+pub async fn on_win(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let earned = update(chat_id, |a| a.award(Badge::FirstWin).into_iter().collect());
+    announce(bot, chat_id, &earned).await
+}
+
+/// Sends a message for each newly earned badge
+async fn announce(bot: &Bot, chat_id: ChatId, earned: &[Badge]) -> ResponseResult<()> {
+    for badge in earned {
+        log::info!(chat_id = chat_id.0; "Chat {} earned badge {:?}", chat_id, badge);
+        bot.send_message(
+            chat_id,
+            format!(
+                "New badge unlocked: {} ({})!",
+                badge.name(),
+                badge.description()
+            ),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Show the badges earned in a chat along with the ones still locked
+pub async fn show_badges(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let earned = get_achievements()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .map(|a| a.badges.clone())
+        .unwrap_or_default();
+
+    let lines = Badge::ALL
+        .iter()
+        .map(|b| {
+            if earned.contains(b) {
+                format!("{} ✅", b.name())
+            } else {
+                format!("{} 🔒 ({})", b.name(), b.description())
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Your badges ({}/{}):\n\n{}",
+            earned.len(),
+            Badge::ALL.len(),
+            lines
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
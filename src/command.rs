@@ -5,10 +5,14 @@ use teloxide::macros::BotCommands;
 pub enum Command {
     #[command(description = "Start the bot and show the game menu")]
     Start,
+    #[command(description = "List all commands")]
+    Help,
     #[command(description = "Play a random game")]
     Play,
-    #[command(description = "Get a hint for the current game")]
-    Hint,
+    #[command(
+        description = "Get a hint for the current game; in Word Chain, ask for a kind of word: /hint [noun|verb|adjective|adverb]"
+    )]
+    Hint(String),
     #[command(description = "Skip the current game")]
     Skip,
     #[command(description = "Check your score")]
@@ -17,6 +21,54 @@ pub enum Command {
     Rules,
     #[command(description = "View your stats")]
     Stats,
+    #[command(description = "Rank this group's players: /leaderboard [points|wins]")]
+    Leaderboard(String),
+    #[command(description = "Show words similar to a word: /similar <word> [n]")]
+    Similar(String),
+    #[command(description = "Look up a word in the dictionary: /define <word>")]
+    Define(String),
+    #[command(description = "View your badges")]
+    Badges,
+    #[command(
+        description = "Choose how the bot picks Word Chain words: /botstyle [similar|dissimilar|random]"
+    )]
+    BotStyle(String),
+    #[command(description = "Choose the bot's tone: /tone [neutral|playful|competitive]")]
+    Tone(String),
+    #[command(description = "Choose how strong my words are: /difficulty [easy|medium|hard]")]
+    Difficulty(String),
+    #[command(description = "Change the Word Ladder goal length: /maxlen <letters>")]
+    MaxLen(String),
+    #[command(description = "Show the definition of a word from this game: /def <word>")]
+    Def(String),
+    #[command(description = "Take back your last turns and try another path: /rewind [turns]")]
+    Rewind(String),
+    #[command(description = "Toggle compact mode: one Word Chain message updated every turn")]
+    Compact,
+    #[command(description = "Toggle a 📖 Define button in place of my words' definitions")]
+    DefineButton,
+    #[command(description = "Toggle example sentences beneath definitions")]
+    Examples,
+    #[command(description = "Show the letters with the fewest words to continue from")]
+    DeadEnds,
+    #[command(description = "Get a word of the day in this chat")]
+    Subscribe,
+    #[command(description = "Stop getting the word of the day")]
+    Unsubscribe,
+    #[command(description = "Join the players taking turns in this group")]
+    Join,
+    #[command(description = "Stop taking turns in this group")]
+    Leave,
+    #[command(
+        description = "Admin: load an embeddings file for comparison: /loadmodel <name> <file>"
+    )]
+    LoadModel(String),
+    #[command(
+        description = "Admin: compare words with another model in this chat: /model [name|default]"
+    )]
+    Model(String),
+    #[command(description = "See how I'd answer a word in a game: /preview <game> <word>")]
+    Preview(String),
     #[command(description = "Stop the current game")]
     Stop,
 }
@@ -0,0 +1,153 @@
+use crate::dictionary::get_daily_word;
+use crate::persist;
+use crate::settings::get_settings;
+use crate::stats::today;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::{ApiError, Bot, RequestError};
+
+const SUBSCRIBERS_PATH: &str = "subscribers.bin";
+static SUBSCRIBERS: OnceLock<Mutex<HashSet<i64>>> = OnceLock::new();
+
+/// Initializes the word of the day subscriber list from disk if available
+pub fn init_subscribers() {
+    let entries: Vec<i64> = persist::load(SUBSCRIBERS_PATH).unwrap_or_default();
+    log::info!("Loaded {} word of the day subscribers", entries.len());
+    let _ = SUBSCRIBERS.set(Mutex::new(entries.into_iter().collect()));
+}
+
+/// Saves the subscriber list to disk
+pub fn save_subscribers() -> std::io::Result<()> {
+    let entries: Vec<i64> = get_subscribers().lock().unwrap().iter().copied().collect();
+    persist::save(&entries, SUBSCRIBERS_PATH)
+}
+
+fn get_subscribers() -> &'static Mutex<HashSet<i64>> {
+    SUBSCRIBERS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Saves the subscriber list right after it changes. It's tiny, and waiting for shutdown would
+/// lose every change since startup if the bot crashed.
+fn subscribers_changed() {
+    if let Err(e) = save_subscribers() {
+        log::error!("Failed to save word of the day subscribers: {}", e);
+    }
+}
+
+/// Handle /subscribe: send the chat a word of the day
+pub async fn handle_subscribe_command(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let added = get_subscribers().lock().unwrap().insert(chat_id.0);
+    let reply = if added {
+        log::info!(chat_id = chat_id.0; "Chat subscribed to the word of the day");
+        subscribers_changed();
+        format!(
+            "Subscribed! You'll get a word of the day at {:02}:00 UTC. Use /unsubscribe to stop.",
+            get_settings().word_of_the_day_hour
+        )
+    } else {
+        "You're already subscribed to the word of the day.".to_string()
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Handle /unsubscribe: stop sending the chat a word of the day
+pub async fn handle_unsubscribe_command(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let removed = get_subscribers().lock().unwrap().remove(&chat_id.0);
+    let reply = if removed {
+        log::info!(chat_id = chat_id.0; "Chat unsubscribed from the word of the day");
+        subscribers_changed();
+        "Unsubscribed. No more words of the day."
+    } else {
+        "You're not subscribed to the word of the day. Use /subscribe to start."
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Time left until the next broadcast at `hour`:00 UTC
+fn until_next_broadcast(hour: u64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let target = hour * 3600;
+    let elapsed = now % 86_400;
+    let wait = if elapsed < target {
+        target - elapsed
+    } else {
+        86_400 - elapsed + target
+    };
+    Duration::from_secs(wait)
+}
+
+/// Whether a send failed because the chat can never be reached again
+fn is_unreachable(error: &RequestError) -> bool {
+    matches!(
+        error,
+        RequestError::Api(
+            ApiError::BotBlocked
+                | ApiError::BotKicked
+                | ApiError::BotKickedFromSupergroup
+                | ApiError::ChatNotFound
+                | ApiError::UserDeactivated
+        )
+    )
+}
+
+/// Sends today's word to every subscriber, pruning chats the bot can no longer reach
+async fn broadcast(bot: &Bot) {
+    let subscribers: Vec<i64> = get_subscribers().lock().unwrap().iter().copied().collect();
+    if subscribers.is_empty() {
+        return;
+    }
+
+    let word = match get_daily_word(today()).await {
+        Ok(word) => word,
+        Err(e) => {
+            log::error!("Failed to pick the word of the day: {}", e);
+            return;
+        }
+    };
+    log::info!(
+        "Sending word of the day '{}' to {} chats",
+        word.word,
+        subscribers.len()
+    );
+
+    let mut pruned = false;
+    for chat_id in subscribers.into_iter().map(ChatId) {
+        let sent = match bot
+            .send_message(chat_id, format!("📅 Word of the day: {}", word.word))
+            .await
+        {
+            Ok(_) => word.send_message(bot, chat_id, 0).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = sent {
+            if is_unreachable(&e) {
+                log::info!(chat_id = chat_id.0; "Removing unreachable subscriber: {}", e);
+                get_subscribers().lock().unwrap().remove(&chat_id.0);
+                pruned = true;
+            } else {
+                log::warn!(chat_id = chat_id.0; "Failed to send word of the day: {}", e);
+            }
+        }
+    }
+    if pruned {
+        subscribers_changed();
+    }
+}
+
+/// Sends the word of the day to subscribers every day at the configured hour
+pub async fn run_word_of_the_day(bot: Bot) {
+    loop {
+        tokio::time::sleep(until_next_broadcast(get_settings().word_of_the_day_hour)).await;
+        broadcast(&bot).await;
+    }
+}
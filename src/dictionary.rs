@@ -1,14 +1,24 @@
-use crate::embeddings::{get_embeddings, is_valid_word};
+use crate::embeddings::{get_embeddings, is_valid_word, words_ending_with, words_of_length};
+use crate::persist::{self, unix_now};
+use crate::preferences::shows_examples;
+use crate::providers::{
+    DictionaryProvider, FreeDictionaryProvider, Lookup, MerriamWebsterProvider, ProviderKind,
+};
+use crate::settings::get_settings;
+use crate::spelling::american_spellings;
 use bincode::{Decode, Encode};
-use merriam_webster_http::MerriamWebsterClient;
+use itertools::Itertools;
 use moka::future::Cache;
-use rand::prelude::IteratorRandom;
+use moka::Expiry;
+use rand::prelude::{IteratorRandom, SliceRandom};
 use rand::rng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use teloxide::payloads::{
     EditMessageReplyMarkupSetters, EditMessageTextSetters, SendMessageSetters,
 };
@@ -16,6 +26,8 @@ use teloxide::prelude::{Requester, ResponseResult};
 use teloxide::types::ParseMode::MarkdownV2;
 use teloxide::types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup, MessageId};
 use teloxide::Bot;
+use teloxide::{ApiError, RequestError};
+use tokio::task::JoinSet;
 
 /// Custom error type for dictionary operations
 #[derive(Debug)]
@@ -24,6 +36,21 @@ pub enum DictionaryError {
     ApiError(String),
     CacheError(String),
     IoError(std::io::Error),
+    /// Live lookups are disabled and the word isn't cached
+    Unavailable(String),
+}
+
+impl DictionaryError {
+    /// Whether the lookup failed for a reason that may pass, such as the API being unreachable,
+    /// rather than because of the word itself
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            DictionaryError::ApiError(_)
+                | DictionaryError::CacheError(_)
+                | DictionaryError::IoError(_)
+        )
+    }
 }
 
 impl fmt::Display for DictionaryError {
@@ -33,6 +60,26 @@ impl fmt::Display for DictionaryError {
             DictionaryError::ApiError(msg) => write!(f, "API error: {}", msg),
             DictionaryError::CacheError(msg) => write!(f, "Cache error: {}", msg),
             DictionaryError::IoError(e) => write!(f, "I/O error: {}", e),
+            DictionaryError::Unavailable(word) => {
+                write!(f, "Live lookups unavailable and '{}' is not cached", word)
+            }
+        }
+    }
+}
+
+impl DictionaryError {
+    /// Message shown to the player when their word is rejected because of this error
+    pub fn rejection_message(&self, word: &str) -> String {
+        match self {
+            DictionaryError::Unavailable(_) => format!(
+                "I can't verify '{}': live dictionary lookups are unavailable, so only words I already know can be played.",
+                word
+            ),
+            e if e.is_transient() => format!(
+                "I couldn't reach the dictionary to check '{}'. Please try again in a moment.",
+                word
+            ),
+            _ => format!("I don't recognize '{}'. Please try another word.", word),
         }
     }
 }
@@ -44,13 +91,18 @@ impl From<std::io::Error> for DictionaryError {
 }
 
 /// Word information including definitions and stems
-#[derive(Encode, Decode, Clone, Debug)]
+#[derive(Encode, Decode, Serialize, Deserialize, Clone, Debug)]
 pub struct WordInfo {
     pub word: String,
     pub stems: Vec<String>,
     pub defs: Vec<Def>,
+    /// Address of an MP3 recording of the word's pronunciation, when the dictionary has one
+    #[serde(default)]
+    pub audio: Option<String>,
 }
 
+/// Longest message Telegram accepts, in characters
+const MAX_MESSAGE_LEN: usize = 4096;
 /// Escapes special characters for Markdown formatting
 fn escape(text: &str) -> String {
     let special_chars = "_*[]()~`>#+-=|{}.!"; // Characters to escape
@@ -66,14 +118,73 @@ fn escape(text: &str) -> String {
 }
 
 impl WordInfo {
-    /// Prepares a formatted message with keyboard for display
-    pub fn get_message(&self, def_idx: usize) -> (String, InlineKeyboardMarkup) {
+    /// Prepares a MarkdownV2-formatted message with keyboard for display
+    ///
+    /// `def_idx` is clamped to the available definitions, since a callback may carry an
+    /// index from an older version of the cached entry. Example sentences are added beneath
+    /// the definitions when `show_examples` is set, as many as fit in one message.
+    pub fn get_message(
+        &self,
+        def_idx: usize,
+        show_examples: bool,
+    ) -> (String, InlineKeyboardMarkup) {
+        self.render(def_idx, show_examples, true)
+    }
+
+    /// Renders the message for `get_message`, as MarkdownV2 or as plain text
+    fn render(
+        &self,
+        def_idx: usize,
+        show_examples: bool,
+        markdown: bool,
+    ) -> (String, InlineKeyboardMarkup) {
+        let text = |raw: &str| {
+            if markdown {
+                escape(raw)
+            } else {
+                raw.to_string()
+            }
+        };
+        let styled = |raw: &str, style: &str| {
+            if markdown {
+                format!(
+                    "{}{}{}",
+                    style,
+                    escape(raw),
+                    style.chars().rev().collect::<String>()
+                )
+            } else {
+                raw.to_string()
+            }
+        };
+
+        // Only the first senses are paged through when the number is capped
+        let navigable = get_settings()
+            .max_senses
+            .map_or(self.defs.len(), |max| max.min(self.defs.len()));
+
+        // Words accepted without a definition get just the headword and no navigation
+        let Some(last_idx) = navigable.checked_sub(1) else {
+            let message = format!("{} {}", text(&self.word), text("(no definition available)"));
+            return (message, InlineKeyboardMarkup::default());
+        };
+        let def_idx = def_idx.min(last_idx);
         let def = &self.defs[def_idx];
-        let message = format!(
-            "{} *__{}__*\n{}",
-            escape(&self.word),
-            escape(&def.functional_label),
-            escape(
+        // An empty label would leave an empty bold underline, "*____*", which Telegram rejects
+        // as an unclosed entity. Escaping keeps "-", "." and "_" in labels from ending the
+        // styling early.
+        let label = if def.functional_label.is_empty() {
+            String::new()
+        } else if markdown {
+            format!(" {}", styled(&def.functional_label, "*__"))
+        } else {
+            format!(" ({})", def.functional_label)
+        };
+        let mut message = format!(
+            "{}{}\n{}",
+            text(&self.word),
+            label,
+            text(
                 &def.definitions
                     .iter()
                     .enumerate()
@@ -82,26 +193,77 @@ impl WordInfo {
                     .join("\n")
             )
         );
+        if navigable < self.defs.len() {
+            message.push_str(&text(&format!(
+                "\n\n(showing {} of {} senses)",
+                navigable,
+                self.defs.len()
+            )));
+        }
+        if show_examples && !def.examples.is_empty() {
+            let mut examples = format!("\n\n{}", styled("Examples:", "_"));
+            for example in &def.examples {
+                let line = format!("\n• {}", styled(example, "_"));
+                if message.chars().count() + examples.chars().count() + line.chars().count()
+                    > MAX_MESSAGE_LEN
+                {
+                    break;
+                }
+                examples.push_str(&line);
+            }
+            if examples.contains('•') {
+                message.push_str(&examples);
+            }
+        }
 
-        let buttons: Vec<_> = vec![("prev", def_idx.wrapping_sub(1)), ("next", def_idx + 1)]
+        // Paging wraps around, so both buttons show whenever there's more than one sense
+        let buttons: Vec<_> = if navigable > 1 {
+            vec![
+                ("prev", (def_idx + navigable - 1) % navigable),
+                ("next", (def_idx + 1) % navigable),
+            ]
             .into_iter()
-            .filter_map(|(txt, idx)| {
-                if idx < self.defs.len() {
-                    Some(InlineKeyboardButton::callback(
-                        txt,
-                        format!("def_{}_{}", self.word, idx.to_string()),
-                    ))
-                } else {
-                    None
-                }
+            .map(|(txt, idx)| {
+                InlineKeyboardButton::callback(txt, format!("def_{}_{}", self.word, idx))
             })
-            .collect();
+            .collect()
+        } else {
+            Vec::new()
+        };
 
-        let keyboard = InlineKeyboardMarkup::new(vec![buttons]);
+        let mut rows = vec![buttons];
+        if self.audio.is_some() {
+            rows.push(vec![InlineKeyboardButton::callback(
+                "🔊 Pronounce",
+                format!("pron_{}", self.word),
+            )]);
+        }
+        let keyboard = InlineKeyboardMarkup::new(rows);
 
         (message, keyboard)
     }
 
+    /// Whether one of the word's senses is the given part of speech, e.g. "verb" also matches
+    /// "transitive verb"
+    pub fn has_pos(&self, pos: &str) -> bool {
+        self.defs.iter().any(|def| {
+            def.functional_label
+                .split_whitespace()
+                .any(|label| label.eq_ignore_ascii_case(pos))
+        })
+    }
+
+    /// One-line plain-text summary: the word with its first definition
+    pub fn summary(&self) -> String {
+        match self.defs.first() {
+            Some(def) => match def.definitions.first() {
+                Some(text) => format!("{} ({}): {}", self.word, def.functional_label, text),
+                None => format!("{} ({})", self.word, def.functional_label),
+            },
+            None => self.word.clone(),
+        }
+    }
+
     /// Sends a new message with word information
     pub async fn send_message(
         &self,
@@ -109,11 +271,28 @@ impl WordInfo {
         chat_id: ChatId,
         def_idx: usize,
     ) -> ResponseResult<()> {
-        let (message, keyboard) = self.get_message(def_idx);
-        bot.send_message(chat_id, message)
+        let show_examples = shows_examples(chat_id);
+        let (message, keyboard) = self.get_message(def_idx, show_examples);
+        let sent = bot
+            .send_message(chat_id, message)
             .reply_markup(keyboard)
             .parse_mode(MarkdownV2)
-            .await?;
+            .await;
+
+        // A formatting slip shouldn't stall the game, so fall back to plain text
+        if let Err(RequestError::Api(ApiError::CantParseEntities(e))) = &sent {
+            log::warn!(
+                "MarkdownV2 rejected for '{}', sending it as plain text: {}",
+                self.word,
+                e
+            );
+            let (message, keyboard) = self.render(def_idx, show_examples, false);
+            bot.send_message(chat_id, message)
+                .reply_markup(keyboard)
+                .await?;
+            return Ok(());
+        }
+        sent?;
 
         Ok(())
     }
@@ -126,10 +305,23 @@ impl WordInfo {
         message_id: MessageId,
         def_idx: usize,
     ) -> ResponseResult<()> {
-        let (message, keyboard) = self.get_message(def_idx);
-        bot.edit_message_text(chat_id, message_id, message)
+        let show_examples = shows_examples(chat_id);
+        let (message, keyboard) = self.get_message(def_idx, show_examples);
+        let edited = bot
+            .edit_message_text(chat_id, message_id, message)
             .parse_mode(MarkdownV2)
-            .await?;
+            .await;
+        if let Err(RequestError::Api(ApiError::CantParseEntities(e))) = &edited {
+            log::warn!(
+                "MarkdownV2 rejected for '{}', showing it as plain text: {}",
+                self.word,
+                e
+            );
+            let (message, _) = self.render(def_idx, show_examples, false);
+            bot.edit_message_text(chat_id, message_id, message).await?;
+        } else {
+            edited?;
+        }
         bot.edit_message_reply_markup(chat_id, message_id)
             .reply_markup(keyboard)
             .await?;
@@ -138,41 +330,312 @@ impl WordInfo {
 }
 
 /// Word definition containing the functional label and definitions
-#[derive(Encode, Decode, Clone, Debug)]
+#[derive(Encode, Decode, Serialize, Deserialize, Clone, Debug)]
 pub struct Def {
     pub definitions: Vec<String>,
     pub functional_label: String,
+    /// Usage examples from the dictionary entry, with its markup removed
+    pub examples: Vec<String>,
 }
 
 const CACHE_SIZE: u64 = 100_000;
-const CACHE_PATH: &str = "cache.bin";
-static CACHE: OnceLock<Cache<String, WordInfo>> = OnceLock::new();
-static CLIENT: OnceLock<MerriamWebsterClient> = OnceLock::new();
+/// Where the word cache is kept between runs
+pub const CACHE_PATH: &str = "cache.bin";
+static CACHE: OnceLock<Cache<String, CachedWord>> = OnceLock::new();
+static PROVIDER: OnceLock<Option<Box<dyn DictionaryProvider>>> = OnceLock::new();
+/// Bundled word details for offline play, keyed by looked-up word
+static OFFLINE_DICTIONARY: OnceLock<HashMap<String, WordInfo>> = OnceLock::new();
+
+const NOT_FOUND_SIZE: u64 = 10_000;
+/// How long a word the API had no usable entry for is turned away without asking again. Kept
+/// short so words the dictionary adds later become playable soon after.
+const NOT_FOUND_TTL: Duration = Duration::from_secs(60 * 60);
+/// Recent lookups the API had no usable entry for, with the reason they failed. Only kept in
+/// memory, so a restart asks again.
+static NOT_FOUND: OnceLock<Cache<String, String>> = OnceLock::new();
+
+fn get_not_found() -> &'static Cache<String, String> {
+    NOT_FOUND.get_or_init(|| {
+        Cache::builder()
+            .max_capacity(NOT_FOUND_SIZE)
+            .time_to_live(NOT_FOUND_TTL)
+            .build()
+    })
+}
+
+/// Word details held in the cache, with the time they were fetched
+#[derive(Clone)]
+pub struct CachedWord {
+    info: WordInfo,
+    /// Unix time in seconds
+    fetched_at: u64,
+}
+
+impl CachedWord {
+    fn new(info: WordInfo) -> Self {
+        CachedWord {
+            info,
+            fetched_at: unix_now(),
+        }
+    }
+
+    /// How much longer the entry may stay cached under `ttl`
+    fn remaining(&self, ttl: Duration) -> Duration {
+        ttl.saturating_sub(Duration::from_secs(
+            unix_now().saturating_sub(self.fetched_at),
+        ))
+    }
+}
+
+/// Expires cache entries `ttl` after their details were fetched, counting time spent on disk
+struct FetchedAtExpiry {
+    ttl: Duration,
+}
+
+impl Expiry<String, CachedWord> for FetchedAtExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &CachedWord,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(value.remaining(self.ttl))
+    }
+
+    // A refetched word replaces its stale entry, which would otherwise pass on its expiry
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &CachedWord,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.remaining(self.ttl))
+    }
+}
+
+/// How long cached details are kept before they're fetched again. Entries never expire without
+/// live lookups, since they couldn't be fetched again.
+fn cache_ttl() -> Option<Duration> {
+    get_settings()
+        .cache_ttl
+        .filter(|_| live_lookups_available())
+}
 
 /// Cache entry for serialization/deserialization
 #[derive(Encode, Decode)]
 struct CacheEntry {
     key: String,
     value: WordInfo,
+    /// Unix time in seconds the details were fetched
+    fetched_at: u64,
+}
+
+/// `WordInfo` as written before words carried their pronunciation
+#[derive(Decode)]
+struct SilentWordInfo {
+    word: String,
+    stems: Vec<String>,
+    defs: Vec<Def>,
+}
+
+impl From<SilentWordInfo> for WordInfo {
+    fn from(info: SilentWordInfo) -> Self {
+        WordInfo {
+            word: info.word,
+            stems: info.stems,
+            defs: info.defs,
+            audio: None,
+        }
+    }
+}
+
+/// Cache entry as written before words carried their pronunciation
+#[derive(Decode)]
+struct SilentCacheEntry {
+    key: String,
+    value: SilentWordInfo,
+    fetched_at: u64,
+}
+
+impl From<SilentCacheEntry> for CacheEntry {
+    fn from(entry: SilentCacheEntry) -> Self {
+        CacheEntry {
+            key: entry.key,
+            value: entry.value.into(),
+            fetched_at: entry.fetched_at,
+        }
+    }
+}
+
+/// Cache entry as written before entries carried their fetch time
+#[derive(Decode)]
+struct UntimedCacheEntry {
+    key: String,
+    value: SilentWordInfo,
+}
+
+impl From<UntimedCacheEntry> for CacheEntry {
+    fn from(entry: UntimedCacheEntry) -> Self {
+        CacheEntry {
+            key: entry.key,
+            value: entry.value.into(),
+            fetched_at: unix_now(),
+        }
+    }
+}
+
+/// Cache entry as written before definitions carried examples. Bincode encodes nested
+/// structs field by field, so the old `WordInfo` and `Def` can be read back flattened.
+#[derive(Decode)]
+struct LegacyCacheEntry {
+    key: String,
+    word: String,
+    stems: Vec<String>,
+    defs: Vec<(Vec<String>, String)>,
+}
+
+impl From<LegacyCacheEntry> for CacheEntry {
+    fn from(entry: LegacyCacheEntry) -> Self {
+        CacheEntry {
+            key: entry.key,
+            fetched_at: unix_now(),
+            value: WordInfo {
+                word: entry.word,
+                stems: entry.stems,
+                defs: entry
+                    .defs
+                    .into_iter()
+                    .map(|(definitions, functional_label)| Def {
+                        definitions,
+                        functional_label,
+                        examples: Vec::new(),
+                    })
+                    .collect(),
+                audio: None,
+            },
+        }
+    }
+}
+
+/// Marks a cache or offline dictionary file that starts with its format version
+const FORMAT_MAGIC: &[u8; 4] = b"KTSM";
+
+/// Version of the format cache and offline dictionary files are written in, following
+/// `FORMAT_MAGIC`. Bump it, and add a case to `read_entries`, whenever `CacheEntry` or anything
+/// it holds changes shape.
+const FORMAT_VERSION: u32 = 1;
+
+/// Writes cache entries to a file, headed by the format version
+fn write_entries(file_path: &str, entries: &[CacheEntry]) -> Result<(), DictionaryError> {
+    let mut bytes = FORMAT_MAGIC.to_vec();
+    let body = bincode::encode_to_vec((FORMAT_VERSION, entries), bincode::config::standard())
+        .map_err(|e| DictionaryError::CacheError(format!("Failed to encode entries: {}", e)))?;
+    bytes.extend(body);
+    persist::write_atomically(file_path, &bytes)?;
+    Ok(())
+}
+
+/// Reads a cache or offline dictionary file, in the format its version header names. Files
+/// written before the header was introduced are upgraded from whichever earlier format they're
+/// in; they get a header the next time they're saved.
+fn read_entries(file_path: &str) -> Result<Vec<CacheEntry>, DictionaryError> {
+    let bytes = std::fs::read(file_path)?;
+
+    let Some(versioned) = bytes.strip_prefix(FORMAT_MAGIC) else {
+        return read_unversioned(file_path, &bytes);
+    };
+    let (version, read) =
+        bincode::decode_from_slice::<u32, _>(versioned, bincode::config::standard())
+            .map_err(|e| DictionaryError::CacheError(format!("Unreadable version: {}", e)))?;
+    let body = &versioned[read..];
+
+    match version {
+        FORMAT_VERSION => decode_all::<CacheEntry>(body).map_err(DictionaryError::CacheError),
+        version => Err(DictionaryError::CacheError(format!(
+            "Unknown format version {} (this build reads up to {})",
+            version, FORMAT_VERSION
+        ))),
+    }
+}
+
+/// Reads a file written before files carried a version header: the current entries, or entries
+/// from before words carried their pronunciation, entries carried their fetch time or
+/// definitions carried examples. Entries without fetch times count as fetched when they're read.
+fn read_unversioned(file_path: &str, bytes: &[u8]) -> Result<Vec<CacheEntry>, DictionaryError> {
+    let error = match decode_all::<CacheEntry>(bytes) {
+        Ok(entries) => return Ok(entries),
+        Err(e) => e,
+    };
+    if let Ok(entries) = decode_all::<SilentCacheEntry>(bytes) {
+        log::info!(
+            "Upgrading {} from the format without pronunciations",
+            file_path
+        );
+        return Ok(entries.into_iter().map(CacheEntry::from).collect());
+    }
+    if let Ok(entries) = decode_all::<UntimedCacheEntry>(bytes) {
+        log::info!(
+            "Upgrading {} from the format without fetch times",
+            file_path
+        );
+        return Ok(entries.into_iter().map(CacheEntry::from).collect());
+    }
+    if let Ok(entries) = decode_all::<LegacyCacheEntry>(bytes) {
+        log::info!("Upgrading {} from the format without examples", file_path);
+        return Ok(entries.into_iter().map(CacheEntry::from).collect());
+    }
+    Err(DictionaryError::CacheError(error))
+}
+
+/// Decodes a whole file's worth of entries in one format, failing on leftover bytes
+fn decode_all<T: Decode<()>>(bytes: &[u8]) -> Result<Vec<T>, String> {
+    match bincode::decode_from_slice::<Vec<T>, _>(bytes, bincode::config::standard()) {
+        Ok((entries, read)) if read == bytes.len() => Ok(entries),
+        Ok(_) => Err("trailing data".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 /// Initializes the word cache from disk if available
 pub async fn init_cache() {
-    let cache: Cache<String, WordInfo> = Cache::new(CACHE_SIZE);
-
-    if let Ok(file) = File::open(CACHE_PATH) {
-        let reader = BufReader::new(file);
-        let entries_result: Result<Vec<CacheEntry>, _> =
-            bincode::decode_from_reader(reader, bincode::config::standard());
+    let ttl = cache_ttl();
+    let mut builder = Cache::builder().max_capacity(CACHE_SIZE);
+    if let Some(ttl) = ttl {
+        builder = builder.expire_after(FetchedAtExpiry { ttl });
+    }
+    let cache: Cache<String, CachedWord> = builder.build();
 
-        match entries_result {
+    if std::path::Path::new(CACHE_PATH).exists() {
+        match read_entries(CACHE_PATH) {
             Ok(entries) => {
-                log::info!("Loaded {} entries from cache", entries.len());
+                let total = entries.len();
+                let mut expired = 0;
                 for entry in entries {
-                    cache.insert(entry.key, entry.value).await;
+                    let cached = CachedWord {
+                        info: entry.value,
+                        fetched_at: entry.fetched_at,
+                    };
+                    // Expired entries are dropped rather than served until the next lookup
+                    if ttl.is_some_and(|ttl| cached.remaining(ttl).is_zero()) {
+                        expired += 1;
+                        continue;
+                    }
+                    cache.insert(entry.key, cached).await;
+                }
+                log::info!(
+                    "Loaded {} entries from cache, dropped {} expired",
+                    total - expired,
+                    expired
+                );
+            }
+            Err(e) => {
+                log::error!("Failed to load cache: {}", e);
+                // A file that doesn't decode would fail again on every start
+                if matches!(e, DictionaryError::CacheError(_)) {
+                    persist::quarantine(CACHE_PATH);
                 }
             }
-            Err(e) => log::error!("Failed to load cache: {}", e),
         }
     } else {
         log::info!("No cache file found, starting with empty cache");
@@ -181,23 +644,116 @@ pub async fn init_cache() {
     let _ = CACHE.set(cache);
 }
 
+/// How long the test cache keeps entries, as if a cache TTL were configured
+#[cfg(test)]
+const TEST_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Starts with an empty cache, leaving the cache file alone. Entries expire a day after their
+/// details were fetched.
+#[cfg(test)]
+pub fn init_empty_cache() {
+    let _ = CACHE.set(
+        Cache::builder()
+            .max_capacity(CACHE_SIZE)
+            .expire_after(FetchedAtExpiry {
+                ttl: TEST_CACHE_TTL,
+            })
+            .build(),
+    );
+}
+
+/// Loads the offline dictionary from OFFLINE_DICT_PATH, if configured.
+///
+/// The file uses the same format as the cache file: a version header, then a bincode list of
+/// looked-up words and their details. Build one with `kotosume_bot build-offline-dict <word list> <output>`.
+pub fn init_offline_dictionary() {
+    let entries: Vec<CacheEntry> = match &get_settings().offline_dict_path {
+        Some(path) => match read_entries(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::error!("Failed to load offline dictionary from {}: {}", path, e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    if !entries.is_empty() {
+        log::info!("Loaded {} words from the offline dictionary", entries.len());
+    }
+    let _ = OFFLINE_DICTIONARY.set(entries.into_iter().map(|e| (e.key, e.value)).collect());
+}
+
+fn get_offline_dictionary() -> &'static HashMap<String, WordInfo> {
+    OFFLINE_DICTIONARY.get_or_init(HashMap::new)
+}
+
 /// Gets a reference to the global word cache
-pub fn get_cache() -> &'static Cache<String, WordInfo> {
+pub fn get_cache() -> &'static Cache<String, CachedWord> {
     CACHE
         .get()
         .expect("Cache not initialized. Call init_cache() first")
 }
 
-/// Initializes the Merriam-Webster API client
-fn init_client() -> MerriamWebsterClient {
-    let api_key = env::var("MERRIAM_WEBSTER_API_KEY")
-        .expect("MERRIAM_WEBSTER_API_KEY environment variable not set");
-    MerriamWebsterClient::new(api_key.into())
+/// Initializes the configured dictionary provider, or `None` in cache-only mode or when
+/// Merriam-Webster is chosen without an API key
+fn init_provider() -> Option<Box<dyn DictionaryProvider>> {
+    let settings = get_settings();
+    if settings.cache_only {
+        return None;
+    }
+    let provider: Box<dyn DictionaryProvider> = match settings.dictionary_provider {
+        ProviderKind::MerriamWebster => {
+            let api_key = env::var("MERRIAM_WEBSTER_API_KEY").ok()?;
+            Box::new(MerriamWebsterProvider::new(api_key))
+        }
+        ProviderKind::FreeDictionary => Box::new(FreeDictionaryProvider::new()),
+    };
+    log::info!("Looking up words with {}", provider.name());
+    Some(provider)
 }
 
-/// Gets a reference to the global Merriam-Webster API client
-fn get_client() -> &'static MerriamWebsterClient {
-    CLIENT.get_or_init(|| init_client())
+/// Looks words up with `provider` instead of the configured one. Only takes effect before the
+/// first lookup.
+#[cfg(test)]
+pub fn use_provider(provider: Box<dyn DictionaryProvider>) {
+    let _ = PROVIDER.set(Some(provider));
+}
+
+/// Gets a reference to the global dictionary provider, if live lookups are enabled
+fn get_provider() -> Option<&'static dyn DictionaryProvider> {
+    PROVIDER.get_or_init(init_provider).as_deref()
+}
+
+/// Whether words can be looked up live, rather than only served from the cache
+pub fn live_lookups_available() -> bool {
+    get_provider().is_some()
+}
+
+/// Whether a word can currently be played: always with live lookups, otherwise only if cached
+/// or in the offline dictionary
+pub fn is_playable(word: &str) -> bool {
+    live_lookups_available()
+        || get_cache().contains_key(word)
+        || get_offline_dictionary().contains_key(word)
+}
+
+/// Details of a word known without asking the dictionary: from the cache, then the offline
+/// dictionary. Never makes a request, so it's cheap enough to call for many words at once.
+pub async fn get_known_word_details(word: &str) -> Option<WordInfo> {
+    if let Some(cached) = get_cache().get(word).await {
+        return Some(cached.info);
+    }
+    get_offline_dictionary().get(word).cloned()
+}
+
+/// The given starting character, or a random letter
+fn start_letter(start_char: Option<char>) -> Result<char, DictionaryError> {
+    match start_char {
+        Some(c) => Ok(c),
+        None => ('a'..='z').choose(&mut rand::rng()).ok_or_else(|| {
+            DictionaryError::ApiError("Failed to generate random character".to_string())
+        }),
+    }
 }
 
 /// Gets a random word that satisfies the given predicate, optionally starting with a specific character
@@ -208,12 +764,7 @@ pub async fn get_random_word<P>(
 where
     P: Fn(&str) -> bool,
 {
-    let char = match start_char {
-        Some(c) => c,
-        None => ('a'..='z').choose(&mut rand::rng()).ok_or_else(|| {
-            DictionaryError::ApiError("Failed to generate random character".to_string())
-        })?,
-    };
+    let char = start_letter(start_char)?;
 
     let embeddings = get_embeddings()
         .map_err(|e| DictionaryError::ApiError(format!("Failed to get embeddings: {}", e)))?;
@@ -224,20 +775,198 @@ where
 
     let word = char_map
         .keys()
-        .filter(|k| predicate(k))
+        .filter(|k| predicate(k) && is_playable(k))
         .choose(&mut rng())
         .ok_or_else(|| DictionaryError::NotFound("No matching word found".to_string()))?;
 
     get_word_details(word).await
 }
 
-/// Gets detailed information about a word
+/// Like `get_random_word`, for words of exactly `len` letters, taken from the embeddings' index
+/// by first letter and length
+pub async fn get_random_word_of_length<P>(
+    len: usize,
+    start_char: Option<char>,
+    predicate: P,
+) -> Result<WordInfo, DictionaryError>
+where
+    P: Fn(&str) -> bool,
+{
+    let char = start_letter(start_char)?;
+
+    let words = words_of_length(char, len)
+        .map_err(|e| DictionaryError::ApiError(format!("Failed to get embeddings: {}", e)))?;
+
+    let word = words
+        .iter()
+        .filter(|k| predicate(k) && is_playable(k))
+        .choose(&mut rng())
+        .ok_or_else(|| DictionaryError::NotFound("No matching word found".to_string()))?;
+
+    get_word_details(word).await
+}
+
+/// Candidates looked up live before giving up on finding a word with the wanted part of speech
+const POS_LOOKUPS: usize = 10;
+
+/// Like `get_random_word`, but the word must have a sense with the given part of speech, e.g.
+/// "noun". Spellings alone don't tell, so candidates are looked up: words already cached or in
+/// the offline dictionary are tried first, then at most `POS_LOOKUPS` live lookups, whose
+/// results are cached like any other.
+pub async fn get_random_word_with_pos<P>(
+    predicate: P,
+    start_char: Option<char>,
+    pos: &str,
+) -> Result<WordInfo, DictionaryError>
+where
+    P: Fn(&str) -> bool,
+{
+    let char = start_letter(start_char)?;
+
+    let embeddings = get_embeddings()
+        .map_err(|e| DictionaryError::ApiError(format!("Failed to get embeddings: {}", e)))?;
+
+    let char_map = embeddings
+        .get(&char)
+        .ok_or_else(|| DictionaryError::NotFound(format!("No embeddings for letter '{}'", char)))?;
+
+    let is_known =
+        |word: &str| get_cache().contains_key(word) || get_offline_dictionary().contains_key(word);
+    let mut candidates = char_map
+        .keys()
+        .filter(|k| predicate(k) && is_playable(k))
+        .collect::<Vec<_>>();
+    candidates.shuffle(&mut rng());
+    // A stable sort keeps the shuffled order within known and unknown words
+    candidates.sort_by_key(|word| !is_known(word));
+
+    let mut lookups = 0;
+    let mut last_error = None;
+    for word in candidates {
+        if !is_known(word) {
+            if lookups == POS_LOOKUPS {
+                break;
+            }
+            lookups += 1;
+        }
+        match get_word_details(word).await {
+            Ok(info) if info.has_pos(pos) => return Ok(info),
+            Ok(_) => {}
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    match last_error {
+        Some(e) if e.is_transient() => Err(e),
+        _ => Err(DictionaryError::NotFound(format!(
+            "No {} starting with '{}' found",
+            pos, char
+        ))),
+    }
+}
+
+/// Gets a random word that ends with `end_char` and satisfies the given predicate, for chains
+/// that link backwards
+pub async fn get_random_word_ending<P>(
+    predicate: P,
+    end_char: char,
+) -> Result<WordInfo, DictionaryError>
+where
+    P: Fn(&str) -> bool,
+{
+    let words = words_ending_with(end_char)
+        .map_err(|e| DictionaryError::ApiError(format!("Failed to get embeddings: {}", e)))?;
+
+    let word = words
+        .iter()
+        .filter(|k| predicate(k) && is_playable(k))
+        .choose(&mut rng())
+        .ok_or_else(|| {
+            DictionaryError::NotFound(format!("No matching word ending with '{}'", end_char))
+        })?;
+
+    get_word_details(word).await
+}
+
+/// Gets the word of the day for a day number: every chat gets the same word on the same day
+pub async fn get_daily_word(day: u64) -> Result<WordInfo, DictionaryError> {
+    /// Candidates tried after the day's pick when it has no definition
+    const MAX_ATTEMPTS: usize = 10;
+
+    let embeddings = get_embeddings()
+        .map_err(|e| DictionaryError::ApiError(format!("Failed to get embeddings: {}", e)))?;
+
+    let words = embeddings
+        .values()
+        .flat_map(|char_map| char_map.keys())
+        .filter(|w| is_playable(w))
+        .sorted()
+        .collect::<Vec<&String>>();
+    if words.is_empty() {
+        return Err(DictionaryError::NotFound("No playable words".to_string()));
+    }
+
+    // A large odd multiplier spreads consecutive days across the alphabet
+    let start = (day.wrapping_mul(2_654_435_761) % words.len() as u64) as usize;
+    let mut last_error = None;
+    for offset in 0..MAX_ATTEMPTS.min(words.len()) {
+        match get_word_details(words[(start + offset) % words.len()]).await {
+            Ok(info) => return Ok(info),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| DictionaryError::NotFound("No daily word".to_string())))
+}
+
+/// Gets detailed information about a word.
+///
+/// With BRITISH_SPELLINGS on, a word the dictionary doesn't know is tried again under its
+/// American spellings ("colour" as "color"), as long as the embeddings know the British one so
+/// the bot can still answer it. The details found are cached under the American form, but
+/// come back with the player's spelling as the word and among its stems.
 pub async fn get_word_details(word: &str) -> Result<WordInfo, DictionaryError> {
+    match lookup_word_details(word).await {
+        Err(DictionaryError::NotFound(reason))
+            if get_settings().british_spellings && is_valid_word(word) =>
+        {
+            for american in american_spellings(word) {
+                let Ok(mut info) = lookup_word_details(&american).await else {
+                    continue;
+                };
+                log::debug!(
+                    "Found '{}' under its American spelling '{}'",
+                    word,
+                    american
+                );
+                if info.word == american {
+                    info.word = word.to_string();
+                }
+                if !info.stems.iter().any(|stem| stem == word) {
+                    info.stems.push(word.to_string());
+                }
+                return Ok(info);
+            }
+            Err(DictionaryError::NotFound(reason))
+        }
+        result => result,
+    }
+}
+
+/// Looks a word up as spelled: in the cache, the offline dictionary, then the live dictionary
+async fn lookup_word_details(word: &str) -> Result<WordInfo, DictionaryError> {
     let cache = get_cache();
 
     // Check cache first for efficiency
     if let Some(cached_word) = cache.get(word).await {
-        return Ok(cached_word);
+        return Ok(cached_word.info);
+    }
+
+    // Then the offline dictionary, which needs no API calls
+    if let Some(offline_word) = get_offline_dictionary().get(word) {
+        cache
+            .insert(word.to_string(), CachedWord::new(offline_word.clone()))
+            .await;
+        return Ok(offline_word.clone());
     }
 
     // Validate word existence
@@ -248,70 +977,494 @@ pub async fn get_word_details(word: &str) -> Result<WordInfo, DictionaryError> {
         )));
     }
 
-    log::info!("Fetching details for word: {}", word);
+    // Words the API recently had nothing for are rejected without spending another request
+    if let Some(reason) = get_not_found().get(word).await {
+        log::debug!("'{}' recently not found, skipping the API", word);
+        return Err(DictionaryError::NotFound(reason));
+    }
 
-    // Call API for word details
-    let client = get_client();
-    let def = client
-        .collegiate_definition(word.into())
-        .await
-        .map_err(|_| DictionaryError::ApiError(format!("No definition found for '{}'", word)))?;
+    log::debug!("Fetching details for word: {}", word);
 
-    // Process definitions
-    let defs = def
-        .iter()
-        .filter_map(|d| {
-            let definitions = d.shortdef.as_ref()?;
-            Some(Def {
-                functional_label: d.fl.clone().unwrap_or_default(),
-                definitions: definitions.iter().map(|s| s.to_string()).collect(),
+    // Ask the dictionary for word details
+    let provider = get_provider().ok_or_else(|| DictionaryError::Unavailable(word.to_string()))?;
+    let lookup = match lookup_with_retries(provider, word).await {
+        Ok(lookup) => lookup,
+        Err(DictionaryError::NotFound(reason)) => return Err(not_found(word, reason).await),
+        Err(e) => return Err(e),
+    };
+
+    if lookup.defs.is_empty() {
+        return Err(not_found(word, format!("No usable definitions for '{}'", word)).await);
+    }
+
+    let word_info = recorded_word_info(word, lookup, get_settings().chain_on_headword);
+    cache
+        .insert(word.into(), CachedWord::new(word_info.clone()))
+        .await;
+
+    Ok(word_info)
+}
+
+/// What the dictionary found for `word`, recorded under the canonical headword instead of the
+/// submitted form when `chain_on_headword` is set. Games display and chain on the recorded
+/// form, so "running" recorded as "run" is shown as "run" and chains on 'n'.
+fn recorded_word_info(word: &str, lookup: Lookup, chain_on_headword: bool) -> WordInfo {
+    let recorded = match lookup.headword {
+        Some(headword) if chain_on_headword => headword,
+        _ => word.to_string(),
+    };
+    WordInfo {
+        word: recorded,
+        stems: lookup.stems,
+        defs: lookup.defs,
+        audio: lookup.audio,
+    }
+}
+
+/// Asks the dictionary about a word, retrying with exponential backoff and jitter when it can't
+/// be reached. Words the dictionary has no entry for come back at once, without a retry.
+async fn lookup_with_retries(
+    provider: &dyn DictionaryProvider,
+    word: &str,
+) -> Result<Lookup, DictionaryError> {
+    let settings = get_settings();
+    let mut delay = settings.lookup_retry_delay;
+    let mut attempt = 1;
+    loop {
+        match provider.lookup(word).await {
+            Err(e) if e.is_transient() && attempt < settings.lookup_attempts => {
+                // Up to half the delay again at random, so lookups that failed together don't
+                // all retry at once
+                let jitter = delay.mul_f64(rng().random_range(0.0..0.5));
+                log::warn!(
+                    "{} lookup of '{}' failed (attempt {}/{}), retrying in {:?}: {}",
+                    provider.name(),
+                    word,
+                    attempt,
+                    settings.lookup_attempts,
+                    delay + jitter,
+                    e
+                );
+                tokio::time::sleep(delay + jitter).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Remember that the dictionary had no usable entry for `word`, and the error to answer it with
+async fn not_found(word: &str, reason: String) -> DictionaryError {
+    get_not_found()
+        .insert(word.to_string(), reason.clone())
+        .await;
+    DictionaryError::NotFound(reason)
+}
+
+/// Gets the details of a player's word in `game`.
+///
+/// When the game's `require_definition` setting is off, a word known to the embeddings is
+/// accepted even if the dictionary has no definition for it (or can't be reached); it then
+/// comes back with no definitions and isn't cached, so a later lookup can still fill it in.
+pub async fn get_submission_details(word: &str, game: &str) -> Result<WordInfo, DictionaryError> {
+    match get_word_details(word).await {
+        Err(e) if !get_settings().require_definition(game) && is_valid_word(word) => {
+            log::debug!("Accepting '{}' without a definition: {}", word, e);
+            Ok(WordInfo {
+                word: word.to_string(),
+                stems: vec![word.to_string()],
+                defs: Vec::new(),
+                audio: None,
             })
-        })
-        .collect::<Vec<Def>>();
+        }
+        result => result,
+    }
+}
 
-    if defs.is_empty() {
-        return Err(DictionaryError::NotFound(format!(
-            "No usable definitions for '{}'",
-            word
-        )));
+/// Pre-fetches the details of every word in a newline-delimited list into the cache.
+///
+/// At most `concurrency` lookups run at once to stay within the API's rate limits; words
+/// already cached cost nothing. Progress is logged as it goes and the cache is saved at the end.
+pub async fn prefetch_words(file_path: &str, concurrency: usize) -> Result<(), DictionaryError> {
+    if !live_lookups_available() {
+        log::warn!(
+            "Live lookups are disabled, skipping pre-fetch of {}",
+            file_path
+        );
+        return Ok(());
     }
 
-    // Collect word stems
-    let stems = def.iter().flat_map(|d| d.meta.stems.clone()).collect();
+    let words = read_word_list(file_path).await?;
+    let total = words.len();
+    log::info!("Pre-fetching {} words from {}", total, file_path);
+
+    let cache = get_cache();
+    let (mut succeeded, mut failed, mut cached) = (0, 0, 0);
+    let mut lookups = JoinSet::new();
 
-    // Create and cache the word info
-    let word_info = WordInfo {
-        word: word.into(),
-        stems,
-        defs,
+    for (done, word) in words.into_iter().enumerate() {
+        if cache.contains_key(&word) {
+            cached += 1;
+            continue;
+        }
+
+        // Wait for a free slot before starting another lookup
+        while lookups.len() >= concurrency {
+            match lookups.join_next().await {
+                Some(Ok(Ok(_))) => succeeded += 1,
+                _ => failed += 1,
+            }
+        }
+        lookups.spawn(async move { get_word_details(&word).await });
+
+        if (done + 1) % 500 == 0 {
+            log::info!("Pre-fetch progress: {}/{} words", done + 1, total);
+        }
+    }
+
+    while let Some(result) = lookups.join_next().await {
+        match result {
+            Ok(Ok(_)) => succeeded += 1,
+            _ => failed += 1,
+        }
+    }
+
+    log::info!(
+        "Pre-fetch finished: {} fetched, {} failed, {} already cached",
+        succeeded,
+        failed,
+        cached
+    );
+    save_cache(cache, CACHE_PATH)
+}
+
+/// Reads a word list with one word per line, lowercased and without duplicates
+async fn read_word_list(file_path: &str) -> Result<Vec<String>, DictionaryError> {
+    let content = tokio::fs::read_to_string(file_path).await?;
+    Ok(content
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .unique()
+        .collect())
+}
+
+/// Builds an offline dictionary file from a word list, looking each word up through the cache
+/// and the API with at most `concurrency` lookups at once. Words without details are left out.
+pub async fn build_offline_dictionary(
+    words_path: &str,
+    output_path: &str,
+    concurrency: usize,
+) -> Result<(), DictionaryError> {
+    let words = read_word_list(words_path).await?;
+    let total = words.len();
+    log::info!("Building offline dictionary from {} words", total);
+
+    let mut entries = Vec::new();
+    let mut lookups = JoinSet::new();
+    let mut finished = |result: Result<(String, Result<WordInfo, DictionaryError>), _>| match result
+    {
+        Ok((key, Ok(value))) => entries.push(CacheEntry {
+            key,
+            value,
+            fetched_at: unix_now(),
+        }),
+        Ok((key, Err(e))) => log::warn!("Leaving out '{}': {}", key, e),
+        Err(e) => log::error!("Lookup task failed: {}", e),
     };
 
-    cache.insert(word.into(), word_info.clone()).await;
+    for (done, word) in words.into_iter().enumerate() {
+        while lookups.len() >= concurrency {
+            if let Some(result) = lookups.join_next().await {
+                finished(result);
+            }
+        }
+        lookups.spawn(async move {
+            let details = get_word_details(&word).await;
+            (word, details)
+        });
 
-    Ok(word_info)
+        if (done + 1) % 500 == 0 {
+            log::info!("Offline dictionary progress: {}/{} words", done + 1, total);
+        }
+    }
+    while let Some(result) = lookups.join_next().await {
+        finished(result);
+    }
+
+    log::info!(
+        "Saving {} of {} words to {}",
+        entries.len(),
+        total,
+        output_path
+    );
+    write_entries(output_path, &entries)?;
+    save_cache(get_cache(), CACHE_PATH)
 }
 
+/// Held while the cache is being saved, so a periodic save and the shutdown save never write
+/// the temporary file at the same time
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
 /// Saves the word cache to disk
 pub fn save_cache(
-    cache: &'static Cache<String, WordInfo>,
+    cache: &'static Cache<String, CachedWord>,
     file_path: &str,
 ) -> Result<(), DictionaryError> {
+    let _saving = SAVE_LOCK.lock().unwrap();
     log::info!("Saving cache to {}", file_path);
 
-    let file = File::create(file_path)?;
-    let mut writer = BufWriter::new(file);
-
     let data = cache
         .iter()
         .map(|(k, v)| CacheEntry {
             key: k.to_string(),
-            value: v.clone(),
+            value: v.info.clone(),
+            fetched_at: v.fetched_at,
         })
         .collect::<Vec<_>>();
 
-    bincode::encode_into_std_write(&data, &mut writer, bincode::config::standard())
-        .map_err(|e| DictionaryError::CacheError(format!("Failed to encode cache: {}", e)))?;
+    write_entries(file_path, &data)?;
 
     log::info!("Cache saved with {} entries", data.len());
     Ok(())
 }
+
+/// Saves the word cache every `interval` while the bot runs, so words fetched since the last
+/// save survive a crash. A save is skipped while the number of cached words hasn't changed.
+pub async fn run_cache_autosave(interval: Duration) {
+    let cache = get_cache();
+    cache.run_pending_tasks().await;
+    let mut saved_count = cache.entry_count();
+
+    loop {
+        tokio::time::sleep(interval).await;
+        cache.run_pending_tasks().await;
+        let count = cache.entry_count();
+        if count == saved_count {
+            log::debug!("Cache unchanged at {} entries, skipping save", count);
+            continue;
+        }
+
+        match tokio::task::spawn_blocking(move || save_cache(cache, CACHE_PATH)).await {
+            Ok(Ok(())) => saved_count = count,
+            Ok(Err(e)) => log::error!("Periodic cache save failed: {}", e),
+            Err(e) => log::error!("Periodic cache save task failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_dictionary;
+    use crate::games::Direction;
+    use teloxide::types::InlineKeyboardButtonKind;
+
+    fn word_with_defs(word: &str, senses: usize) -> WordInfo {
+        WordInfo {
+            word: word.to_string(),
+            stems: vec![word.to_string()],
+            defs: (0..senses)
+                .map(|i| Def {
+                    definitions: vec![format!("sense {}", i + 1)],
+                    functional_label: "noun".to_string(),
+                    examples: Vec::new(),
+                })
+                .collect(),
+            audio: None,
+        }
+    }
+
+    /// Callback data of every button on the keyboard, row by row
+    fn callbacks(keyboard: &InlineKeyboardMarkup) -> Vec<String> {
+        keyboard
+            .inline_keyboard
+            .iter()
+            .flatten()
+            .filter_map(|button| match &button.kind {
+                InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn navigation_wraps_around_the_senses() {
+        let info = word_with_defs("bank", 3);
+        let expected = [
+            ["def_bank_2", "def_bank_1"],
+            ["def_bank_0", "def_bank_2"],
+            ["def_bank_1", "def_bank_0"],
+        ];
+        for (idx, buttons) in expected.iter().enumerate() {
+            let (message, keyboard) = info.get_message(idx, false);
+            assert!(message.contains(&format!("sense {}", idx + 1)));
+            assert_eq!(callbacks(&keyboard), buttons);
+        }
+    }
+
+    #[test]
+    fn out_of_range_index_shows_the_last_sense() {
+        let info = word_with_defs("bank", 3);
+        let (message, keyboard) = info.get_message(7, false);
+        assert!(message.contains("sense 3"));
+        assert_eq!(callbacks(&keyboard), ["def_bank_1", "def_bank_0"]);
+    }
+
+    #[test]
+    fn word_without_definitions_has_no_navigation() {
+        let info = word_with_defs("zyzzyva", 0);
+        for idx in [0, 1] {
+            let (message, keyboard) = info.get_message(idx, true);
+            assert_eq!(message, "zyzzyva \\(no definition available\\)");
+            assert!(callbacks(&keyboard).is_empty());
+        }
+    }
+
+    #[test]
+    fn label_punctuation_is_escaped_inside_the_styling() {
+        let mut info = word_with_defs("e-mail", 1);
+        info.defs[0].functional_label = "abbr. noun-like".to_string();
+        let (message, _) = info.get_message(0, false);
+        assert!(message.starts_with("e\\-mail *__abbr\\. noun\\-like__*\n"));
+        assert!(message.ends_with("1\\. sense 1"));
+    }
+
+    #[test]
+    fn cached_word_expires_once_past_its_ttl() {
+        let ttl = Duration::from_secs(60 * 60);
+        let expiry = FetchedAtExpiry { ttl };
+        let key = "bank".to_string();
+
+        let stale = CachedWord {
+            info: word_with_defs("bank", 1),
+            fetched_at: unix_now() - 2 * 60 * 60,
+        };
+        assert_eq!(stale.remaining(ttl), Duration::ZERO);
+        assert_eq!(
+            expiry.expire_after_create(&key, &stale, Instant::now()),
+            Some(Duration::ZERO)
+        );
+
+        let fresh = CachedWord::new(word_with_defs("bank", 1));
+        assert!(fresh.remaining(ttl) > ttl - Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn stale_cached_word_is_fetched_again() {
+        fake_dictionary::install();
+        let stale = CachedWord {
+            info: word_with_defs("walrus", 2),
+            fetched_at: unix_now() - 2 * 24 * 60 * 60,
+        };
+        get_cache().insert("walrus".to_string(), stale).await;
+        let lookups = fake_dictionary::lookups("walrus");
+
+        let info = get_word_details("walrus").await.unwrap();
+        assert_eq!(fake_dictionary::lookups("walrus"), lookups + 1);
+        assert_eq!(info.defs[0].definitions, [fake_dictionary::SENSE]);
+
+        let cached = get_cache().get("walrus").await.unwrap();
+        assert_eq!(cached.info.defs.len(), 1);
+        assert!(cached.remaining(TEST_CACHE_TTL) > TEST_CACHE_TTL - Duration::from_secs(5));
+    }
+
+    #[test]
+    fn chain_letter_follows_the_recorded_form() {
+        let lookup = || Lookup {
+            defs: word_with_defs("run", 1).defs,
+            stems: vec!["run".to_string(), "running".to_string()],
+            headword: Some("run".to_string()),
+            audio: None,
+        };
+
+        let headword = recorded_word_info("running", lookup(), true);
+        assert_eq!(headword.word, "run");
+        assert_eq!(Direction::Forward.link_letter(&headword.word), Some('n'));
+
+        let as_played = recorded_word_info("running", lookup(), false);
+        assert_eq!(as_played.word, "running");
+        assert_eq!(Direction::Forward.link_letter(&as_played.word), Some('g'));
+    }
+
+    /// A file path of the test's own under the system's temporary directory
+    fn scratch_path(name: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("kotosume_{}_{}.bin", name, std::process::id()));
+        path.to_str().unwrap().to_string()
+    }
+
+    fn entry(word: &str) -> CacheEntry {
+        CacheEntry {
+            key: word.to_string(),
+            value: word_with_defs(word, 2),
+            fetched_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn entries_are_written_behind_a_version_header() {
+        let path = scratch_path("versioned_entries");
+        write_entries(&path, &[entry("cat"), entry("tiger")]).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let entries = read_entries(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(bytes.starts_with(FORMAT_MAGIC));
+        let entries = entries.unwrap();
+        let keys = entries.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, ["cat", "tiger"]);
+        assert_eq!(entries[1].value.defs.len(), 2);
+        assert_eq!(entries[1].fetched_at, 1_700_000_000);
+    }
+
+    #[test]
+    fn unknown_format_version_is_rejected() {
+        let path = scratch_path("future_entries");
+        let mut bytes = FORMAT_MAGIC.to_vec();
+        let body = (FORMAT_VERSION + 1, vec![entry("cat")]);
+        bytes.extend(bincode::encode_to_vec(body, bincode::config::standard()).unwrap());
+        std::fs::write(&path, bytes).unwrap();
+        let entries = read_entries(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(entries, Err(DictionaryError::CacheError(_))));
+    }
+
+    #[test]
+    fn files_from_before_the_header_are_upgraded() {
+        let path = scratch_path("unversioned_entries");
+        let legacy = vec![(
+            "cats".to_string(),
+            "cat".to_string(),
+            vec!["cat".to_string(), "cats".to_string()],
+            vec![(vec!["a small animal".to_string()], "noun".to_string())],
+        )];
+        let bytes = bincode::encode_to_vec(legacy, bincode::config::standard()).unwrap();
+        std::fs::write(&path, bytes).unwrap();
+        let entries = read_entries(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let entries = entries.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "cats");
+        assert_eq!(entries[0].value.word, "cat");
+        assert_eq!(entries[0].value.defs[0].functional_label, "noun");
+    }
+
+    #[tokio::test]
+    async fn known_word_details_come_only_from_the_cache() {
+        init_empty_cache();
+        get_cache()
+            .insert(
+                "qzxcat".to_string(),
+                CachedWord::new(word_with_defs("qzxcat", 1)),
+            )
+            .await;
+
+        let known = get_known_word_details("qzxcat").await;
+        assert_eq!(known.map(|info| info.word), Some("qzxcat".to_string()));
+        assert!(get_known_word_details("qzxdog").await.is_none());
+    }
+}
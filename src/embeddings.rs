@@ -1,18 +1,28 @@
+use crate::settings::get_settings;
+use flate2::read::GzDecoder;
 use itertools::Itertools;
 use log::{info, warn};
-use std::collections::HashMap;
-use std::fs;
-use std::io::{self, ErrorKind};
+use rand::seq::IteratorRandom;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use teloxide::types::ChatId;
 
 /// Error type for embedding operations
 #[derive(Debug)]
 pub enum EmbeddingError {
     IoError(io::Error),
+    /// The file has words, but not one of them is followed by a vector that could be read
     ParseError(String),
     MissingData(String),
     InvalidWord(String),
+    /// No word in the vocabulary starts with this letter
+    NoWordsForLetter(char),
 }
 
 impl From<io::Error> for EmbeddingError {
@@ -21,6 +31,13 @@ impl From<io::Error> for EmbeddingError {
     }
 }
 
+impl EmbeddingError {
+    /// Whether the embeddings couldn't be read, rather than having no word that fits
+    pub fn is_transient(&self) -> bool {
+        matches!(self, EmbeddingError::IoError(_))
+    }
+}
+
 impl std::fmt::Display for EmbeddingError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -28,6 +45,7 @@ impl std::fmt::Display for EmbeddingError {
             EmbeddingError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             EmbeddingError::MissingData(msg) => write!(f, "Missing data: {}", msg),
             EmbeddingError::InvalidWord(word) => write!(f, "Invalid word: {}", word),
+            EmbeddingError::NoWordsForLetter(c) => write!(f, "No words start with '{}'", c),
         }
     }
 }
@@ -37,8 +55,113 @@ pub type EmbeddingVec = Vec<f64>;
 /// Type alias for word-to-embedding maps grouped by first letter
 pub type EmbeddingMap = HashMap<char, HashMap<String, EmbeddingVec>>;
 
+/// Type alias for vector norms, keyed the same way as `EmbeddingMap`
+type NormMap = HashMap<char, HashMap<String, f64>>;
+
 static EMBEDDINGS: OnceLock<EmbeddingMap> = OnceLock::new();
-const EMBEDDINGS_FILE: &str = "word2vec.txt";
+static NORMS: OnceLock<NormMap> = OnceLock::new();
+
+/// Alternate models loaded at runtime for comparison, by name. They're leaked so they can be
+/// handed out as `'static` like the default model, and stay in memory until the bot exits.
+static MODELS: OnceLock<Mutex<HashMap<String, &'static EmbeddingMap>>> = OnceLock::new();
+/// Alternate model chosen by each chat; chats not listed use the default model
+static CHAT_MODELS: OnceLock<Mutex<HashMap<ChatId, String>>> = OnceLock::new();
+
+/// Norms of each alternate model's vectors, keyed by the address of the leaked model
+static MODEL_NORMS: OnceLock<Mutex<HashMap<usize, &'static NormMap>>> = OnceLock::new();
+
+fn get_model_norms() -> &'static Mutex<HashMap<usize, &'static NormMap>> {
+    MODEL_NORMS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_models() -> &'static Mutex<HashMap<String, &'static EmbeddingMap>> {
+    MODELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_chat_models() -> &'static Mutex<HashMap<ChatId, String>> {
+    CHAT_MODELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Load an alternate embeddings file under `name`, returning its number of words. Reloading a
+/// name makes chats using it switch to the new file.
+pub fn load_model(name: &str, file_name: &str) -> Result<usize, EmbeddingError> {
+    let model = init(file_name)?;
+    let words = model.values().map(|map| map.len()).sum::<usize>();
+    let bytes = model
+        .values()
+        .flat_map(|map| map.iter())
+        .map(|(word, vec)| word.len() + vec.len() * size_of::<f64>())
+        .sum::<usize>();
+    warn!(
+        "Loaded embeddings model '{}' from {}: {} words, about {} MB held in memory until exit",
+        name,
+        file_name,
+        words,
+        bytes / (1024 * 1024)
+    );
+
+    let model = with_cached_norms(model);
+    get_models().lock().unwrap().insert(name.to_string(), model);
+    Ok(words)
+}
+
+/// Keep a model for the rest of the run, with the norms of its vectors computed up front so
+/// comparing words with it is a dot product each
+fn with_cached_norms(model: EmbeddingMap) -> &'static EmbeddingMap {
+    let norms: &'static NormMap = Box::leak(Box::new(compute_norms(&model)));
+    let model: &'static EmbeddingMap = Box::leak(Box::new(model));
+    get_model_norms()
+        .lock()
+        .unwrap()
+        .insert(model as *const EmbeddingMap as usize, norms);
+    model
+}
+
+/// Names of the alternate models loaded so far, sorted
+pub fn model_names() -> Vec<String> {
+    get_models()
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .sorted()
+        .collect()
+}
+
+/// Make a chat use a loaded alternate model, or the default one with `None`
+pub fn select_model(chat_id: ChatId, name: Option<&str>) -> Result<(), EmbeddingError> {
+    match name {
+        Some(name) if !get_models().lock().unwrap().contains_key(name) => Err(
+            EmbeddingError::MissingData(format!("No model named '{}' is loaded", name)),
+        ),
+        Some(name) => {
+            get_chat_models()
+                .lock()
+                .unwrap()
+                .insert(chat_id, name.to_string());
+            Ok(())
+        }
+        None => {
+            get_chat_models().lock().unwrap().remove(&chat_id);
+            Ok(())
+        }
+    }
+}
+
+/// Name of the alternate model a chat uses, if any
+pub fn selected_model(chat_id: ChatId) -> Option<String> {
+    get_chat_models().lock().unwrap().get(&chat_id).cloned()
+}
+
+/// The embeddings a chat compares words with: its chosen alternate model, or the default one
+pub fn active_model(chat_id: ChatId) -> Result<&'static EmbeddingMap, EmbeddingError> {
+    let alternate =
+        selected_model(chat_id).and_then(|name| get_models().lock().unwrap().get(&name).copied());
+    match alternate {
+        Some(model) => Ok(model),
+        None => get_embeddings(),
+    }
+}
 
 /// Initialize embeddings from a file
 fn init(file_name: &str) -> Result<EmbeddingMap, EmbeddingError> {
@@ -51,46 +174,58 @@ fn init(file_name: &str) -> Result<EmbeddingMap, EmbeddingError> {
         )));
     }
 
-    let content = fs::read_to_string(file_name)?;
-    info!("Loaded embeddings file, processing {} bytes", content.len());
+    // Parse line by line so a large (or compressed) dump never sits in memory as text
+    let mut dropped = 0;
+    let mut unparsed = 0;
+    let mut lines = 0;
+    let mut result = EmbeddingMap::new();
+    for line in open_text(file_name)?.lines() {
+        let line = line?;
+        lines += 1;
+        let mut word_iter = line.split_whitespace();
+        let Some(word) = word_iter.next() else {
+            warn!("Empty line in embeddings file");
+            continue;
+        };
 
-    let result: EmbeddingMap = content
-        .replace("\r", "")
-        .lines()
-        .filter_map(|line| {
-            let mut word_iter = line.split_whitespace();
-            let word = match word_iter.next() {
-                Some(w) => w.to_string(),
-                None => {
-                    warn!("Empty line in embeddings file");
-                    return None;
-                }
-            };
+        // Games only ever ask for words starting with a lowercase letter, so anything
+        // else would be reachable by some lookups but never by random selection
+        let Some(first_char) = word.chars().next().filter(|c| c.is_ascii_lowercase()) else {
+            dropped += 1;
+            continue;
+        };
 
-            let vec: Result<Vec<f64>, _> = word_iter
-                .map(|x| x.parse::<f64>().map_err(|e| e.to_string()))
-                .collect();
+        let vec: Result<Vec<f64>, _> = word_iter
+            .map(|x| x.parse::<f64>().map_err(|e| e.to_string()))
+            .collect();
 
-            match vec {
-                Ok(v) => Some((word, v)),
-                Err(e) => {
-                    warn!("Failed to parse embedding for word '{}': {}", word, e);
-                    None
-                }
+        match vec {
+            Ok(v) => {
+                result
+                    .entry(first_char)
+                    .or_default()
+                    .insert(word.to_string(), v);
             }
-        })
-        .into_iter()
-        .sorted_by_key(|(key, _)| {
-            key.chars().next().unwrap_or('_') // Default to underscore for empty words
-        })
-        .chunk_by(|(key, _)| key.chars().next().unwrap_or('_'))
-        .into_iter()
-        .map(|(first_char, group)| {
-            let inner_map = group.into_iter().collect::<HashMap<_, _>>();
-            (first_char, inner_map)
-        })
-        .collect();
+            Err(e) => {
+                unparsed += 1;
+                warn!("Failed to parse embedding for word '{}': {}", word, e);
+            }
+        }
+    }
+    info!("Read {} lines from {}", lines, file_name);
+    if result.is_empty() && unparsed > 0 {
+        return Err(EmbeddingError::ParseError(format!(
+            "No embedding in {} could be parsed",
+            file_name
+        )));
+    }
 
+    if dropped > 0 {
+        info!(
+            "Dropped {} embeddings whose word doesn't start with a lowercase letter",
+            dropped
+        );
+    }
     info!(
         "Embeddings initialized with {} first characters",
         result.len()
@@ -98,12 +233,23 @@ fn init(file_name: &str) -> Result<EmbeddingMap, EmbeddingError> {
     Ok(result)
 }
 
+/// Open a text file for reading line by line, decompressing it on the fly when its name ends
+/// in `.gz`. Decompression failures surface as I/O errors while reading.
+pub fn open_text(file_name: &str) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(file_name)?;
+    if file_name.ends_with(".gz") {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
 /// Get the global embeddings map, initializing if necessary
 pub fn get_embeddings() -> Result<&'static EmbeddingMap, EmbeddingError> {
     match EMBEDDINGS.get() {
         Some(embeddings) => Ok(embeddings),
         None => {
-            let embeddings = init(EMBEDDINGS_FILE)?;
+            let embeddings = init(&get_settings().embeddings_file)?;
             EMBEDDINGS
                 .set(embeddings)
                 .expect("Failed to set embeddings");
@@ -112,6 +258,13 @@ pub fn get_embeddings() -> Result<&'static EmbeddingMap, EmbeddingError> {
     }
 }
 
+/// Uses `embeddings` as the default model instead of loading the configured file. Only takes
+/// effect before the model is first needed.
+#[cfg(test)]
+pub fn use_embeddings(embeddings: EmbeddingMap) {
+    let _ = EMBEDDINGS.set(embeddings);
+}
+
 /// Check if a word exists in the embeddings
 pub fn is_valid_word(word: &str) -> bool {
     if word.is_empty() {
@@ -126,16 +279,234 @@ pub fn is_valid_word(word: &str) -> bool {
     match get_embeddings() {
         Ok(embeddings) => embeddings
             .get(&first_char)
-            .map_or(false, |map| map.contains_key(word)),
+            .is_some_and(|map| map.contains_key(word)),
         Err(_) => false,
     }
 }
 
+/// Vocabulary words that start with the given prefix and satisfy the predicate
+pub fn words_with_prefix<P>(prefix: &str, predicate: P) -> Result<Vec<&'static str>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    let Some(first_char) = prefix.chars().next() else {
+        return Err(EmbeddingError::InvalidWord(
+            "Prefix cannot be empty".to_string(),
+        ));
+    };
+    Ok(embeddings.get(&first_char).map_or_else(Vec::new, |map| {
+        map.keys()
+            .filter(|k| k.starts_with(prefix) && predicate(k))
+            .map(String::as_str)
+            .collect()
+    }))
+}
+
+/// Whether any vocabulary word satisfying the predicate starts with the given prefix
+pub fn has_words_with_prefix<P>(prefix: &str, predicate: P) -> Result<bool, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    Ok(prefix.chars().next().is_some_and(|first_char| {
+        embeddings
+            .get(&first_char)
+            .is_some_and(|map| map.keys().any(|k| k.starts_with(prefix) && predicate(k)))
+    }))
+}
+
+/// Count the words in the whole vocabulary that satisfy the predicate
+pub fn count_words<P>(predicate: P) -> Result<usize, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    Ok(embeddings
+        .values()
+        .map(|map| map.keys().filter(|k| predicate(k)).count())
+        .sum())
+}
+
+/// Number of vocabulary words starting with each letter, computed once per run
+static LETTER_POOLS: OnceLock<Vec<(char, usize)>> = OnceLock::new();
+
+/// Count the words starting with a letter that satisfy the predicate
+pub fn letter_pool_size<P>(letter: char, predicate: P) -> Result<usize, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    Ok(embeddings
+        .get(&letter)
+        .map_or(0, |map| map.keys().filter(|k| predicate(k)).count()))
+}
+
+/// Number of words starting with `starting_char` that satisfy the predicate; none when the
+/// embeddings can't be loaded
+pub fn count_candidates<P>(starting_char: char, predicate: P) -> usize
+where
+    P: Fn(&str) -> bool,
+{
+    letter_pool_size(starting_char, predicate).unwrap_or(0)
+}
+
+/// Whether any word starting with `starting_char` satisfies the predicate, stopping at the
+/// first one; false when the embeddings can't be loaded
+pub fn has_any_word<P>(starting_char: char, predicate: P) -> bool
+where
+    P: Fn(&str) -> bool,
+{
+    get_embeddings().is_ok_and(|embeddings| {
+        embeddings
+            .get(&starting_char)
+            .is_some_and(|map| map.keys().any(|k| predicate(k)))
+    })
+}
+
+/// Count the words starting with each letter from 'a' to 'z' that satisfy the predicate
+pub fn letter_pool_sizes<P>(predicate: P) -> Result<Vec<(char, usize)>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    ('a'..='z')
+        .map(|c| Ok((c, letter_pool_size(c, &predicate)?)))
+        .collect()
+}
+
+/// Number of vocabulary words starting with each letter from 'a' to 'z'
+pub fn letter_pools() -> Result<&'static [(char, usize)], EmbeddingError> {
+    if let Some(pools) = LETTER_POOLS.get() {
+        return Ok(pools);
+    }
+    let pools = letter_pool_sizes(|_| true)?;
+    Ok(LETTER_POOLS.get_or_init(|| pools))
+}
+
+/// How to continue a chain when no word starts with the required letter
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chaining {
+    /// Fail with `EmbeddingError::NoWordsForLetter`
+    Strict,
+    /// Fall back to candidates starting with any letter
+    Lenient,
+}
+
 /// Find the most similar word to the given word that starts with the specified character
 /// and satisfies the predicate
 pub fn get_similar_word<P>(
     word: &str,
     starting_char: char,
+    chaining: Chaining,
+    predicate: P,
+) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let mut best = get_top_similar_words(word, starting_char, chaining, 1, predicate)?;
+    Ok(best.swap_remove(0).0)
+}
+
+/// Find up to `n` words most similar to the given word that start with the specified character
+/// and satisfy the predicate, with their cosine similarity, most similar first
+pub fn get_top_similar_words<P>(
+    word: &str,
+    starting_char: char,
+    chaining: Chaining,
+    n: usize,
+    predicate: P,
+) -> Result<Vec<(String, f64)>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    get_top_similar_words_in(
+        get_embeddings()?,
+        word,
+        starting_char,
+        chaining,
+        n,
+        predicate,
+    )
+}
+
+/// Like `get_top_similar_words`, comparing words with the given model instead of the default one
+pub fn get_top_similar_words_in<P>(
+    embeddings: &EmbeddingMap,
+    word: &str,
+    starting_char: char,
+    chaining: Chaining,
+    n: usize,
+    predicate: P,
+) -> Result<Vec<(String, f64)>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    ranked_in(
+        embeddings,
+        word,
+        starting_candidates(embeddings, starting_char, chaining, predicate)?,
+        n,
+        false,
+        &format!("starting with '{}'", starting_char),
+    )
+}
+
+/// Like `get_top_similar_words`, for words of exactly `len` letters: candidates come from the
+/// first-letter-and-length index instead of a scan of every word starting with the letter
+pub fn get_top_similar_words_of_length<P>(
+    word: &str,
+    starting_char: char,
+    len: usize,
+    n: usize,
+    predicate: P,
+) -> Result<Vec<(String, f64)>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    let s_map = embeddings
+        .get(&starting_char)
+        .ok_or(EmbeddingError::NoWordsForLetter(starting_char))?;
+    let candidates = words_of_length(starting_char, len)?
+        .iter()
+        .filter(|x| predicate(x))
+        .map(|&x| (x, s_map))
+        .collect();
+    ranked_in(
+        embeddings,
+        word,
+        candidates,
+        n,
+        false,
+        &format!("starting with '{}' with {} letters", starting_char, len),
+    )
+}
+
+/// Find the least similar word to the given word that starts with the specified character
+/// and satisfies the predicate
+pub fn get_dissimilar_word<P>(
+    word: &str,
+    starting_char: char,
+    chaining: Chaining,
+    predicate: P,
+) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    best_match(
+        get_embeddings()?,
+        word,
+        starting_char,
+        chaining,
+        predicate,
+        true,
+    )
+}
+
+/// Pick a random word that starts with the specified character and satisfies the predicate
+pub fn get_any_word<P>(
+    starting_char: char,
+    chaining: Chaining,
     predicate: P,
 ) -> Result<String, EmbeddingError>
 where
@@ -143,6 +514,274 @@ where
 {
     let embeddings = get_embeddings()?;
 
+    let s_maps = match (embeddings.get(&starting_char), chaining) {
+        (Some(s_map), _) => vec![s_map],
+        (None, Chaining::Lenient) => embeddings.values().collect(),
+        (None, Chaining::Strict) => return Err(EmbeddingError::NoWordsForLetter(starting_char)),
+    };
+
+    s_maps
+        .into_iter()
+        .flat_map(|s_map| s_map.keys())
+        .filter(|x| predicate(x))
+        .choose(&mut rand::rng())
+        .cloned()
+        .ok_or_else(|| {
+            EmbeddingError::MissingData(format!(
+                "No words starting with '{}' match the predicate",
+                starting_char
+            ))
+        })
+}
+
+/// Find up to `n` words ending with `ending_char` that are most similar to the given word and
+/// satisfy the predicate, with their cosine similarity, most similar first
+pub fn get_top_similar_words_ending<P>(
+    word: &str,
+    ending_char: char,
+    n: usize,
+    predicate: P,
+) -> Result<Vec<(String, f64)>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    ranked_in(
+        embeddings,
+        word,
+        ending_candidates(embeddings, ending_char, predicate)?,
+        n,
+        false,
+        &format!("ending with '{}'", ending_char),
+    )
+}
+
+/// Find the word ending with `ending_char` that is least similar to the given word and
+/// satisfies the predicate, for chains that link backwards
+pub fn get_dissimilar_word_ending<P>(
+    word: &str,
+    ending_char: char,
+    predicate: P,
+) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    best_match_ending(word, ending_char, predicate, true)
+}
+
+/// Pick a random word that ends with the specified character and satisfies the predicate
+pub fn random_word_ending_with<P>(ending_char: char, predicate: P) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    words_ending_with(ending_char)?
+        .iter()
+        .filter(|x| predicate(x))
+        .choose(&mut rand::rng())
+        .map(|x| x.to_string())
+        .ok_or_else(|| {
+            EmbeddingError::MissingData(format!(
+                "No words ending with '{}' match the predicate",
+                ending_char
+            ))
+        })
+}
+
+/// Words of the default model grouped by last letter, built on first use
+static ENDINGS: OnceLock<HashMap<char, Vec<&'static str>>> = OnceLock::new();
+
+/// Words of the default model ending with `ending_char`. The embeddings are grouped by first
+/// letter, so an index by last letter is built once per run instead of scanning the whole
+/// vocabulary on every lookup.
+pub fn words_ending_with(ending_char: char) -> Result<&'static [&'static str], EmbeddingError> {
+    let index = match ENDINGS.get() {
+        Some(index) => index,
+        None => {
+            let started = Instant::now();
+            let index = ending_index(get_embeddings()?);
+            info!(
+                "Indexed embeddings by last letter in {:?}",
+                started.elapsed()
+            );
+            ENDINGS.get_or_init(|| index)
+        }
+    };
+    Ok(index.get(&ending_char).map_or(&[], Vec::as_slice))
+}
+
+/// A model's words grouped by last letter
+fn ending_index(embeddings: &EmbeddingMap) -> HashMap<char, Vec<&str>> {
+    let mut index: HashMap<char, Vec<&str>> = HashMap::new();
+    for word in embeddings.values().flat_map(|s_map| s_map.keys()) {
+        if let Some(last) = word.chars().last() {
+            index.entry(last).or_default().push(word);
+        }
+    }
+    index
+}
+
+/// Words of the default model grouped by first letter and length, each group sorted, built on
+/// first use
+static LENGTHS: OnceLock<HashMap<(char, usize), Vec<&'static str>>> = OnceLock::new();
+
+/// The first-letter-and-length index of the default model, built the first time it's needed.
+/// The embeddings never change once loaded, so the index is never rebuilt.
+fn length_index() -> Result<&'static HashMap<(char, usize), Vec<&'static str>>, EmbeddingError> {
+    if let Some(index) = LENGTHS.get() {
+        return Ok(index);
+    }
+    let started = Instant::now();
+    let mut index: HashMap<(char, usize), Vec<&'static str>> = HashMap::new();
+    for (&letter, s_map) in get_embeddings()? {
+        for word in s_map.keys() {
+            index.entry((letter, word.len())).or_default().push(word);
+        }
+    }
+    index.values_mut().for_each(|words| words.sort_unstable());
+    info!(
+        "Indexed embeddings by first letter and length in {:?}",
+        started.elapsed()
+    );
+    Ok(LENGTHS.get_or_init(|| index))
+}
+
+/// Words of the default model starting with `letter` that are `len` letters long, sorted.
+/// Length-constrained games look their candidates up here instead of filtering every word
+/// starting with the letter.
+pub fn words_of_length(
+    letter: char,
+    len: usize,
+) -> Result<&'static [&'static str], EmbeddingError> {
+    Ok(length_index()?
+        .get(&(letter, len))
+        .map_or(&[], Vec::as_slice))
+}
+
+/// Number of words of the default model that are `len` letters long
+pub fn count_words_of_length(len: usize) -> Result<usize, EmbeddingError> {
+    Ok(length_index()?
+        .iter()
+        .filter(|((_, l), _)| *l == len)
+        .map(|(_, words)| words.len())
+        .sum())
+}
+
+/// Candidate words, each with the map holding its embedding
+type Candidates<'a> = Vec<(&'a str, &'a HashMap<String, EmbeddingVec>)>;
+
+/// Find the candidate ending with `ending_char` that is most (or, with `dissimilar`, least)
+/// similar to the given word, using the default model's last-letter index
+fn best_match_ending<P>(
+    word: &str,
+    ending_char: char,
+    predicate: P,
+    dissimilar: bool,
+) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    let embeddings = get_embeddings()?;
+    closest_in(
+        embeddings,
+        word,
+        ending_candidates(embeddings, ending_char, predicate)?,
+        dissimilar,
+        &format!("ending with '{}'", ending_char),
+    )
+}
+
+/// Words ending with `ending_char` that satisfy the predicate, each with the map holding its
+/// embedding
+fn ending_candidates<P>(
+    embeddings: &EmbeddingMap,
+    ending_char: char,
+    predicate: P,
+) -> Result<Candidates<'_>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    Ok(words_ending_with(ending_char)?
+        .iter()
+        .filter(|x| predicate(x))
+        .filter_map(|&x| {
+            let s_map = embeddings.get(&x.chars().next()?)?;
+            Some((x, s_map))
+        })
+        .collect())
+}
+
+/// Find the candidate starting with `starting_char` that is most (or, with `dissimilar`,
+/// least) similar to the given word
+fn best_match<P>(
+    embeddings: &EmbeddingMap,
+    word: &str,
+    starting_char: char,
+    chaining: Chaining,
+    predicate: P,
+    dissimilar: bool,
+) -> Result<String, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    closest_in(
+        embeddings,
+        word,
+        starting_candidates(embeddings, starting_char, chaining, predicate)?,
+        dissimilar,
+        &format!("starting with '{}'", starting_char),
+    )
+}
+
+/// Words starting with `starting_char` that satisfy the predicate, each with the map holding
+/// its embedding
+fn starting_candidates<P>(
+    embeddings: &EmbeddingMap,
+    starting_char: char,
+    chaining: Chaining,
+    predicate: P,
+) -> Result<Candidates<'_>, EmbeddingError>
+where
+    P: Fn(&str) -> bool,
+{
+    // Get map(s) for target starting character, relaxing to every letter if allowed
+    let s_maps = match (embeddings.get(&starting_char), chaining) {
+        (Some(s_map), _) => vec![s_map],
+        (None, Chaining::Lenient) => embeddings.values().collect(),
+        (None, Chaining::Strict) => return Err(EmbeddingError::NoWordsForLetter(starting_char)),
+    };
+
+    Ok(s_maps
+        .into_iter()
+        .flat_map(|s_map| s_map.keys().map(move |k| (k.as_str(), s_map)))
+        .filter(|(x, _)| predicate(x))
+        .collect())
+}
+
+/// Find the candidate that is most (or, with `dissimilar`, least) similar to the given word.
+/// Each candidate comes with the map holding its embedding; `wanted` describes the candidates
+/// for error messages, e.g. "starting with 'a'".
+fn closest_in(
+    embeddings: &EmbeddingMap,
+    word: &str,
+    candidates: Candidates<'_>,
+    dissimilar: bool,
+    wanted: &str,
+) -> Result<String, EmbeddingError> {
+    let mut ranked = ranked_in(embeddings, word, candidates, 1, dissimilar, wanted)?;
+    Ok(ranked.swap_remove(0).0)
+}
+
+/// Find up to `n` candidates most (or, with `dissimilar`, least) similar to the given word,
+/// best first, with their cosine similarity. Never empty: finding no candidate at all is an
+/// error.
+fn ranked_in(
+    embeddings: &EmbeddingMap,
+    word: &str,
+    candidates: Candidates<'_>,
+    n: usize,
+    dissimilar: bool,
+    wanted: &str,
+) -> Result<Vec<(String, f64)>, EmbeddingError> {
     // Validate input word
     if word.is_empty() {
         return Err(EmbeddingError::InvalidWord("Word is empty".to_string()));
@@ -157,66 +796,190 @@ where
         EmbeddingError::MissingData(format!("No embeddings for letter '{}'", first_char))
     })?;
 
-    if !f_map.contains_key(word) {
+    let Some(embed) = f_map.get(word) else {
         return Err(EmbeddingError::InvalidWord(format!(
             "Word '{}' not found in embeddings",
             word
         )));
-    }
+    };
+    let norms = norms_of(embeddings);
+    let embed_norm = norm_in(norms, word, embed);
 
-    // Get map for target starting character
-    let s_map = embeddings.get(&starting_char).ok_or_else(|| {
-        EmbeddingError::MissingData(format!("No embeddings for letter '{}'", starting_char))
-    })?;
+    if candidates.is_empty() {
+        return Err(EmbeddingError::MissingData(format!(
+            "No words {} match the predicate",
+            wanted
+        )));
+    }
 
-    // Find the most similar word
-    let result = s_map
-        .keys()
-        .filter(|x| predicate(x))
-        .collect::<Vec<&String>>();
+    // Keep only the best `n` words (most or least similar) in a min-heap, reusing the
+    // precomputed norms
+    let n = n.max(1);
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (candidate, s_map) in candidates {
+        let Some(vec) = s_map.get(candidate) else {
+            continue;
+        };
+        let sim = cosine_with_norms(
+            (word, embed, embed_norm),
+            (candidate, vec, norm_in(norms, candidate, vec)),
+        );
+        let score = if dissimilar { -sim } else { sim };
+        heap.push(Reverse(Scored(score, candidate)));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
 
-    if result.is_empty() {
+    if heap.is_empty() {
         return Err(EmbeddingError::MissingData(format!(
-            "No words starting with '{}' match the predicate",
-            starting_char
+            "Could not find a matching word {}",
+            wanted
         )));
     }
 
-    // Find the most similar word to the input word
-    let mut best_similarity = -1.0;
-    let mut best_word = String::new();
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Scored(score, candidate))| {
+            let similarity = if dissimilar { -score } else { score };
+            (candidate.to_string(), similarity)
+        })
+        .collect())
+}
 
-    for candidate in result {
-        match similarity_eff(word, f_map, candidate, s_map) {
-            Ok(sim) => {
-                if sim > best_similarity {
-                    best_similarity = sim;
-                    best_word = candidate.clone();
-                }
+/// Find the `n` words most similar to the given word across the whole vocabulary,
+/// ordered from most to least similar
+pub fn most_similar(word: &str, n: usize) -> Result<Vec<(String, f64)>, EmbeddingError> {
+    most_similar_in(get_embeddings()?, get_norms()?, word, n)
+}
+
+/// Like `most_similar`, with the given model and its precomputed norms
+fn most_similar_in(
+    embeddings: &EmbeddingMap,
+    norms: &NormMap,
+    word: &str,
+    n: usize,
+) -> Result<Vec<(String, f64)>, EmbeddingError> {
+    let first_char = word
+        .chars()
+        .next()
+        .ok_or_else(|| EmbeddingError::InvalidWord("Word is empty".to_string()))?;
+
+    let embed = embeddings
+        .get(&first_char)
+        .and_then(|map| map.get(word))
+        .ok_or_else(|| {
+            EmbeddingError::InvalidWord(format!("Word '{}' not found in embeddings", word))
+        })?;
+
+    let embed_norm = norm_in(Some(norms), word, embed);
+
+    // Keep only the best `n` candidates in a min-heap so the whole vocabulary
+    // never has to be collected and sorted
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (letter, map) in embeddings {
+        let letter_norms = norms.get(letter);
+        for (candidate, vec) in map {
+            if candidate == word {
+                continue;
+            }
+            let candidate_norm = letter_norms
+                .and_then(|m| m.get(candidate))
+                .copied()
+                .unwrap_or_else(|| norm(vec));
+            let score =
+                cosine_with_norms((word, embed, embed_norm), (candidate, vec, candidate_norm));
+
+            heap.push(Reverse(Scored(score, candidate.as_str())));
+            if heap.len() > n {
+                heap.pop();
             }
-            Err(_) => continue, // Skip words with errors
         }
     }
 
-    if best_word.is_empty() {
-        return Err(EmbeddingError::MissingData(format!(
-            "Could not find a similar word starting with '{}'",
-            starting_char
-        )));
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Scored(score, candidate))| (candidate.to_string(), score))
+        .collect())
+}
+
+/// A similarity score paired with its word, ordered by score
+struct Scored<'a>(f64, &'a str);
+
+impl PartialEq for Scored<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored<'_> {}
+
+impl PartialOrd for Scored<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then_with(|| other.1.cmp(self.1))
+    }
+}
+
+/// Get the norms of every embedding vector, computing them on first use
+fn get_norms() -> Result<&'static NormMap, EmbeddingError> {
+    if let Some(norms) = NORMS.get() {
+        return Ok(norms);
     }
 
-    Ok(best_word)
+    let embeddings = get_embeddings()?;
+    Ok(NORMS.get_or_init(|| compute_norms(embeddings)))
 }
 
-/// Calculate similarity between two words
-pub fn similarity(a: &str, b: &str) -> Result<f64, EmbeddingError> {
+/// Norm of every vector in a model
+fn compute_norms(embeddings: &EmbeddingMap) -> NormMap {
+    embeddings
+        .iter()
+        .map(|(letter, map)| {
+            let inner = map.iter().map(|(w, v)| (w.clone(), norm(v))).collect();
+            (*letter, inner)
+        })
+        .collect()
+}
+
+/// Precomputed norms of a model's vectors: the default model's, or a loaded alternate's
+fn norms_of(embeddings: &EmbeddingMap) -> Option<&'static NormMap> {
+    if EMBEDDINGS
+        .get()
+        .is_some_and(|default| std::ptr::eq(default, embeddings))
+    {
+        return get_norms().ok();
+    }
+    get_model_norms()
+        .lock()
+        .unwrap()
+        .get(&(embeddings as *const EmbeddingMap as usize))
+        .copied()
+}
+
+/// Norm of a word's vector, looked up in `norms` or computed when it isn't there
+fn norm_in(norms: Option<&NormMap>, word: &str, vec: &[f64]) -> f64 {
+    norms
+        .and_then(|norms| norms.get(&word.chars().next()?)?.get(word))
+        .copied()
+        .unwrap_or_else(|| norm(vec))
+}
+
+/// Calculate similarity between two words with the given model
+pub fn similarity_in(embeddings: &EmbeddingMap, a: &str, b: &str) -> Result<f64, EmbeddingError> {
     if a.is_empty() || b.is_empty() {
         return Err(EmbeddingError::InvalidWord(
             "Words cannot be empty".to_string(),
         ));
     }
 
-    let embeddings = get_embeddings()?;
     let a_first = a
         .chars()
         .next()
@@ -240,54 +1003,323 @@ pub fn similarity(a: &str, b: &str) -> Result<f64, EmbeddingError> {
         EmbeddingError::InvalidWord(format!("Word '{}' not found in embeddings", b))
     })?;
 
-    Ok(cosine(a_embed, b_embed))
+    let norms = norms_of(embeddings);
+    Ok(cosine_with_norms(
+        (a, a_embed, norm_in(norms, a, a_embed)),
+        (b, b_embed, norm_in(norms, b, b_embed)),
+    ))
 }
 
-/// Helper function to calculate similarity efficiently when maps are already available
-fn similarity_eff(
-    a: &str,
-    a_embed_map: &HashMap<String, Vec<f64>>,
-    b: &str,
-    b_embed_map: &HashMap<String, Vec<f64>>,
-) -> Result<f64, EmbeddingError> {
-    let a_embed = a_embed_map.get(a).ok_or_else(|| {
-        EmbeddingError::InvalidWord(format!("Word '{}' not found in embeddings", a))
-    })?;
-    let b_embed = b_embed_map.get(b).ok_or_else(|| {
-        EmbeddingError::InvalidWord(format!("Word '{}' not found in embeddings", b))
-    })?;
-
-    Ok(cosine(a_embed, b_embed))
+/// Euclidean norm of a vector
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
 }
 
-/// Calculate cosine similarity between two vectors
-fn cosine(a: &[f64], b: &[f64]) -> f64 {
-    if a.len() != b.len() {
-        warn!(
-            "Vector length mismatch in cosine calculation: {} vs {}",
-            a.len(),
-            b.len()
-        );
-        return 0.0;
-    }
+/// Similarity comparisons that hit vectors of different dimensions since startup
+static DIMENSION_MISMATCHES: AtomicUsize = AtomicUsize::new(0);
 
-    let mut dot: f64 = 0.0;
-    let mut norm_a: f64 = 0.0;
-    let mut norm_b: f64 = 0.0;
+/// Number of similarity comparisons between vectors of different dimensions since startup.
+/// Each one scores the pair as unrelated, so a non-zero count points at a ragged embeddings
+/// file.
+pub fn dimension_mismatches() -> usize {
+    DIMENSION_MISMATCHES.load(AtomicOrdering::Relaxed)
+}
 
-    for i in 0..a.len() {
-        dot += a[i] * b[i];
-        norm_a += a[i] * a[i];
-        norm_b += b[i] * b[i];
+/// Whether two words' vectors can be compared; a mismatch is logged and counted
+fn same_dimension(a_word: &str, a: &[f64], b_word: &str, b: &[f64]) -> bool {
+    if a.len() == b.len() {
+        return true;
     }
+    let total = DIMENSION_MISMATCHES.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+    warn!(
+        "Vector dimension mismatch comparing '{}' ({}) with '{}' ({}); scoring them as unrelated ({} mismatches so far)",
+        a_word,
+        a.len(),
+        b_word,
+        b.len(),
+        total
+    );
+    false
+}
 
-    norm_a = norm_a.sqrt();
-    norm_b = norm_b.sqrt();
+/// Calculate cosine similarity between two words' vectors whose norms are already known
+fn cosine_with_norms(
+    (a_word, a, norm_a): (&str, &[f64], f64),
+    (b_word, b, norm_b): (&str, &[f64], f64),
+) -> f64 {
+    if !same_dimension(a_word, a, b_word, b) {
+        return 0.0;
+    }
 
     // Handle division by zero
     if norm_a.abs() < f64::EPSILON || norm_b.abs() < f64::EPSILON {
         return 0.0;
     }
 
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
     dot / (norm_a * norm_b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::fs;
+    use std::time::Duration;
+
+    /// Writes `contents` to a file of the test's own under the system's temporary directory
+    fn scratch_file(name: &str, contents: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("kotosume_{}_{}.txt", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// A model holding the given words and vectors
+    fn model(words: &[(&str, &[f64])]) -> EmbeddingMap {
+        let mut map = EmbeddingMap::new();
+        for (word, vec) in words {
+            map.entry(word.chars().next().unwrap())
+                .or_default()
+                .insert(word.to_string(), vec.to_vec());
+        }
+        map
+    }
+
+    /// A model of `words` made-up words with random `dims`-dimensional vectors, the same every
+    /// run. Words start with every letter in turn.
+    fn synthetic_model(words: usize, dims: usize) -> EmbeddingMap {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut map = EmbeddingMap::new();
+        for i in 0..words {
+            let mut word = String::new();
+            let mut rest = i;
+            loop {
+                word.push((b'a' + (rest % 26) as u8) as char);
+                rest /= 26;
+                if rest == 0 && word.len() >= 3 {
+                    break;
+                }
+            }
+            let vec = (0..dims).map(|_| rng.random_range(-1.0..1.0)).collect();
+            map.entry(word.chars().next().unwrap())
+                .or_default()
+                .insert(word, vec);
+        }
+        map
+    }
+
+    /// Run `f` `rounds` times and return the average time it took
+    fn time_per_round<T>(rounds: u32, mut f: impl FnMut() -> T) -> Duration {
+        let started = Instant::now();
+        for _ in 0..rounds {
+            std::hint::black_box(f());
+        }
+        started.elapsed() / rounds
+    }
+
+    /// Every word of the model ranked against `word` by collecting and sorting the whole
+    /// vocabulary, the way the heap-based searches avoid
+    fn fully_sorted(embeddings: &EmbeddingMap, word: &str) -> Vec<(String, f64)> {
+        let embed = &embeddings[&word.chars().next().unwrap()][word];
+        embeddings
+            .values()
+            .flatten()
+            .filter(|(candidate, _)| candidate.as_str() != word)
+            .map(|(candidate, vec)| {
+                let score =
+                    cosine_with_norms((word, embed, norm(embed)), (candidate, vec, norm(vec)));
+                (candidate.clone(), score)
+            })
+            .sorted_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+
+    #[test]
+    fn most_similar_matches_a_full_sort() {
+        let model = synthetic_model(2_000, 50);
+        let norms = compute_norms(&model);
+
+        let top = most_similar_in(&model, &norms, "abc", 10).unwrap();
+        let mut sorted = fully_sorted(&model, "abc");
+        sorted.truncate(10);
+        assert_eq!(top, sorted);
+
+        let absent = most_similar_in(&model, &norms, "zzzzzz", 10);
+        assert!(matches!(absent, Err(EmbeddingError::InvalidWord(_))));
+        assert!(most_similar_in(&model, &norms, "", 10).is_err());
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture bench_`
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_most_similar() {
+        let model = synthetic_model(100_000, 300);
+        let norms = compute_norms(&model);
+
+        let heap = time_per_round(5, || most_similar_in(&model, &norms, "abc", 10).unwrap());
+        let sort = time_per_round(5, || fully_sorted(&model, "abc"));
+        println!(
+            "Top 10 of 100000 words, 300 dimensions:\n\
+            Bounded heap with cached norms: {:?}\n\
+            Full sort: {:?}",
+            heap, sort
+        );
+    }
+
+    #[test]
+    fn cached_norms_rank_words_as_recomputed_ones_do() {
+        let uncached = synthetic_model(3_000, 300);
+        let cached = with_cached_norms(uncached.clone());
+        assert!(norms_of(cached).is_some());
+        assert!(norms_of(&uncached).is_none());
+
+        let top = |model| {
+            get_top_similar_words_in(model, "abc", 'b', Chaining::Strict, 5, |_| true).unwrap()
+        };
+        let expected = fully_sorted(&uncached, "abc")
+            .into_iter()
+            .filter(|(word, _)| word.starts_with('b'))
+            .take(5)
+            .collect::<Vec<_>>();
+        assert_eq!(top(cached), expected);
+        assert_eq!(top(&uncached), expected);
+        assert_eq!(
+            similarity_in(cached, "abc", "bcd").unwrap(),
+            similarity_in(&uncached, "abc", "bcd").unwrap()
+        );
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture bench_`
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_cached_norms() {
+        let uncached = synthetic_model(100_000, 300);
+        let cached = with_cached_norms(uncached.clone());
+
+        let rank = |model| {
+            get_top_similar_words_in(model, "abc", 'b', Chaining::Strict, 1, |_| true).unwrap()
+        };
+        let with_norms = time_per_round(20, || rank(cached));
+        let without = time_per_round(20, || rank(&uncached));
+        println!(
+            "Best match among words starting with 'b' of 100000 words, 300 dimensions:\n\
+            Cached norms: {:?}\n\
+            Norms recomputed per comparison: {:?}",
+            with_norms, without
+        );
+    }
+
+    #[test]
+    fn ending_index_groups_every_word_by_its_last_letter() {
+        let model = synthetic_model(2_000, 2);
+        let index = ending_index(&model);
+
+        assert_eq!(index.values().map(Vec::len).sum::<usize>(), 2_000);
+        for (last, words) in &index {
+            assert!(words.iter().all(|word| word.ends_with(*last)));
+        }
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture bench_`
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_ending_lookups() {
+        let model = synthetic_model(100_000, 1);
+        let index = ending_index(&model);
+        let mut rng = rand::rng();
+
+        let indexed = time_per_round(100, || {
+            ('a'..='z')
+                .map(|letter| index.get(&letter)?.iter().choose(&mut rng).copied())
+                .collect::<Vec<_>>()
+        }) / 26;
+        let scanned = time_per_round(100, || {
+            ('a'..='z')
+                .map(|letter| {
+                    model
+                        .values()
+                        .flat_map(|s_map| s_map.keys())
+                        .filter(|w| w.ends_with(letter))
+                        .choose(&mut rng)
+                })
+                .collect::<Vec<_>>()
+        }) / 26;
+        println!(
+            "Random word ending with a letter, 100000 words:\n\
+            Last-letter index: {:?}\n\
+            Full scan: {:?}",
+            indexed, scanned
+        );
+    }
+
+    #[test]
+    fn lenient_chaining_continues_from_a_letter_no_word_starts_with() {
+        let model = model(&[
+            ("fox", &[1.0, 0.0]),
+            ("wolf", &[0.9, 0.1]),
+            ("apple", &[0.0, 1.0]),
+        ]);
+
+        let strict = get_top_similar_words_in(&model, "fox", 'x', Chaining::Strict, 1, |_| true);
+        assert!(matches!(strict, Err(EmbeddingError::NoWordsForLetter('x'))));
+
+        let lenient =
+            get_top_similar_words_in(&model, "fox", 'x', Chaining::Lenient, 1, |w| w != "fox")
+                .unwrap();
+        assert_eq!(lenient[0].0, "wolf");
+    }
+
+    #[test]
+    fn loader_skips_words_not_starting_with_a_lowercase_letter() {
+        let path = scratch_file(
+            "embeddings_first_letter",
+            "apple 0.1 0.2\n\
+            42nd 0.3 0.1\n\
+            'tis 0.2 0.2\n\
+            -ish 0.4 0.1\n\
+            Paris 0.1 0.1\n\
+            banana 0.5 0.5\n\
+            axe 0.2 0.3\n",
+        );
+        let map = init(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let mut letters = map.keys().copied().collect::<Vec<_>>();
+        letters.sort();
+        assert_eq!(letters, ['a', 'b']);
+        assert_eq!(map[&'a'].len(), 2);
+        assert_eq!(map[&'b'].len(), 1);
+        assert!(map.values().all(|words| words
+            .keys()
+            .all(|w| w.starts_with(|c: char| c.is_ascii_lowercase()))));
+    }
+
+    #[test]
+    fn file_without_a_single_vector_is_a_parse_error() {
+        let path = scratch_file("embeddings_unparsable", "apple one two\nbanana 0.1 x\n");
+        let result = init(&path);
+        let _ = fs::remove_file(&path);
+        assert!(matches!(result, Err(EmbeddingError::ParseError(_))));
+    }
+
+    #[test]
+    fn top_similar_words_are_sorted_and_match_the_predicate() {
+        let model = model(&[
+            ("cat", &[1.0, 0.0, 0.0]),
+            ("tiger", &[0.9, 0.1, 0.0]),
+            ("toad", &[0.5, 0.5, 0.0]),
+            ("tuna", &[0.7, 0.0, 0.3]),
+            ("tea", &[0.0, 0.0, 1.0]),
+            ("tabby", &[0.95, 0.0, 0.05]),
+        ]);
+
+        let top =
+            get_top_similar_words_in(&model, "cat", 't', Chaining::Strict, 3, |w| w != "tabby")
+                .unwrap();
+        let words = top.iter().map(|(w, _)| w.as_str()).collect::<Vec<_>>();
+        assert_eq!(words, ["tiger", "tuna", "toad"]);
+        assert!(top.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+}
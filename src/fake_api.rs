@@ -0,0 +1,127 @@
+//! Stand-in for the Telegram Bot API in tests. It answers every request the bot makes and
+//! records the text of each message the bot sends.
+
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use teloxide::types::Me;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The bot's own details, as getMe reports them
+pub fn me() -> Me {
+    serde_json::from_value(json!({
+        "id": 1,
+        "is_bot": true,
+        "first_name": "Kotosume",
+        "username": "kotosume_bot",
+        "can_join_groups": true,
+        "can_read_all_group_messages": false,
+        "supports_inline_queries": false,
+    }))
+    .unwrap()
+}
+
+/// A player's text message in a private chat
+pub fn player_message(id: i32, chat_id: i64, text: &str) -> Message {
+    serde_json::from_value(json!({
+        "message_id": id,
+        "date": 0,
+        "chat": { "id": chat_id, "type": "private", "first_name": "Player" },
+        "from": { "id": chat_id, "is_bot": false, "first_name": "Player" },
+        "text": text,
+    }))
+    .unwrap()
+}
+
+/// Local server the bot is pointed at instead of Telegram
+pub struct FakeBotApi {
+    url: Url,
+    sent: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeBotApi {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let log = sent.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(answer(socket, log.clone()));
+            }
+        });
+        FakeBotApi {
+            url: url.parse().unwrap(),
+            sent,
+        }
+    }
+
+    /// A bot that talks to this server. Its requests never time out, so tests may pause the
+    /// clock while it waits for an answer.
+    pub fn bot(&self) -> Bot {
+        Bot::with_client("0:test", reqwest::Client::new()).set_api_url(self.url.clone())
+    }
+
+    /// Text of every message sent so far, oldest first
+    pub fn sent(&self) -> Vec<String> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+/// Read one request and answer it: getMe with the bot's details, anything else with a message
+async fn answer(mut socket: TcpStream, sent: Arc<Mutex<Vec<String>>>) {
+    let mut request = Vec::new();
+    let mut buf = [0; 4096];
+    // Read the headers, then as much body as they announce
+    let body_start = loop {
+        let Ok(n) = socket.read(&mut buf).await else {
+            return;
+        };
+        if n == 0 {
+            return;
+        }
+        request.extend_from_slice(&buf[..n]);
+        if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+    let length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while request.len() < body_start + length {
+        match socket.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+    let body: Value = serde_json::from_slice(&request[body_start..]).unwrap_or(Value::Null);
+    let method = head.split_whitespace().nth(1).unwrap_or_default();
+
+    let result = if method.ends_with("/getme") {
+        serde_json::to_value(me()).unwrap()
+    } else {
+        let text = body["text"].as_str().unwrap_or_default().to_string();
+        if method.ends_with("/sendmessage") {
+            sent.lock().unwrap().push(text.clone());
+        }
+        json!({
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": body["chat_id"].as_i64().unwrap_or(1), "type": "private" },
+            "text": text,
+        })
+    };
+    let reply = json!({ "ok": true, "result": result }).to_string();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.len(),
+        reply
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+}
@@ -0,0 +1,83 @@
+//! Stand-in for the live dictionary and the embeddings in tests. The vocabulary is a handful
+//! of words: those the dictionary knows look up as a single sense, and any other word fails
+//! as if the dictionary were unreachable. Every lookup is counted.
+
+use crate::dictionary::{init_empty_cache, use_provider, Def, DictionaryError};
+use crate::embeddings::{use_embeddings, EmbeddingMap};
+use crate::providers::{DictionaryProvider, Lookup, LookupFuture};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Every word of the test model. A few of them start with 'r', so the bot has several
+/// replies to "tiger" to try.
+const VOCABULARY: &[&str] = &[
+    "tiger", "walrus", "rabbit", "raven", "reindeer", "rhino", "robin", "rook",
+];
+
+/// Words the dictionary has an entry for
+const KNOWN: &[&str] = &["tiger", "walrus"];
+
+/// The one sense every known word is looked up with
+pub const SENSE: &str = "a word the test dictionary knows";
+
+static LOOKUPS: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+
+fn get_lookups() -> &'static Mutex<HashMap<String, usize>> {
+    LOOKUPS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Look words up in the fake dictionary and choose them from the test model, with an empty
+/// word cache. Safe to call from every test that needs it.
+pub fn install() {
+    init_empty_cache();
+    use_provider(Box::new(FakeDictionary));
+    let mut model = EmbeddingMap::new();
+    for (i, word) in VOCABULARY.iter().enumerate() {
+        model
+            .entry(word.chars().next().unwrap())
+            .or_default()
+            .insert(word.to_string(), vec![1.0, i as f64]);
+    }
+    use_embeddings(model);
+}
+
+/// How many times `word` has been looked up
+pub fn lookups(word: &str) -> usize {
+    get_lookups()
+        .lock()
+        .unwrap()
+        .get(word)
+        .copied()
+        .unwrap_or(0)
+}
+
+struct FakeDictionary;
+
+impl DictionaryProvider for FakeDictionary {
+    fn name(&self) -> &'static str {
+        "the test dictionary"
+    }
+
+    fn lookup<'a>(&'a self, word: &'a str) -> LookupFuture<'a> {
+        *get_lookups()
+            .lock()
+            .unwrap()
+            .entry(word.to_string())
+            .or_default() += 1;
+        Box::pin(async move {
+            if !KNOWN.contains(&word) {
+                return Err(DictionaryError::ApiError("connection refused".to_string()));
+            }
+            Ok(Lookup {
+                defs: vec![Def {
+                    definitions: vec![SENSE.to_string()],
+                    functional_label: "noun".to_string(),
+                    examples: Vec::new(),
+                }],
+                stems: vec![word.to_string()],
+                headword: None,
+                audio: None,
+            })
+        })
+    }
+}
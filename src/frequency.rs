@@ -0,0 +1,111 @@
+use crate::embeddings::open_text;
+use crate::settings::get_settings;
+use log::{info, warn};
+use rand::prelude::IteratorRandom;
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+/// Rank above which a word counts as rare
+pub const RARE_RANK: u32 = 20_000;
+
+static FREQUENCIES: OnceLock<HashMap<String, u32>> = OnceLock::new();
+
+/// Loads word ranks from a file whose lines start with a word.
+///
+/// With `rank_column`, a second `rank` field is used when present; otherwise (and when it's
+/// missing or unparseable) the line number is the rank, so any list sorted by frequency works.
+fn load(file_name: &str, rank_column: bool) -> io::Result<HashMap<String, u32>> {
+    let reader = open_text(file_name)?;
+    let mut ranks = HashMap::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let mut fields = line.split_whitespace();
+        let Some(word) = fields.next() else {
+            continue;
+        };
+        let rank = fields
+            .next()
+            .filter(|_| rank_column)
+            .and_then(|r| r.parse::<u32>().ok())
+            .unwrap_or(idx as u32 + 1);
+        ranks.entry(word.to_lowercase()).or_insert(rank);
+    }
+
+    Ok(ranks)
+}
+
+/// Initializes the frequency table from `FREQUENCY_FILE`, falling back to the
+/// embeddings file's line order as a crude rank
+pub fn init_frequencies() {
+    let ranks = match &get_settings().frequency_file {
+        Some(path) => match load(path, true) {
+            Ok(ranks) => {
+                info!("Loaded {} word frequencies from {}", ranks.len(), path);
+                Some(ranks)
+            }
+            Err(e) => {
+                warn!("Failed to load frequency file {}: {}", path, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let ranks = ranks.unwrap_or_else(|| match load(&get_settings().embeddings_file, false) {
+        Ok(ranks) => {
+            info!("Using embeddings line order as word frequency rank");
+            ranks
+        }
+        Err(e) => {
+            warn!("No word frequency data available: {}", e);
+            HashMap::new()
+        }
+    });
+
+    let _ = FREQUENCIES.set(ranks);
+}
+
+/// Frequency rank of a word (1 is the most common), if known
+pub fn frequency_rank(word: &str) -> Option<u32> {
+    FREQUENCIES.get()?.get(word).copied()
+}
+
+/// Whether any frequency data was loaded
+fn has_ranks() -> bool {
+    FREQUENCIES.get().is_some_and(|ranks| !ranks.is_empty())
+}
+
+/// Whether a word is rare: ranked beyond `RARE_RANK`, or absent from a loaded frequency table
+pub fn is_rare(word: &str) -> bool {
+    has_ranks() && frequency_rank(word).is_none_or(|r| r > RARE_RANK)
+}
+
+/// Bonus points for playing a less common word: none for the thousand commonest words, rising
+/// to 3 for rare ones. Without frequency data no word earns a bonus.
+pub fn rarity_bonus(word: &str) -> u32 {
+    if !has_ranks() {
+        return 0;
+    }
+    match frequency_rank(word) {
+        Some(rank) if rank <= 1_000 => 0,
+        Some(rank) if rank <= 5_000 => 1,
+        Some(rank) if rank <= RARE_RANK => 2,
+        _ => 3,
+    }
+}
+
+/// A random word whose rank lies in `ranks` and that satisfies `predicate`, if the table has one
+pub fn random_word_ranked<P>(ranks: RangeInclusive<u32>, predicate: P) -> Option<String>
+where
+    P: Fn(&str) -> bool,
+{
+    FREQUENCIES
+        .get()?
+        .iter()
+        .filter(|(word, rank)| ranks.contains(rank) && predicate(word))
+        .choose(&mut rand::rng())
+        .map(|(word, _)| word.clone())
+}
@@ -1,10 +1,35 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
-use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, EmbeddingError};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{
+    count_candidates, get_top_similar_words, letter_pool_size, Chaining, EmbeddingError,
+};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    bot_concedes, count_player_word, finish_game, game_started, reach_word_target, score_extras,
+    skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, begin_processing, carry_on_message, credit_player_word,
+    define_chain_word, is_healthy_letter, is_just_played, just_played_message, looks_like_command,
+    normalize_word, reject_word, reply_unknown_command, reseed_opener, retry_turn, show_dead_ends,
+    show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
 use crate::state::MyDialogue;
-use crate::state::State::{AlphabetSprint, Start};
-use log::{error, info, warn};
+use crate::state::State::AlphabetSprint;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::IndexedRandom;
 use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
 use teloxide::types::Me;
 use teloxide::utils::command::BotCommands;
@@ -12,11 +37,12 @@ use teloxide::Bot;
 
 /// Error type specific to Alphabet Sprint game
 #[derive(Debug)]
-enum AlphabetSprintError {
+pub enum AlphabetSprintError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
-    InvalidInput(String),
     NoValidWords(String),
+    /// Fewer than `EXHAUSTED_POOL` unplayed words start with the sprint's letter
+    Exhausted(usize),
 }
 
 impl From<DictionaryError> for AlphabetSprintError {
@@ -31,24 +57,100 @@ impl From<EmbeddingError> for AlphabetSprintError {
     }
 }
 
+impl AlphabetSprintError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            AlphabetSprintError::Dictionary(e) => e.is_transient(),
+            AlphabetSprintError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for AlphabetSprintError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             AlphabetSprintError::Dictionary(e) => write!(f, "Dictionary error: {}", e),
             AlphabetSprintError::Embedding(e) => write!(f, "Embedding error: {}", e),
-            AlphabetSprintError::InvalidInput(msg) => write!(f, "{}", msg),
             AlphabetSprintError::NoValidWords(msg) => write!(f, "{}", msg),
+            AlphabetSprintError::Exhausted(remaining) => {
+                write!(
+                    f,
+                    "Only {} unplayed words start with this letter",
+                    remaining
+                )
+            }
         }
     }
 }
 
+/// Menu id of this game
+pub const GAME: &str = "alphabet_sprint";
+
+/// Every form of the words played so far: each word as played, and its dictionary stems
+fn played_forms(words: &[WordInfo]) -> Vec<String> {
+    words
+        .iter()
+        .flat_map(|w| std::iter::once(&w.word).chain(&w.stems))
+        .cloned()
+        .collect()
+}
+
+/// Forms a word could be the plural of, e.g. "box" and "boxe" for "boxes"
+fn singulars(word: &str) -> impl Iterator<Item = &str> {
+    ["s", "es"]
+        .into_iter()
+        .filter_map(move |suffix| word.strip_suffix(suffix))
+        .filter(|singular| !singular.is_empty())
+}
+
+/// Whether a word was already played, as it is or as the singular or plural of a played form.
+/// Dictionaries don't always list a word's plural among its stems, so the plural is checked
+/// here too.
+fn is_played(played: &[String], word: &str) -> bool {
+    played.iter().any(|form| {
+        form == word || singulars(word).any(|s| s == form) || singulars(form).any(|s| s == word)
+    })
+}
+
+/// Whether looked-up word details match a word already played, by the word or any of its stems
+fn details_played(played: &[String], details: &WordInfo) -> bool {
+    std::iter::once(&details.word)
+        .chain(&details.stems)
+        .any(|form| is_played(played, form))
+}
+
+/// The sprint ends once fewer unplayed words than this start with its letter
+const EXHAUSTED_POOL: usize = 10;
+
+/// The number of unplayed words left for the sprint's letter, when it has nearly run out
+fn nearly_exhausted(alphabet: char, used_words: &[String]) -> Option<usize> {
+    letter_pool_size(alphabet, |w| {
+        is_playable(w) && !used_words.iter().any(|u| u == w)
+    })
+    .ok()
+    .filter(|&remaining| remaining < EXHAUSTED_POOL)
+}
+
+/// Wrap up a sprint whose letter has nearly run out of words
+fn exhausted_message(alphabet: char, words: &[WordInfo]) -> String {
+    format!(
+        "We've nearly exhausted '{}' words — great sprint! {} words played this round, {} of them yours.",
+        alphabet,
+        words.len(),
+        words.len() / 2
+    )
+}
+
 /// Start a new Alphabet Sprint game
 pub async fn start_alphabet_sprint(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
 ) -> ResponseResult<()> {
-    info!("Starting Alphabet Sprint game for chat {}", chat_id);
+    info!(chat_id = chat_id.0, game = GAME; "Starting Alphabet Sprint game");
 
     bot.send_message(
         chat_id,
@@ -56,44 +158,56 @@ pub async fn start_alphabet_sprint(
     )
     .await?;
 
+    // Sprint on a letter with more words than would already count as nearly exhausted, or any
+    // letter if none has that many
+    let roomy = ('a'..='z')
+        .filter(|&c| count_candidates(c, is_playable) >= EXHAUSTED_POOL)
+        .collect::<Vec<char>>();
+
     // Try to get a random word to start the game
     for _ in 0..3 {
         // Try up to 3 times
-        match get_random_word(|_| true, None).await {
+        let letter = roomy.choose(&mut rand::rng()).copied();
+        match get_random_word(|_| true, letter).await {
             Ok(word) => {
                 let start_char = match word.word.chars().next() {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                info!("Alphabet Sprint started with letter: {}", start_char);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Alphabet Sprint started with letter: {}",
+                    start_char
+                );
+
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
-                bot.send_message(chat_id, format!("First word: {}", word.word))
+                bot.send_message(chat_id, say(chat_id, Phrase::YourTurn(start_char)))
                     .await?;
-                word.send_message(&bot, chat_id, 0).await?;
-
-                bot.send_message(
-                    chat_id,
-                    format!("Now give a word starting with '{}'", start_char),
-                )
-                .await?;
 
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(AlphabetSprint {
                         words: vec![word.clone()],
                         alphabet: start_char,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
                 // Try again
             }
         }
@@ -113,36 +227,97 @@ pub async fn start_alphabet_sprint(
 pub async fn alphabet_sprint(
     bot: Bot,
     dialogue: MyDialogue,
-    (alphabet, words): (char, Vec<WordInfo>),
+    (alphabet, words, mut progress): (char, Vec<WordInfo>, Progress),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
+            Ok(Command::Hint(_)) => {
                 provide_hint(&bot, msg.chat.id, alphabet, &words).await?;
             }
             Ok(Command::Skip) => {
-                skip_turn(&bot, msg.chat.id, dialogue, words, alphabet).await?;
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
+                skip_turn(&bot, msg.chat.id, dialogue, words, alphabet, progress).await?;
             }
             Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &words).await?;
+                show_score(&bot, msg.chat.id, &words, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &words, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id).await?;
+                show_rules(&bot, msg.chat.id, alphabet, words.len()).await?;
             }
             Ok(Command::Stop) => {
-                info!(
-                    "Player stopped Alphabet Sprint game in chat {}",
-                    msg.chat.id
-                );
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Alphabet Sprint game");
 
                 // Show final score
                 let player_words = words.len() / 2;
@@ -160,13 +335,28 @@ pub async fn alphabet_sprint(
 
                 bot.send_message(
                     msg.chat.id,
-                    "Alphabet Sprint game stopped. Thanks for playing!",
+                    say(msg.chat.id, Phrase::GameStopped("Alphabet Sprint")),
                 )
                 .await?;
-                let _ = dialogue.update(Start).await;
+                let outcome = GameOutcome::new(&progress, GAME, None, &words);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
             }
             Err(_) => {
-                process_player_word(text, bot, dialogue, words, alphabet, msg.chat.id).await?;
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(
+                        text,
+                        bot,
+                        dialogue,
+                        words,
+                        alphabet,
+                        progress,
+                        msg.chat.id,
+                    )
+                    .await?;
+                }
             }
         },
         None => {
@@ -183,6 +373,7 @@ async fn process_player_word(
     dialogue: MyDialogue,
     mut chain: Vec<WordInfo>,
     alphabet: char,
+    mut progress: Progress,
     chat_id: ChatId,
 ) -> ResponseResult<()> {
     let words = text.split_whitespace().collect::<Vec<&str>>();
@@ -199,49 +390,77 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
 
     // Check if word starts with the current alphabet
     if !word.starts_with(alphabet) {
-        bot.send_message(chat_id, format!("Your word must start with '{}'", alphabet))
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(alphabet)))
             .await?;
         return Ok(());
     }
 
-    // Get list of already used words/stems
-    let used_stems = chain
-        .iter()
-        .flat_map(|x| x.stems.clone())
-        .collect::<Vec<String>>();
+    // Every form of the words played so far, the bot's latest reply included
+    let used_stems = played_forms(&chain);
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
-            if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+            if details_played(&used_stems, &word_details) {
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!("Player used word: {} in chat {}", word, chat_id);
-            let mut updated_stems = used_stems.clone();
-            updated_stems.push(word.clone());
-
-            word_details.send_message(&bot, chat_id, 0).await?;
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
             chain.push(word_details.clone());
+            // The bot's reply mustn't be a form of any word played, the player's new one included
+            let updated_stems = played_forms(&chain);
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
 
             // Get the bot's response word
-            match get_bot_response(&word, &updated_stems, alphabet).await {
+            let response = get_bot_response(
+                &word_details.word,
+                &updated_stems,
+                alphabet,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
+                    announce_bot_word(
+                        &bot,
+                        chat_id,
+                        Phrase::BotWord(&next_word_details.word),
+                        &next_word_details,
+                    )
+                    .await?;
 
                     // Prompt for the next word
                     bot.send_message(
@@ -252,32 +471,80 @@ async fn process_player_word(
                         ),
                     )
                     .await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
 
                     // Update game state
                     let _ = dialogue
                         .update(AlphabetSprint {
                             alphabet,
                             words: chain,
+                            progress,
                         })
                         .await;
                 }
+                Err(AlphabetSprintError::Exhausted(remaining)) => {
+                    info!(
+                        chat_id = chat_id.0, game = GAME;
+                        "Alphabet Sprint on '{}' nearly exhausted ({} words left)",
+                        alphabet, remaining
+                    );
+                    bot.send_message(chat_id, exhausted_message(alphabet, &chain))
+                        .await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
+                }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(chat_id, "I can't think of a word! You win this round!")
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // A fresh sprint gets a letter with plenty of words to race through
+                    if let Some(opener) = reseed_opener(&bot, chat_id, &chain, &mut progress, |w| {
+                        w.chars().next().is_some_and(is_healthy_letter)
+                    })
+                    .await?
+                    {
+                        let alphabet = opener.word.chars().next().unwrap_or(alphabet);
+                        bot.send_message(chat_id, say(chat_id, Phrase::YourTurn(alphabet)))
+                            .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(AlphabetSprint {
+                                alphabet,
+                                words: vec![opener],
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give another word starting with '{}'",
+                                alphabet
+                            )),
+                        )
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(AlphabetSprint {
+                                alphabet,
+                                words: chain,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    bot.send_message(chat_id, say(chat_id, Phrase::BotStumped("a word")))
+                        .await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(alphabet)
+            })
             .await?;
         }
     }
@@ -286,40 +553,55 @@ async fn process_player_word(
 }
 
 /// Get the bot's response word starting with the same alphabet
-async fn get_bot_response(
+pub async fn get_bot_response(
     player_word: &str,
     used_words: &[String],
     alphabet: char,
+    difficulty: Difficulty,
 ) -> Result<WordInfo, AlphabetSprintError> {
+    if let Some(remaining) = nearly_exhausted(alphabet, used_words) {
+        return Err(AlphabetSprintError::Exhausted(remaining));
+    }
     let mut used_words = used_words.to_vec();
 
     // Get a similar word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
         // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, alphabet, |x| {
-            !used_words.contains(&x.to_string())
-        });
+        let next_word_result = get_top_similar_words(
+            player_word,
+            alphabet,
+            Chaining::Strict,
+            difficulty.choices(),
+            |x| is_playable(x) && !is_played(&used_words, x),
+        )
+        .and_then(|words| difficulty.choose(words));
 
         match next_word_result {
             Ok(word) => {
                 // Try to get details for this word
                 match get_word_details(&word).await {
                     Ok(details) => {
-                        if contains_any(&used_words, &details.stems) {
+                        if details_played(&used_words, &details) {
                             used_words.extend(details.stems.clone());
+                            used_words.push(word);
                             continue;
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
-                        continue
-                    }, // Try another word
+                        continue;
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -331,6 +613,9 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(AlphabetSprintError::Dictionary(e));
+    }
     Err(AlphabetSprintError::NoValidWords(format!(
         "Could not find a valid word starting with '{}'",
         alphabet
@@ -344,34 +629,29 @@ async fn provide_hint(
     alphabet: char,
     words: &[WordInfo],
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
 
-    let used_stems = words
-        .iter()
-        .flat_map(|x| x.stems.clone())
-        .collect::<Vec<String>>();
+    let used_stems = played_forms(words);
 
     // Get a random word starting with the current alphabet (not used before)
-    match get_random_word(|w| !used_stems.contains(&w.to_string()), Some(alphabet)).await {
+    match get_random_word(|w| !is_played(&used_stems, w), Some(alphabet)).await {
         Ok(hint) => {
-            bot.send_message(
-                chat_id,
-                format!(
-                    "Hint: You could try a word like '{}' or something similar.",
-                    hint.word
-                ),
-            )
-            .await?;
+            bot.send_message(chat_id, hint_message(chat_id, &hint.word))
+                .await?;
         }
         Err(_) => {
-            bot.send_message(
-                chat_id,
-                format!(
+            let text = match nearly_exhausted(alphabet, &used_stems) {
+                Some(remaining) => format!(
+                    "We've nearly exhausted '{}' words — only {} left that haven't been played. \
+                    Find one if you can, or /stop to end on a high note!",
+                    alphabet, remaining
+                ),
+                None => format!(
                     "I can't think of a hint right now. Just try any word starting with '{}'.",
                     alphabet
                 ),
-            )
-            .await?;
+            };
+            bot.send_message(chat_id, text).await?;
         }
     }
 
@@ -379,29 +659,26 @@ async fn provide_hint(
 }
 
 /// Skip the current turn
-async fn skip_turn(
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
     mut words: Vec<WordInfo>,
     alphabet: char,
+    mut progress: Progress,
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
-    let used_stems = words
-        .iter()
-        .flat_map(|x| x.stems.clone())
-        .collect::<Vec<String>>();
+    let used_stems = played_forms(&words);
 
     // Try to get a word for the bot
-    match get_random_word(|w| !used_stems.contains(&w.to_string()), Some(alphabet)).await {
+    match get_random_word(|w| !is_played(&used_stems, w), Some(alphabet)).await {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
             words.push(word.clone());
 
             bot.send_message(
@@ -410,16 +687,21 @@ async fn skip_turn(
             )
             .await?;
 
-            let _ = dialogue.update(AlphabetSprint { alphabet, words }).await;
+            next_player(bot, chat_id, &mut progress).await?;
+            let _ = dialogue
+                .update(AlphabetSprint {
+                    alphabet,
+                    words,
+                    progress,
+                })
+                .await;
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &words);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
@@ -427,33 +709,81 @@ async fn skip_turn(
 }
 
 /// Show the current score (word count)
-async fn show_score(bot: &Bot, chat_id: ChatId, words: &[WordInfo]) -> ResponseResult<()> {
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    words: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
     let player_words = words.len() / 2;
     let bot_words = words.len() - player_words;
 
-    bot.send_message(
-        chat_id,
-        format!(
-            "Current score:\nYou: {} words\nBot: {} words",
-            player_words, bot_words
-        ),
-    )
-    .await?;
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, words.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
 
     Ok(())
 }
 
 /// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    alphabet: char,
+    word_count: usize,
+) -> ResponseResult<()> {
     bot.send_message(
         chat_id,
-        "Alphabet Sprint Rules:\n\
-        1. We'll focus on words starting with the same letter\n\
-        2. Take turns giving words that start with that letter\n\
-        3. No repeating words\n\
-        4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
+        format!(
+            "Alphabet Sprint Rules:\n\
+            1. Every word this round must start with '{}'\n\
+            2. Take turns giving words that start with that letter ({} played so far)\n\
+            3. No repeating words\n\
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            alphabet, word_count
+        ),
     )
     .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, stems: &[&str]) -> WordInfo {
+        WordInfo {
+            word: word.to_string(),
+            stems: stems.iter().map(|s| s.to_string()).collect(),
+            defs: Vec::new(),
+            audio: None,
+        }
+    }
+
+    #[test]
+    fn plural_of_the_bots_reply_is_already_played() {
+        // The player submits, then the bot replies; neither entry lists its plural as a stem
+        let mut chain = vec![word("box", &["box"])];
+        assert!(!details_played(
+            &played_forms(&chain),
+            &word("bus", &["bus"])
+        ));
+        chain.push(word("bus", &["bus"]));
+
+        let played = played_forms(&chain);
+        assert!(details_played(&played, &word("buses", &["buses"])));
+        assert!(details_played(&played, &word("boxes", &["boxes"])));
+        assert!(is_played(&played, "boxes"));
+        assert!(!is_played(&played, "bush"));
+    }
+}
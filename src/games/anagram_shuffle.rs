@@ -0,0 +1,530 @@
+use crate::achievements::{on_player_word, show_badges};
+use crate::command::Command;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_random_word, get_submission_details, get_word_details, WordInfo};
+use crate::frequency::is_rare;
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::{
+    count_player_word, finish_game, game_started, reach_word_target, score_extras, GameOutcome,
+    Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    accept_submission, begin_processing, looks_like_command, normalize_word, reject_word,
+    reply_unknown_command, show_dead_ends, show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::state::MyDialogue;
+use crate::state::State::AnagramShuffle;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::SliceRandom;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "anagram_shuffle";
+
+/// Shortest and longest words the letters are drawn from
+const MIN_LETTERS: usize = 5;
+const MAX_LETTERS: usize = 7;
+
+/// Letters of a word in sorted order: two words are anagrams when these match
+fn letter_set(word: &str) -> Vec<char> {
+    let mut letters = word.chars().collect::<Vec<char>>();
+    letters.sort_unstable();
+    letters
+}
+
+/// Shuffle a word's letters, avoiding the word itself when its letters allow another order
+fn scramble(word: &str) -> String {
+    let mut letters = word.chars().collect::<Vec<char>>();
+    for _ in 0..10 {
+        letters.shuffle(&mut rand::rng());
+        if letters.iter().collect::<String>() != word {
+            break;
+        }
+    }
+    letters.into_iter().collect()
+}
+
+/// Scrambled letters spaced out for display, e.g. "T A L P E"
+fn show_letters(scrambled: &str) -> String {
+    scrambled
+        .to_uppercase()
+        .chars()
+        .map(String::from)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Words found so far that use the given letters
+fn found_in_round<'a>(solved: &'a [String], target: &WordInfo) -> Vec<&'a str> {
+    let letters = letter_set(&target.word);
+    solved
+        .iter()
+        .filter(|w| letter_set(w) == letters)
+        .map(String::as_str)
+        .collect()
+}
+
+/// Pick a new word and deal its scrambled letters, keeping the words found so far
+async fn deal_letters(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    solved: Vec<String>,
+    progress: Progress,
+) -> ResponseResult<bool> {
+    for _ in 0..3 {
+        let word = get_random_word(
+            |w| {
+                (MIN_LETTERS..=MAX_LETTERS).contains(&w.len())
+                    && w.chars().all(|c| c.is_ascii_lowercase())
+                    && !is_rare(w)
+                    && !solved.iter().any(|s| s == w)
+            },
+            None,
+        )
+        .await;
+        match word {
+            Ok(target) => {
+                let scrambled = scramble(&target.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Anagram Shuffle dealt '{}' for '{}'",
+                    scrambled, target.word
+                );
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "🔀 Your letters: {}\n\nFind as many words as you can that use all of them.",
+                        show_letters(&scrambled)
+                    ),
+                )
+                .await?;
+                let _ = dialogue
+                    .update(AnagramShuffle {
+                        target,
+                        scrambled,
+                        solved,
+                        progress,
+                    })
+                    .await;
+                return Ok(true);
+            }
+            Err(e) => {
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e)
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Start a new Anagram Shuffle game
+pub async fn start_anagram_shuffle(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Anagram Shuffle game");
+
+    bot.send_message(
+        chat_id,
+        "Anagram Shuffle! I'll scramble a word's letters — rearrange them into real words. Every word you find scores a point; /skip when you've found them all to get new letters.",
+    )
+    .await?;
+
+    if deal_letters(&bot, chat_id, dialogue, Vec::new(), Progress::default()).await? {
+        game_started(&bot, chat_id).await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            "Sorry, I'm having trouble starting the game. Please try again later.",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Handle player input during Anagram Shuffle game
+pub async fn anagram_shuffle(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (target, scrambled, solved, progress): (WordInfo, String, Vec<String>, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &target, &solved).await?;
+            }
+            Ok(Command::Skip) => {
+                skip_turn(&bot, msg.chat.id, dialogue, target, solved, progress).await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &target, &solved, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_found_word(&bot, msg.chat.id, &solved, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &scrambled).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Anagram Shuffle game");
+
+                bot.send_message(
+                    msg.chat.id,
+                    format!(
+                        "Game finished! The last letters spelled '{}'.\nYou found {} words: {}",
+                        target.word,
+                        solved.len(),
+                        if solved.is_empty() {
+                            "none".to_string()
+                        } else {
+                            solved.join(", ")
+                        }
+                    ),
+                )
+                .await?;
+
+                bot.send_message(
+                    msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Anagram Shuffle")),
+                )
+                .await?;
+                let outcome = outcome(&progress, None, &solved);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                process_player_word(
+                    text,
+                    bot,
+                    dialogue,
+                    (target, scrambled, solved, progress),
+                    msg.chat.id,
+                )
+                .await?;
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a game in which the player found the given words; the bot plays none
+pub fn outcome(progress: &Progress, winner: Option<Winner>, solved: &[String]) -> GameOutcome {
+    GameOutcome {
+        words: solved.to_vec(),
+        ..GameOutcome::new(progress, GAME, winner, &[])
+    }
+    .with_scores(solved.len(), 0)
+}
+
+/// Process a player's word submission
+async fn process_player_word(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (target, scrambled, mut solved, mut progress): (WordInfo, String, Vec<String>, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if words.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if words.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(words[0]);
+
+    if letter_set(&word) != letter_set(&scrambled) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "'{}' doesn't use exactly the letters {}.",
+                word,
+                show_letters(&scrambled)
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if solved.contains(&word) {
+        bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+            .await?;
+        return Ok(());
+    }
+
+    // Validate the player's word
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            debug!(chat_id = chat_id.0, game = GAME; "Player found anagram: {}", word);
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            solved.push(word.clone());
+            accept_submission(chat_id);
+            on_player_word(&bot, chat_id, &word_details.word, solved.len()).await?;
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                let outcome = outcome(&progress, Some(Winner::Player), &solved);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            let found = found_in_round(&solved, &target).len();
+            bot.send_message(
+                chat_id,
+                format!(
+                    "✅ '{}' — {} found from these letters. Any more? /skip for new letters.",
+                    word, found
+                ),
+            )
+            .await?;
+
+            let _ = dialogue
+                .update(AnagramShuffle {
+                    target,
+                    scrambled,
+                    solved,
+                    progress,
+                })
+                .await;
+        }
+        Err(e) => {
+            reject_word(&bot, chat_id, &word, &e, &solved, |_| true).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Give the player a hint: the word the letters were drawn from, unless they've found it
+async fn provide_hint(
+    bot: &Bot,
+    chat_id: ChatId,
+    target: &WordInfo,
+    solved: &[String],
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    if solved.contains(&target.word) {
+        bot.send_message(
+            chat_id,
+            "You've already found the word I scrambled. Any other arrangement is a bonus — or /skip for new letters.",
+        )
+        .await?;
+    } else {
+        bot.send_message(chat_id, hint_message(chat_id, &target.word))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Reveal the word behind the letters and deal new ones
+pub async fn skip_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    target: WordInfo,
+    solved: Vec<String>,
+    progress: Progress,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped letters");
+
+    let found = found_in_round(&solved, &target).len();
+    bot.send_message(
+        chat_id,
+        if solved.contains(&target.word) {
+            format!("New letters! You found {} words from the last ones.", found)
+        } else {
+            format!(
+                "The letters spelled '{}'. You found {} words from them.",
+                target.word, found
+            )
+        },
+    )
+    .await?;
+
+    if !deal_letters(
+        bot,
+        chat_id,
+        dialogue.clone(),
+        solved.clone(),
+        progress.clone(),
+    )
+    .await?
+    {
+        bot.send_message(chat_id, "I'm out of letters to deal, so that's the game!")
+            .await?;
+        let outcome = outcome(&progress, None, &solved);
+        finish_game(bot, chat_id, dialogue, outcome).await?;
+    }
+
+    Ok(())
+}
+
+/// Show a found word's definition
+async fn define_found_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    solved: &[String],
+    args: &str,
+) -> ResponseResult<()> {
+    let word = normalize_word(args.trim());
+    if !solved.contains(&word) {
+        bot.send_message(
+            chat_id,
+            "Usage: /def <word> — shows the definition of a word you've found in this game.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match get_word_details(&word).await {
+        Ok(details) => details.send_message(bot, chat_id, 0).await,
+        Err(e) => {
+            error!(
+                chat_id = chat_id.0, game = GAME;
+                "Error retrieving word details for '{}': {:?}",
+                word, e
+            );
+            bot.send_message(
+                chat_id,
+                format!("Sorry, I couldn't look up '{}' right now.", word),
+            )
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+/// Show the current game score
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    target: &WordInfo,
+    solved: &[String],
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let mut score = format!(
+        "Anagram Shuffle score:\nWords found: {} ({} from the current letters)",
+        solved.len(),
+        found_in_round(solved, target).len()
+    );
+    for extra in score_extras(progress, solved.iter().map(String::as_str)) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, scrambled: &str) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Anagram Shuffle rules:\n\
+            1. I scramble the letters of a word\n\
+            2. Find real words that use every letter exactly once\n\
+            3. Each word you find scores a point — the same letters often make several words\n\
+            4. /skip reveals my word and deals new letters\n\
+            5. /hint helps you towards my word\n\n\
+            Your letters: {}",
+            show_letters(scrambled)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
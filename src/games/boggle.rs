@@ -0,0 +1,705 @@
+use crate::achievements::{on_player_word, show_badges};
+use crate::command::Command;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_submission_details, get_word_details, is_playable};
+use crate::embeddings::{words_with_prefix, EmbeddingError};
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::{
+    count_player_word, finish_game, game_started, reach_word_target, score_extras, GameOutcome,
+    Progress, Winner,
+};
+use crate::games::turn_timer::arm_game_clock;
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    accept_submission, begin_processing, looks_like_command, normalize_word, reply_unknown_command,
+    show_dead_ends, show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::persist::unix_now;
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::state::MyDialogue;
+use crate::state::State::Boggle;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::{IndexedRandom, IteratorRandom};
+use std::collections::BTreeSet;
+use std::time::Duration;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::{Me, ParseMode};
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "boggle";
+
+/// Rows and columns in the grid
+const GRID_SIZE: usize = 4;
+/// Shortest word that can be played
+const MIN_WORD_LEN: usize = 3;
+/// How long the player has to find words
+const ROUND_TIME: Duration = Duration::from_secs(180);
+/// Time left when the player is told the round is nearly over
+pub const WARN_AT: Duration = Duration::from_secs(30);
+/// Points that make a round a win
+const WINNING_POINTS: u32 = 10;
+/// Words the grid has to hold for it to be dealt, when one can be found within `GRID_ATTEMPTS`
+const MIN_GRID_WORDS: usize = 25;
+/// Grids tried in search of one with enough words
+const GRID_ATTEMPTS: usize = 10;
+/// Words shown at the end of the round that the player missed
+const MISSED_SHOWN: usize = 5;
+
+/// How often each letter turns up in English text, in occurrences per thousand letters
+const LETTER_WEIGHTS: [(char, u32); 26] = [
+    ('e', 127),
+    ('t', 91),
+    ('a', 82),
+    ('o', 75),
+    ('i', 70),
+    ('n', 67),
+    ('s', 63),
+    ('h', 61),
+    ('r', 60),
+    ('d', 43),
+    ('l', 40),
+    ('c', 28),
+    ('u', 28),
+    ('m', 24),
+    ('w', 24),
+    ('f', 22),
+    ('g', 20),
+    ('y', 20),
+    ('p', 19),
+    ('b', 15),
+    ('v', 10),
+    ('k', 8),
+    ('j', 2),
+    ('x', 2),
+    ('q', 1),
+    ('z', 1),
+];
+
+/// Time left in a round that ends at `deadline` (Unix time in seconds)
+pub fn time_left(deadline: u64) -> Duration {
+    Duration::from_secs(deadline.saturating_sub(unix_now()))
+}
+
+/// Time left for display, e.g. "2:05"
+fn show_time(left: Duration) -> String {
+    format!("{}:{:02}", left.as_secs() / 60, left.as_secs() % 60)
+}
+
+/// Boggle's points for a word: 1 for three or four letters, 2 for five, 3 for six, 5 for
+/// seven and 11 for eight or more
+fn word_score(word: &str) -> u32 {
+    match word.chars().count() {
+        0..=4 => 1,
+        5 => 2,
+        6 => 3,
+        7 => 5,
+        _ => 11,
+    }
+}
+
+/// Total points for the words found
+fn total_score(found: &[String]) -> u32 {
+    found.iter().map(|w| word_score(w)).sum()
+}
+
+/// A grid of letters drawn by how often they turn up in English
+fn random_grid() -> Vec<Vec<char>> {
+    let mut rng = rand::rng();
+    (0..GRID_SIZE)
+        .map(|_| {
+            (0..GRID_SIZE)
+                .map(|_| {
+                    LETTER_WEIGHTS
+                        .choose_weighted(&mut rng, |&(_, weight)| weight)
+                        .map_or('e', |&(letter, _)| letter)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The grid as a monospaced MarkdownV2 code block, one row per line
+fn show_grid(grid: &[Vec<char>]) -> String {
+    let rows = grid
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| c.to_ascii_uppercase().to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("```\n{}\n```", rows)
+}
+
+/// Cells next to the given one, diagonals included
+fn neighbours(
+    grid: &[Vec<char>],
+    row: usize,
+    col: usize,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (-1isize..=1)
+        .flat_map(|dr| (-1isize..=1).map(move |dc| (dr, dc)))
+        .filter(|&step| step != (0, 0))
+        .filter_map(move |(dr, dc)| {
+            let r = row.checked_add_signed(dr)?;
+            let c = col.checked_add_signed(dc)?;
+            grid.get(r)?.get(c)?;
+            Some((r, c))
+        })
+}
+
+/// Whether `letters` can be traced starting at the given cell without reusing a cell
+fn trace_from(
+    grid: &[Vec<char>],
+    letters: &[char],
+    (row, col): (usize, usize),
+    used: &mut Vec<(usize, usize)>,
+) -> bool {
+    let Some((&first, rest)) = letters.split_first() else {
+        return true;
+    };
+    if grid[row][col] != first || used.contains(&(row, col)) {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+    used.push((row, col));
+    let traced = neighbours(grid, row, col).any(|cell| trace_from(grid, rest, cell, used));
+    used.pop();
+    traced
+}
+
+/// Whether a word can be traced through the grid: each letter next to the one before it,
+/// diagonals included, and no cell used twice
+fn can_trace(grid: &[Vec<char>], word: &str) -> bool {
+    let letters = word.chars().collect::<Vec<char>>();
+    let mut used = Vec::with_capacity(letters.len());
+    (0..grid.len())
+        .flat_map(|row| (0..grid[row].len()).map(move |col| (row, col)))
+        .any(|cell| trace_from(grid, &letters, cell, &mut used))
+}
+
+/// Words of plain letters in the vocabulary that can be traced through the grid and played.
+/// This scans every word starting with a grid letter, so it's only run when dealing the grid.
+fn grid_words(grid: &[Vec<char>]) -> Result<Vec<&'static str>, EmbeddingError> {
+    let letters = grid.iter().flatten().copied().collect::<BTreeSet<char>>();
+    let mut words = Vec::new();
+    for letter in letters {
+        words.extend(words_with_prefix(&letter.to_string(), |w| {
+            w.len() >= MIN_WORD_LEN
+                && w.chars().all(|c| c.is_ascii_lowercase())
+                && can_trace(grid, w)
+                && is_playable(w)
+        })?);
+    }
+    Ok(words)
+}
+
+/// A grid holding at least `MIN_GRID_WORDS` words, or the richest of the grids tried, with the
+/// words it holds
+fn deal_grid() -> Result<(Vec<Vec<char>>, Vec<String>), EmbeddingError> {
+    let mut best = (Vec::new(), Vec::new());
+    for _ in 0..GRID_ATTEMPTS {
+        let grid = random_grid();
+        let words = grid_words(&grid)?;
+        if words.len() >= MIN_GRID_WORDS {
+            return Ok((grid, words.into_iter().map(String::from).collect()));
+        }
+        if words.len() > best.1.len() {
+            best = (grid, words);
+        }
+    }
+    Ok((best.0, best.1.into_iter().map(String::from).collect()))
+}
+
+/// Send the grid with the time left in the round
+async fn send_grid(
+    bot: &Bot,
+    chat_id: ChatId,
+    grid: &[Vec<char>],
+    deadline: u64,
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "🔠 Your grid, {} left\n{}",
+            show_time(time_left(deadline)),
+            show_grid(grid)
+        ),
+    )
+    .parse_mode(ParseMode::MarkdownV2)
+    .await?;
+
+    Ok(())
+}
+
+/// Start a new Boggle game
+pub async fn start_boggle(chat_id: ChatId, bot: Bot, dialogue: MyDialogue) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Boggle game");
+
+    // Tracing the vocabulary through several grids takes a while, so it's kept off the runtime
+    let (grid, words) = match tokio::task::spawn_blocking(deal_grid).await {
+        Ok(Ok((grid, words))) if !grid.is_empty() => (grid, words),
+        result => {
+            match result {
+                Ok(Err(e)) => {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to deal a Boggle grid: {:?}", e)
+                }
+                Err(e) => {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to deal a Boggle grid: {}", e)
+                }
+                Ok(Ok(_)) => {}
+            }
+            bot.send_message(
+                chat_id,
+                "Sorry, I'm having trouble starting the game. Please try again later.",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Boggle! Find as many words as you can in the grid before time runs out. Trace each word through letters that touch, diagonals included, using each letter at most once per word. Words need at least {} letters, and longer ones score more. {} points wins.",
+            MIN_WORD_LEN, WINNING_POINTS
+        ),
+    )
+    .await?;
+
+    let deadline = unix_now() + ROUND_TIME.as_secs();
+    send_grid(&bot, chat_id, &grid, deadline).await?;
+    game_started(&bot, chat_id).await?;
+    arm_game_clock(chat_id, deadline);
+    let _ = dialogue
+        .update(Boggle {
+            grid,
+            words,
+            found: Vec::new(),
+            deadline,
+            progress: Progress::default(),
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Handle player input during Boggle game
+pub async fn boggle(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (grid, words, found, deadline, progress): (
+        Vec<Vec<char>>,
+        Vec<String>,
+        Vec<String>,
+        u64,
+        Progress,
+    ),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &words, &found).await?;
+            }
+            Ok(Command::Skip) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "There's nothing to skip in Boggle — keep finding words until the clock runs out, or /stop to end the round early.",
+                )
+                .await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &found, deadline, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_found_word(&bot, msg.chat.id, &found, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &grid, deadline).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Boggle game");
+
+                bot.send_message(msg.chat.id, summary(&words, &found))
+                    .await?;
+                bot.send_message(msg.chat.id, say(msg.chat.id, Phrase::GameStopped("Boggle")))
+                    .await?;
+                let outcome = outcome(&progress, None, &found);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                process_player_word(
+                    text,
+                    bot,
+                    dialogue,
+                    (grid, words, found, deadline, progress),
+                    msg.chat.id,
+                )
+                .await?;
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a round in which the player found the given words; the bot plays none
+fn outcome(progress: &Progress, winner: Option<Winner>, found: &[String]) -> GameOutcome {
+    GameOutcome {
+        words: found.to_vec(),
+        ..GameOutcome::new(progress, GAME, winner, &[])
+    }
+    .with_scores(found.len(), 0)
+}
+
+/// The words found and their points, followed by the longest of the grid's words the player
+/// missed
+fn summary(words: &[String], found: &[String]) -> String {
+    let mut summary = format!(
+        "You found {} words for {} points: {}",
+        found.len(),
+        total_score(found),
+        if found.is_empty() {
+            "none".to_string()
+        } else {
+            found.join(", ")
+        }
+    );
+    let mut missed = words
+        .iter()
+        .filter(|w| !found.contains(w))
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+    missed.sort_by_key(|w| std::cmp::Reverse(w.len()));
+    missed.truncate(MISSED_SHOWN);
+    if !missed.is_empty() {
+        summary.push_str(&format!("\nSome words you missed: {}", missed.join(", ")));
+    }
+    summary
+}
+
+/// The round's clock ran out: show how the player did and end the game, as a win when they
+/// scored `WINNING_POINTS`
+pub async fn time_up(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    words: &[String],
+    found: Vec<String>,
+    progress: Progress,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Boggle round ran out of time");
+
+    let points = total_score(&found);
+    bot.send_message(
+        chat_id,
+        format!("⏰ Time's up!\n\n{}", summary(words, &found)),
+    )
+    .await?;
+
+    let winner = (points >= WINNING_POINTS).then_some(Winner::Player);
+    let outcome = outcome(&progress, winner, &found);
+    finish_game(bot, chat_id, dialogue, outcome).await
+}
+
+/// Process a player's word submission
+async fn process_player_word(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (grid, words, mut found, deadline, mut progress): (
+        Vec<Vec<char>>,
+        Vec<String>,
+        Vec<String>,
+        u64,
+        Progress,
+    ),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    // Words sent after the clock ran out, e.g. while the bot was restarting, don't count
+    if time_left(deadline).is_zero() {
+        return time_up(&bot, chat_id, dialogue, &words, found, progress).await;
+    }
+
+    let entered = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if entered.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if entered.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(entered[0]);
+
+    if word.chars().count() < MIN_WORD_LEN {
+        bot.send_message(
+            chat_id,
+            format!("Words need at least {} letters.", MIN_WORD_LEN),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if !can_trace(&grid, &word) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "'{}' can't be traced through the grid — each letter has to touch the one before, and no letter can be used twice.",
+                word
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if found.contains(&word) {
+        bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+            .await?;
+        return Ok(());
+    }
+
+    // Validate the player's word
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            debug!(chat_id = chat_id.0, game = GAME; "Player found Boggle word: {}", word);
+            found.push(word.clone());
+            accept_submission(chat_id);
+            on_player_word(&bot, chat_id, &word_details.word, found.len()).await?;
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                let outcome = outcome(&progress, Some(Winner::Player), &found);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            bot.send_message(
+                chat_id,
+                format!(
+                    "✅ '{}' +{} — {} points so far, {} left.",
+                    word,
+                    word_score(&word),
+                    total_score(&found),
+                    show_time(time_left(deadline))
+                ),
+            )
+            .await?;
+
+            let _ = dialogue
+                .update(Boggle {
+                    grid,
+                    words,
+                    found,
+                    deadline,
+                    progress,
+                })
+                .await;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, e.rejection_message(&word))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Give the player a hint towards one of the grid's words they haven't found
+async fn provide_hint(
+    bot: &Bot,
+    chat_id: ChatId,
+    words: &[String],
+    found: &[String],
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    let word = words
+        .iter()
+        .filter(|w| !found.contains(w))
+        .choose(&mut rand::rng());
+    match word {
+        Some(word) => {
+            bot.send_message(chat_id, hint_message(chat_id, word))
+                .await?;
+        }
+        None => {
+            bot.send_message(chat_id, "You've found every word I know in this grid!")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show a found word's definition
+async fn define_found_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    found: &[String],
+    args: &str,
+) -> ResponseResult<()> {
+    let word = normalize_word(args.trim());
+    if !found.contains(&word) {
+        bot.send_message(
+            chat_id,
+            "Usage: /def <word> — shows the definition of a word you've found in this game.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match get_word_details(&word).await {
+        Ok(details) => details.send_message(bot, chat_id, 0).await,
+        Err(e) => {
+            error!(
+                chat_id = chat_id.0, game = GAME;
+                "Error retrieving word details for '{}': {:?}",
+                word, e
+            );
+            bot.send_message(
+                chat_id,
+                format!("Sorry, I couldn't look up '{}' right now.", word),
+            )
+            .await?;
+            Ok(())
+        }
+    }
+}
+
+/// Show the current game score
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    found: &[String],
+    deadline: u64,
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let mut score = format!(
+        "Boggle score:\nWords found: {}\nPoints: {} ({} wins)\nTime left: {}",
+        found.len(),
+        total_score(found),
+        WINNING_POINTS,
+        show_time(time_left(deadline))
+    );
+    for extra in score_extras(progress, found.iter().map(String::as_str)) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    grid: &[Vec<char>],
+    deadline: u64,
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Boggle rules:\n\
+            1. Find as many words as you can in the grid within {} minutes\n\
+            2. Trace each word through letters that touch, across, down or diagonally\n\
+            3. Each letter can be used once per word, and words need at least {} letters\n\
+            4. Words score by length: 1 point for 3-4 letters, 2 for 5, 3 for 6, 5 for 7 and 11 for 8 or more\n\
+            5. Score {} points to win\n\
+            6. /hint helps you towards a word in the grid",
+            ROUND_TIME.as_secs() / 60,
+            MIN_WORD_LEN,
+            WINNING_POINTS
+        ),
+    )
+    .await?;
+    send_grid(bot, chat_id, grid, deadline).await
+}
@@ -0,0 +1,547 @@
+use crate::achievements::{on_player_word, show_badges};
+use crate::command::Command;
+use crate::contains_any;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_random_word_with_pos, get_submission_details, is_playable, WordInfo};
+use crate::embeddings::most_similar;
+use crate::frequency::is_rare;
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::{
+    count_player_word, finish_game, game_started, reach_word_target, score_extras, GameOutcome,
+    Progress, Winner,
+};
+use crate::games::synonym_string::chat_similarity;
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    accept_submission, begin_processing, define_chain_word, looks_like_command, normalize_word,
+    reject_word, reply_unknown_command, show_dead_ends, show_help, still_processing,
+    REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::state::MyDialogue;
+use crate::state::State::Categories;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::IteratorRandom;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "categories";
+
+/// Minimum similarity between a player's word and the theme
+const MIN_THEME_SIMILARITY: f64 = 0.5;
+
+/// Words closest to a candidate theme that are checked for being on theme
+const THEME_POOL: usize = 200;
+
+/// On-theme words a theme needs before it's dealt, so there's plenty to find
+const MIN_THEME_WORDS: usize = 10;
+
+/// Themes tried before giving up on dealing one
+const THEME_ATTEMPTS: usize = 3;
+
+/// Shortest and longest theme words
+const MIN_THEME_LEN: usize = 4;
+const MAX_THEME_LEN: usize = 10;
+
+/// Stems of the words found so far
+fn used_stems(words: &[WordInfo]) -> Vec<String> {
+    words.iter().flat_map(|w| w.stems.clone()).collect()
+}
+
+/// Playable words close enough to a theme to be accepted, most similar first
+fn on_theme_words(theme: &str, threshold: f64) -> Vec<String> {
+    match most_similar(theme, THEME_POOL) {
+        Ok(words) => words
+            .into_iter()
+            .take_while(|(_, score)| *score > threshold)
+            .map(|(word, _)| word)
+            .filter(|w| is_playable(w))
+            .collect(),
+        Err(e) => {
+            error!(game = GAME; "Failed to find words near theme '{}': {:?}", theme, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Pick a new theme and announce it, keeping the words found so far
+async fn deal_theme(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    (words, progress): (Vec<WordInfo>, Progress),
+    previous: Option<&str>,
+) -> ResponseResult<bool> {
+    for _ in 0..THEME_ATTEMPTS {
+        // Themes are common nouns, so they name a category rather than a single thing
+        let theme = get_random_word_with_pos(
+            |w| {
+                (MIN_THEME_LEN..=MAX_THEME_LEN).contains(&w.len())
+                    && w.chars().all(|c| c.is_ascii_lowercase())
+                    && !is_rare(w)
+                    && Some(w) != previous
+            },
+            None,
+            "noun",
+        )
+        .await;
+        match theme {
+            Ok(theme) => {
+                let found = on_theme_words(&theme.word, MIN_THEME_SIMILARITY).len();
+                if found < MIN_THEME_WORDS {
+                    debug!(
+                        chat_id = chat_id.0, game = GAME;
+                        "Theme '{}' has only {} words on theme, picking another",
+                        theme.word, found
+                    );
+                    continue;
+                }
+                debug!(chat_id = chat_id.0, game = GAME; "Categories dealt theme '{}'", theme.word);
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "🗂 Your theme: {}\n\nSend words that belong with it — any letter, any order.",
+                        theme.word.to_uppercase()
+                    ),
+                )
+                .await?;
+                let _ = dialogue
+                    .update(Categories {
+                        theme: theme.word,
+                        threshold: MIN_THEME_SIMILARITY,
+                        words,
+                        progress,
+                    })
+                    .await;
+                return Ok(true);
+            }
+            Err(e) => {
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get a theme word: {:?}", e)
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Start a new Categories game
+pub async fn start_categories(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Categories game");
+
+    bot.send_message(
+        chat_id,
+        "Categories! I'll name a theme — find words that belong with it. Every on-theme word scores a point; /skip for a new theme.",
+    )
+    .await?;
+
+    if deal_theme(
+        &bot,
+        chat_id,
+        dialogue,
+        (Vec::new(), Progress::default()),
+        None,
+    )
+    .await?
+    {
+        game_started(&bot, chat_id).await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            "Sorry, I'm having trouble starting the game. Please try again later.",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Handle player input during Categories game
+pub async fn categories(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (theme, threshold, words, progress): (String, f64, Vec<WordInfo>, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &theme, threshold, &words).await?;
+            }
+            Ok(Command::Skip) => {
+                skip_turn(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    theme,
+                    threshold,
+                    (words, progress),
+                )
+                .await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &words, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &words, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &theme, threshold).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Categories game");
+
+                bot.send_message(
+                    msg.chat.id,
+                    format!(
+                        "Game finished! You found {} on-theme words: {}",
+                        words.len(),
+                        if words.is_empty() {
+                            "none".to_string()
+                        } else {
+                            words
+                                .iter()
+                                .map(|w| w.word.as_str())
+                                .collect::<Vec<&str>>()
+                                .join(", ")
+                        }
+                    ),
+                )
+                .await?;
+
+                bot.send_message(
+                    msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Categories")),
+                )
+                .await?;
+                let outcome = outcome(&progress, None, &words);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                process_player_word(
+                    text,
+                    bot,
+                    dialogue,
+                    (theme, threshold, words, progress),
+                    msg.chat.id,
+                )
+                .await?;
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a game in which the player found the given words; the bot plays none
+pub fn outcome(progress: &Progress, winner: Option<Winner>, words: &[WordInfo]) -> GameOutcome {
+    GameOutcome {
+        words: words.iter().map(|w| w.word.clone()).collect(),
+        ..GameOutcome::new(progress, GAME, winner, &[])
+    }
+    .with_scores(words.len(), 0)
+}
+
+/// Process a player's word submission
+async fn process_player_word(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (theme, threshold, mut words, mut progress): (String, f64, Vec<WordInfo>, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let submitted = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if submitted.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if submitted.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(submitted[0]);
+
+    if word == theme {
+        bot.send_message(
+            chat_id,
+            "The theme itself doesn't count — find words that go with it.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let sim_score = chat_similarity(chat_id, &word, &theme);
+    if sim_score <= threshold {
+        bot.send_message(
+            chat_id,
+            format!(
+                "'{}' doesn't fit the theme '{}' closely enough. Try something more related.",
+                word, theme
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let used_stems = used_stems(&words);
+
+    // Validate the player's word
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            if contains_any(&used_stems, &word_details.stems) {
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
+                return Ok(());
+            }
+
+            debug!(
+                chat_id = chat_id.0, game = GAME;
+                "Player found on-theme word: {} (similarity: {:.2})",
+                word, sim_score
+            );
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            words.push(word_details.clone());
+            accept_submission(chat_id);
+            on_player_word(&bot, chat_id, &word_details.word, words.len()).await?;
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                let outcome = outcome(&progress, Some(Winner::Player), &words);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            bot.send_message(
+                chat_id,
+                format!(
+                    "✅ '{}' fits '{}' — {} found so far. Any more? /skip for a new theme.",
+                    word_details.word,
+                    theme,
+                    words.len()
+                ),
+            )
+            .await?;
+
+            let _ = dialogue
+                .update(Categories {
+                    theme,
+                    threshold,
+                    words,
+                    progress,
+                })
+                .await;
+        }
+        Err(e) => {
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                chat_similarity(chat_id, w, &theme) > threshold
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Give the player a hint: an on-theme word they haven't found yet
+async fn provide_hint(
+    bot: &Bot,
+    chat_id: ChatId,
+    theme: &str,
+    threshold: f64,
+    words: &[WordInfo],
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    let used_stems = used_stems(words);
+    let hint = on_theme_words(theme, threshold)
+        .into_iter()
+        .filter(|w| !used_stems.contains(w))
+        .choose(&mut rand::rng());
+    match hint {
+        Some(hint) => {
+            bot.send_message(
+                chat_id,
+                format!("{} It goes with '{}'.", hint_message(chat_id, &hint), theme),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "I can't think of a hint right now. Just try any word that goes with '{}'.",
+                    theme
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deal a new theme, keeping the words found so far
+pub async fn skip_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    theme: String,
+    threshold: f64,
+    (words, progress): (Vec<WordInfo>, Progress),
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped theme");
+
+    // A few of the words the player missed, to show what the theme was after
+    let used_stems = used_stems(&words);
+    let missed = on_theme_words(&theme, threshold)
+        .into_iter()
+        .filter(|w| !used_stems.contains(w))
+        .take(3)
+        .collect::<Vec<String>>();
+    if missed.is_empty() {
+        bot.send_message(chat_id, "New theme!").await?;
+    } else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "New theme! For '{}' you could also have played: {}",
+                theme,
+                missed.join(", ")
+            ),
+        )
+        .await?;
+    }
+
+    let kept = (words.clone(), progress.clone());
+    if !deal_theme(bot, chat_id, dialogue.clone(), kept, Some(&theme)).await? {
+        bot.send_message(chat_id, "I'm out of themes to deal, so that's the game!")
+            .await?;
+        let outcome = outcome(&progress, None, &words);
+        finish_game(bot, chat_id, dialogue, outcome).await?;
+    }
+
+    Ok(())
+}
+
+/// Show the current game score
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    words: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let mut score = format!("Categories score:\nOn-theme words found: {}", words.len());
+    for extra in score_extras(progress, words.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, theme: &str, threshold: f64) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Categories rules:\n\
+            1. I name a theme\n\
+            2. Send words that belong with it (similarity of at least {:.0}%) — they can start with any letter\n\
+            3. Each on-theme word scores a point; no repeating words\n\
+            4. /skip deals a new theme, and /hint suggests a word that fits\n\n\
+            Your theme: {}",
+            threshold * 100.0,
+            theme
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
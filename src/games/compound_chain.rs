@@ -0,0 +1,716 @@
+use crate::achievements::show_badges;
+use crate::command::Command;
+use crate::contains_any;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{
+    active_model, has_words_with_prefix, is_valid_word, similarity_in, words_with_prefix,
+    EmbeddingError,
+};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    bot_concedes, count_player_word, finish_game, game_started, reach_word_target, score_extras,
+    skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, begin_processing, carry_on_message, credit_player_word,
+    define_chain_word, is_just_played, just_played_message, looks_like_command, normalize_word,
+    reject_word, reply_unknown_command, retry_turn, show_dead_ends, show_help, still_processing,
+    REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
+use crate::state::MyDialogue;
+use crate::state::State::CompoundChain;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::IndexedRandom;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Error type specific to Compound Chain game
+#[derive(Debug)]
+pub enum CompoundError {
+    Dictionary(DictionaryError),
+    Embedding(EmbeddingError),
+    NoCompounds(String),
+}
+
+impl From<DictionaryError> for CompoundError {
+    fn from(error: DictionaryError) -> Self {
+        CompoundError::Dictionary(error)
+    }
+}
+
+impl From<EmbeddingError> for CompoundError {
+    fn from(error: EmbeddingError) -> Self {
+        CompoundError::Embedding(error)
+    }
+}
+
+impl CompoundError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// compound carries on
+    fn is_transient(&self) -> bool {
+        match self {
+            CompoundError::Dictionary(e) => e.is_transient(),
+            CompoundError::Embedding(e) => e.is_transient(),
+            CompoundError::NoCompounds(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for CompoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CompoundError::Dictionary(e) => write!(f, "Dictionary error: {}", e),
+            CompoundError::Embedding(e) => write!(f, "Embedding error: {}", e),
+            CompoundError::NoCompounds(tail) => {
+                write!(f, "No unplayed compound starts with '{}'", tail)
+            }
+        }
+    }
+}
+
+/// Menu id of this game
+pub const GAME: &str = "compound_chain";
+
+/// Fewest letters either half of a compound may have, so "sunny" isn't read as "sun" + "ny"
+const MIN_PART: usize = 3;
+
+/// Replies the bot looks up before giving up on a turn
+const MAX_LOOKUPS: usize = 5;
+
+/// The second half of `word` when it's `head` followed by another word, e.g. "flower" for
+/// "sunflower" after "sun"
+pub fn compound_tail<'a>(word: &'a str, head: &str) -> Option<&'a str> {
+    word.strip_prefix(head)
+        .filter(|tail| tail.chars().count() >= MIN_PART && is_valid_word(tail))
+}
+
+/// The shortest first half `word` splits at into two words, e.g. "sun" for "sunflower"
+fn compound_head(word: &str) -> Option<&str> {
+    (MIN_PART..=word.len().saturating_sub(MIN_PART))
+        .filter(|&i| word.is_char_boundary(i))
+        .map(|i| &word[..i])
+        .find(|head| is_valid_word(head) && compound_tail(word, head).is_some())
+}
+
+/// Playable compounds starting with `tail` that haven't been `used` yet
+fn compounds_of(tail: &str, used: &[String]) -> Result<Vec<&'static str>, EmbeddingError> {
+    words_with_prefix(tail, |w| {
+        compound_tail(w, tail).is_some() && is_playable(w) && !used.contains(&w.to_string())
+    })
+}
+
+/// Prompt for the player's next word
+fn prompt(tail: &str) -> String {
+    format!(
+        "Now give a compound starting with '{}': '{}' followed by another word",
+        tail, tail
+    )
+}
+
+/// Start a new Compound Chain game
+pub async fn start_compound_chain(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Compound Chain game");
+
+    bot.send_message(
+        chat_id,
+        "Compound Chain starts now! Build compound words, each starting with the second half of the last one.",
+    )
+    .await?;
+
+    // Open with the first half of a real compound, so there's always a word to play
+    for _ in 0..3 {
+        let compound = get_random_word(
+            |w| {
+                w.chars().all(|c| c.is_ascii_lowercase())
+                    && compound_head(w).is_some_and(is_playable)
+            },
+            None,
+        )
+        .await;
+        let base = match compound {
+            Ok(compound) => match compound_head(&compound.word) {
+                Some(head) => get_word_details(head).await,
+                None => continue,
+            },
+            Err(e) => Err(e),
+        };
+        match base {
+            Ok(base) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Compound Chain started with word: {}",
+                    base.word
+                );
+
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&base.word), &base).await?;
+                bot.send_message(chat_id, prompt(&base.word)).await?;
+
+                game_started(&bot, chat_id).await?;
+                let current_tail = base.word.clone();
+                let _ = dialogue
+                    .update(CompoundChain {
+                        chain: vec![base],
+                        current_tail,
+                        progress: Progress::default(),
+                    })
+                    .await;
+
+                return Ok(());
+            }
+            Err(e) => {
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get opening word: {:?}", e);
+                // Try again
+            }
+        }
+    }
+
+    bot.send_message(
+        chat_id,
+        "Sorry, I'm having trouble starting the game. Please try again later.",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle player input during Compound Chain game
+pub async fn compound_chain(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (chain, current_tail, mut progress): (Vec<WordInfo>, String, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &chain, &current_tail).await?;
+            }
+            Ok(Command::Skip) => {
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
+                skip_turn(&bot, msg.chat.id, dialogue, chain, current_tail, progress).await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &chain, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &current_tail).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Compound Chain game");
+
+                // Show final score/summary
+                let player_words = chain.len() / 2;
+                let bot_words = chain.len() - player_words;
+
+                bot.send_message(
+                    msg.chat.id,
+                    format!(
+                        "Game finished! Final score:\nYou: {} words\nBot: {} words\n\nCompound chain: {}",
+                        player_words,
+                        bot_words,
+                        chain.iter().map(|w| w.word.clone()).collect::<Vec<String>>().join(" → ")
+                    ),
+                ).await?;
+
+                bot.send_message(
+                    msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Compound Chain")),
+                )
+                .await?;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(
+                        text,
+                        bot,
+                        dialogue,
+                        (chain, current_tail, progress),
+                        msg.chat.id,
+                    )
+                    .await?;
+                }
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Process a player's word submission
+async fn process_player_word(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (mut chain, current_tail, mut progress): (Vec<WordInfo>, String, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if words.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if words.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
+
+    // The word must be the current tail followed by another word, which becomes the next tail
+    let Some(next_tail) = compound_tail(&word, &current_tail).map(String::from) else {
+        let reply = if word.starts_with(&current_tail) {
+            format!(
+                "'{}' doesn't split into '{}' and another word of at least {} letters. Try another compound.",
+                word, current_tail, MIN_PART
+            )
+        } else {
+            format!("Your word must start with '{}'.", current_tail)
+        };
+        bot.send_message(chat_id, reply).await?;
+        return Ok(());
+    };
+
+    // Get list of already used words/stems
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+
+    // Validate the player's word
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            // Check if word has already been used
+            if contains_any(&used_stems, &word_details.stems) {
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
+                return Ok(());
+            }
+
+            debug!(
+                chat_id = chat_id.0, game = GAME;
+                "Player used word: {} (next tail: {})",
+                word, next_tail
+            );
+            let mut updated_stems = used_stems.clone();
+            updated_stems.push(word.clone());
+
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            chain.push(word_details.clone());
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            // Get the bot's response word
+            let response = get_bot_response(
+                chat_id,
+                &word_details.word,
+                &next_tail,
+                &updated_stems,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
+                Ok(reply) => {
+                    let Some(reply_tail) = compound_tail(&reply.word, &next_tail) else {
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Bot's word '{}' doesn't carry on from '{}'",
+                            reply.word, next_tail
+                        );
+                        bot.send_message(chat_id, "Error in game, please try again.")
+                            .await?;
+                        let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                        return finish_game(&bot, chat_id, dialogue, outcome).await;
+                    };
+                    let current_tail = reply_tail.to_string();
+
+                    chain.push(reply.clone());
+                    announce_bot_word(&bot, chat_id, Phrase::BotWord(&reply.word), &reply).await?;
+                    bot.send_message(chat_id, prompt(&current_tail)).await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
+
+                    let _ = dialogue
+                        .update(CompoundChain {
+                            chain,
+                            current_tail,
+                            progress,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    if bot_concedes(chat_id, &mut progress) {
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a compound starting with '{}'",
+                                next_tail
+                            )),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(CompoundChain {
+                                chain,
+                                current_tail: next_tail,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    bot.send_message(chat_id, say(chat_id, Phrase::BotStumped("a compound")))
+                        .await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
+                }
+            }
+        }
+        Err(e) => {
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                compound_tail(w, &current_tail).is_some()
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the bot's compound carrying on from `tail`: one of the closest in meaning to `word`,
+/// preferring those whose own second half the player can carry on from
+pub async fn get_bot_response(
+    chat_id: ChatId,
+    word: &str,
+    tail: &str,
+    used_words: &[String],
+    difficulty: Difficulty,
+) -> Result<WordInfo, CompoundError> {
+    let mut used_words = used_words.to_vec();
+    let model = active_model(chat_id)?;
+    let mut lookup_error = None;
+
+    for _ in 0..MAX_LOOKUPS {
+        let candidates = compounds_of(tail, &used_words)?;
+        if candidates.is_empty() {
+            break;
+        }
+
+        let open = candidates
+            .iter()
+            .copied()
+            .filter(|&candidate| {
+                compound_tail(candidate, tail).is_some_and(|next| {
+                    has_words_with_prefix(next, |w| {
+                        w != candidate
+                            && compound_tail(w, next).is_some()
+                            && !used_words.contains(&w.to_string())
+                    })
+                    .unwrap_or(false)
+                })
+            })
+            .collect::<Vec<&str>>();
+        let pool = if open.is_empty() { candidates } else { open };
+
+        let mut ranked = pool
+            .into_iter()
+            .map(|w| (w.to_string(), similarity_in(model, word, w).unwrap_or(0.0)))
+            .collect::<Vec<(String, f64)>>();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(difficulty.choices());
+        let reply = difficulty.choose(ranked)?;
+
+        match get_word_details(&reply).await {
+            Ok(details) if contains_any(&used_words, &details.stems) => {
+                used_words.extend(details.stems);
+            }
+            Ok(details) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Bot found compound '{}' after '{}'",
+                    details.word, tail
+                );
+                return Ok(details);
+            }
+            Err(e) => {
+                // Running out of words because lookups failed isn't a real dead end
+                if e.is_transient() {
+                    lookup_error = Some(e);
+                }
+                used_words.push(reply);
+            }
+        }
+    }
+
+    if let Some(e) = lookup_error {
+        return Err(CompoundError::Dictionary(e));
+    }
+    Err(CompoundError::NoCompounds(tail.to_string()))
+}
+
+/// Provide a hint for the current turn
+async fn provide_hint(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    current_tail: &str,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+
+    let hint = compounds_of(current_tail, &used_stems)
+        .ok()
+        .and_then(|words| words.choose(&mut rand::rng()).copied());
+    let reply = match hint {
+        Some(hint) => format!(
+            "{} It's '{}' followed by another word.",
+            hint_message(chat_id, hint),
+            current_tail
+        ),
+        None => format!(
+            "I can't think of a compound starting with '{}' either. Try /skip.",
+            current_tail
+        ),
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Skip the current turn
+pub async fn skip_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    mut chain: Vec<WordInfo>,
+    current_tail: String,
+    mut progress: Progress,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
+
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
+
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+    let prev_word = chain
+        .last()
+        .map_or_else(|| current_tail.clone(), |w| w.word.clone());
+
+    match get_bot_response(
+        chat_id,
+        &prev_word,
+        &current_tail,
+        &used_stems,
+        get_difficulty(chat_id),
+    )
+    .await
+    {
+        Ok(word) => {
+            let Some(next_tail) = compound_tail(&word.word, &current_tail).map(String::from) else {
+                error!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Bot's word '{}' doesn't carry on from '{}'",
+                    word.word, current_tail
+                );
+                bot.send_message(chat_id, "Error in game, please try again.")
+                    .await?;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                return finish_game(bot, chat_id, dialogue, outcome).await;
+            };
+
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
+            chain.push(word);
+            bot.send_message(chat_id, prompt(&next_tail)).await?;
+
+            next_player(bot, chat_id, &mut progress).await?;
+            let _ = dialogue
+                .update(CompoundChain {
+                    chain,
+                    current_tail: next_tail,
+                    progress,
+                })
+                .await;
+        }
+        Err(e) => {
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get compound for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the current score (word count)
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let player_words = chain.len() / 2;
+    let bot_words = chain.len() - player_words;
+
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, current_tail: &str) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Compound Chain Rules:\n\
+            1. Each word must start with the second half of the previous compound (next: '{}')\n\
+            2. The rest of your word must be a word of its own, of at least {} letters: after 'sun', 'sunflower' passes 'flower' on\n\
+            3. No repeating words\n\
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            current_tail,
+            MIN_PART
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,20 +1,46 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
 use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, EmbeddingError};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{count_words, get_top_similar_words, Chaining, EmbeddingError};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    begin_turn, bot_concedes, count_player_word, finish_game, game_started, reach_word_target,
+    score_extras, skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, announce_rewind, begin_processing, carry_on_message,
+    chain_letter, credit_player_word, dead_end_message, define_chain_word,
+    has_healthy_continuation, is_just_played, just_played_message, looks_like_command,
+    normalize_word, open_letters, reject_word, reply_unknown_command, reseed_opener, retry_turn,
+    rewind_chain, show_dead_ends, show_help, still_processing, Direction,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
+use crate::settings::get_settings;
 use crate::state::MyDialogue;
-use crate::state::State::{ForbiddenLetters, Start};
-use log::{error, info, warn};
+use crate::state::State::ForbiddenLetters;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
 use rand::prelude::IteratorRandom;
 use rand::rng;
+use teloxide::payloads::SendMessageSetters;
 use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
-use teloxide::types::Me;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me};
 use teloxide::utils::command::BotCommands;
 use teloxide::Bot;
 
 /// Error type specific to Forbidden Letters game
 #[derive(Debug)]
-enum ForbiddenLettersError {
+pub enum ForbiddenLettersError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
     InvalidInput(String),
@@ -33,6 +59,18 @@ impl From<EmbeddingError> for ForbiddenLettersError {
     }
 }
 
+impl ForbiddenLettersError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            ForbiddenLettersError::Dictionary(e) => e.is_transient(),
+            ForbiddenLettersError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for ForbiddenLettersError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -44,51 +82,105 @@ impl std::fmt::Display for ForbiddenLettersError {
     }
 }
 
-/// Start a new Forbidden Letters game
+/// Menu id of this game
+pub const GAME: &str = "forbidden_letters";
+
+/// Most letters a player can choose to forbid
+pub const MAX_FORBIDDEN: usize = 3;
+
+/// Attempts at an opening word per forbidden letter: every extra letter rules out more words
+const START_ATTEMPTS_PER_LETTER: usize = 3;
+
+/// Ask how many letters to forbid before the game starts
+pub async fn ask_letter_count(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let keyboard = InlineKeyboardMarkup::new(vec![(1..=MAX_FORBIDDEN)
+        .map(|count| {
+            InlineKeyboardButton::callback(
+                match count {
+                    1 => "1 letter".to_string(),
+                    n => format!("{} letters", n),
+                },
+                format!("forbidden_count_{}", count),
+            )
+        })
+        .collect::<Vec<_>>()]);
+    bot.send_message(
+        chat_id,
+        "Forbidden Letters! How many letters should I ban? More letters make it harder.",
+    )
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
+/// Start a new Forbidden Letters game with `count` banned letters
 pub async fn start_forbidden_letters(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
+    count: usize,
 ) -> ResponseResult<()> {
-    info!("Starting Forbidden Letters game for chat {}", chat_id);
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Starting Forbidden Letters game with {} letters",
+        count
+    );
 
     bot.send_message(chat_id, "Forbidden Letters! Avoid the banned ones.")
         .await?;
 
     // Choose some random letters to forbid
-    let forbidden_letters = ('a'..='z').choose_multiple(&mut rng(), 1);
+    let forbidden_letters = choose_forbidden_letters(count);
 
-    info!(
-        "Forbidden letters for chat {}: {:?}",
-        chat_id, forbidden_letters
-    );
+    debug!(chat_id = chat_id.0, game = GAME; "Forbidden letters: {:?}", forbidden_letters);
+
+    // Open with a word the chain can carry on from without the forbidden letters, so the first
+    // reply isn't a dead end
+    let open = open_letters(|w| !contains_forbidden_chars(w, &forbidden_letters));
 
     // Try to get a random word to start the game
-    for _ in 0..3 {
-        // Try up to 3 times
-        match get_random_word(|w| !contains_forbidden_chars(w, &forbidden_letters), None).await {
+    for _ in 0..START_ATTEMPTS_PER_LETTER * count {
+        match get_random_word(
+            |w| {
+                !contains_forbidden_chars(w, &forbidden_letters)
+                    && Direction::Forward.opens(w, &open)
+            },
+            None,
+        )
+        .await
+        {
             Ok(word) => {
-                let next_char = match word.word.chars().last() {
+                let next_char = match chain_letter(&word.word) {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                info!("Forbidden Letters started with word: {}", word.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Forbidden Letters started with word: {}",
+                    word.word
+                );
 
                 bot.send_message(
                     chat_id,
-                    format!("Forbidden Letters! Avoid {:?}", forbidden_letters),
+                    format!(
+                        "Forbidden Letters! Avoid {}",
+                        letter_list(&forbidden_letters)
+                    ),
                 )
                 .await?;
 
-                bot.send_message(chat_id, format!("First word: {}", word.word))
-                    .await?;
-                word.send_message(&bot, chat_id, 0).await?;
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
                 bot.send_message(
                     chat_id,
@@ -99,18 +191,20 @@ pub async fn start_forbidden_letters(
                 )
                 .await?;
 
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(ForbiddenLetters {
                         chain: vec![word],
                         forbidden_letters: forbidden_letters.clone(),
                         curr_char: next_char,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
                 // Try again
             }
         }
@@ -126,27 +220,66 @@ pub async fn start_forbidden_letters(
     Ok(())
 }
 
+/// Maximum number of forbidden letter draws before settling for the last one
+const MAX_SETUP_ATTEMPTS: usize = 5;
+
+/// Choose `count` random letters to forbid, redrawing while too few words avoid them
+fn choose_forbidden_letters(count: usize) -> Vec<char> {
+    let min_pool = get_settings().min_word_pool;
+    let mut letters = ('a'..='z').choose_multiple(&mut rng(), count);
+
+    for attempt in 1..MAX_SETUP_ATTEMPTS {
+        let pool = count_words(|w| !contains_forbidden_chars(w, &letters)).unwrap_or(0);
+        if pool >= min_pool {
+            break;
+        }
+        debug!(
+            game = GAME;
+            "Only {} words avoid {:?} (attempt {}), retrying with different forbidden letters",
+            pool, letters, attempt
+        );
+        letters = ('a'..='z').choose_multiple(&mut rng(), count);
+    }
+
+    letters
+}
+
 /// Handle player input during Forbidden Letters game
 pub async fn forbidden_letters(
     bot: Bot,
     dialogue: MyDialogue,
-    (forbidden_letters, chain, curr_char): (Vec<char>, Vec<WordInfo>, char),
+    (forbidden_letters, chain, curr_char, mut progress): (Vec<char>, Vec<WordInfo>, char, Progress),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
+            Ok(Command::Hint(_)) => {
                 provide_hint(&bot, msg.chat.id, curr_char, &forbidden_letters).await?;
             }
             Ok(Command::Skip) => {
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
                 skip_turn(
                     &bot,
                     msg.chat.id,
@@ -154,19 +287,76 @@ pub async fn forbidden_letters(
                     chain,
                     forbidden_letters,
                     curr_char,
+                    progress,
                 )
                 .await?;
             }
             Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &chain).await?;
+                show_score(&bot, msg.chat.id, &chain, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(args)) => {
+                rewind(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    &args,
+                    (chain, forbidden_letters, progress),
+                )
+                .await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &forbidden_letters).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id, &forbidden_letters).await?;
+                show_rules(&bot, msg.chat.id, &forbidden_letters, curr_char).await?;
             }
             Ok(Command::Stop) => {
                 info!(
-                    "Player stopped Forbidden Letters game in chat {}",
-                    msg.chat.id
+                    chat_id = msg.chat.id.0, game = GAME;
+                    "Player stopped Forbidden Letters game"
                 );
 
                 // Show final score/summary
@@ -186,22 +376,28 @@ pub async fn forbidden_letters(
 
                 bot.send_message(
                     msg.chat.id,
-                    "Forbidden Letters game stopped. Thanks for playing!",
+                    say(msg.chat.id, Phrase::GameStopped("Forbidden Letters")),
                 )
                 .await?;
-                let _ = dialogue.update(Start).await;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
             }
             Err(_) => {
-                process_player_word(
-                    text,
-                    bot,
-                    dialogue,
-                    chain,
-                    forbidden_letters,
-                    curr_char,
-                    msg.chat.id,
-                )
-                .await?
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(
+                        text,
+                        bot,
+                        dialogue,
+                        (chain, progress),
+                        forbidden_letters,
+                        curr_char,
+                        msg.chat.id,
+                    )
+                    .await?
+                }
             }
         },
         None => {
@@ -216,7 +412,7 @@ async fn process_player_word(
     text: &str,
     bot: Bot,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
+    (mut chain, mut progress): (Vec<WordInfo>, Progress),
     forbidden_letters: Vec<char>,
     curr_char: char,
     chat_id: ChatId,
@@ -235,15 +431,19 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
 
     // Check if word starts with correct letter and doesn't contain forbidden letters
     if !word.starts_with(curr_char) {
-        bot.send_message(
-            chat_id,
-            format!("Your word must start with '{}'", curr_char),
-        )
-        .await?;
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(curr_char)))
+            .await?;
         return Ok(());
     }
 
@@ -266,78 +466,155 @@ async fn process_player_word(
         .collect::<Vec<String>>();
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
             if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!("Player used word: {} in chat {}", word, chat_id);
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
             let mut updated_stems = used_stems.clone();
             updated_stems.push(word.clone());
 
-            word_details.send_message(&bot, chat_id, 0).await?;
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            begin_turn(&mut progress, chain.len());
             chain.push(word_details.clone());
 
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
             // Get the bot's response word
-            match get_bot_response(&word, &updated_stems, &forbidden_letters).await {
+            let response = get_bot_response(
+                &word_details.word,
+                &updated_stems,
+                &forbidden_letters,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
-                    let next_char = match next_word_details.word.chars().last() {
+                    let next_char = match chain_letter(&next_word_details.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot's word '{}' has no characters", next_word_details.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot's word '{}' has no characters",
+                                next_word_details.word
+                            );
                             bot.send_message(chat_id, "Error in game, please try again.")
                                 .await?;
-                            let _ = dialogue.update(Start).await;
+                            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                            finish_game(&bot, chat_id, dialogue, outcome).await?;
                             return Ok(());
                         }
                     };
 
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
-
-                    // Prompt for the next word
-                    bot.send_message(
+                    announce_bot_word(
+                        &bot,
                         chat_id,
-                        format!("Now give a word starting with '{}'", next_char),
+                        Phrase::BotWord(&next_word_details.word),
+                        &next_word_details,
                     )
                     .await?;
 
+                    // Prompt for the next word
+                    bot.send_message(chat_id, say(chat_id, Phrase::YourTurn(next_char)))
+                        .await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
+
                     // Update game state
                     let _ = dialogue
                         .update(ForbiddenLetters {
                             chain,
                             forbidden_letters,
                             curr_char: next_char,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(chat_id, "I can't think of a word! You win this round!")
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // Conceding to the very first reply would end the game before any real play
+                    if let Some(opener) = reseed_opener(&bot, chat_id, &chain, &mut progress, |w| {
+                        !contains_forbidden_chars(w, &forbidden_letters)
+                            && has_healthy_continuation(w)
+                    })
+                    .await?
+                    {
+                        let curr_char = chain_letter(&opener.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            format!(
+                                "Now give a word starting with '{}' that doesn't contain forbidden letters",
+                                curr_char
+                            ),
+                        )
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(ForbiddenLetters {
+                                chain: vec![opener],
+                                forbidden_letters,
+                                curr_char,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = chain_letter(&word_details.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!("give a word starting with '{}'", next_char)),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(ForbiddenLetters {
+                                chain,
+                                forbidden_letters,
+                                curr_char: next_char,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    let reply = match &e {
+                        ForbiddenLettersError::Embedding(EmbeddingError::NoWordsForLetter(c)) => {
+                            dead_end_message(*c)
+                        }
+                        _ => say(chat_id, Phrase::BotStumped("a word")),
+                    };
+                    bot.send_message(chat_id, reply).await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(curr_char) && !contains_forbidden_chars(w, &forbidden_letters)
+            })
             .await?;
         }
     }
@@ -346,13 +623,14 @@ async fn process_player_word(
 }
 
 /// Get the bot's response word that doesn't use forbidden letters
-async fn get_bot_response(
+pub async fn get_bot_response(
     player_word: &str,
     used_words: &[String],
     forbidden_letters: &[char],
+    difficulty: Difficulty,
 ) -> Result<WordInfo, ForbiddenLettersError> {
     let mut used_words = used_words.to_vec();
-    let last_char = match player_word.chars().last() {
+    let last_char = match chain_letter(player_word) {
         Some(c) => c,
         None => {
             return Err(ForbiddenLettersError::InvalidInput(
@@ -364,14 +642,24 @@ async fn get_bot_response(
     // Get a similar word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
         // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, last_char, |x| {
-            !used_words.contains(&x.to_string()) && !contains_forbidden_chars(x, forbidden_letters)
-        });
+        let next_word_result = get_top_similar_words(
+            player_word,
+            last_char,
+            Chaining::Strict,
+            difficulty.choices(),
+            |x| {
+                is_playable(x)
+                    && !used_words.contains(&x.to_string())
+                    && !contains_forbidden_chars(x, forbidden_letters)
+            },
+        )
+        .and_then(|words| difficulty.choose(words));
 
         match next_word_result {
             Ok(word) => {
@@ -384,10 +672,14 @@ async fn get_bot_response(
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
-                        continue
-                    }, // Try another word
+                        continue;
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -399,6 +691,9 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(ForbiddenLettersError::Dictionary(e));
+    }
     Err(ForbiddenLettersError::NoValidWords(format!(
         "Could not find a valid word without forbidden letters: {:?}",
         forbidden_letters
@@ -412,7 +707,7 @@ async fn provide_hint(
     curr_char: char,
     forbidden_letters: &[char],
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
 
     // Get a random word starting with the current character without forbidden letters
     match get_random_word(
@@ -422,14 +717,8 @@ async fn provide_hint(
     .await
     {
         Ok(hint) => {
-            bot.send_message(
-                chat_id,
-                format!(
-                    "Hint: You could try a word like '{}' or something similar.",
-                    hint.word
-                ),
-            )
-            .await?;
+            bot.send_message(chat_id, hint_message(chat_id, &hint.word))
+                .await?;
         }
         Err(_) => {
             bot.send_message(
@@ -446,17 +735,19 @@ async fn provide_hint(
 }
 
 /// Skip the current turn
-async fn skip_turn(
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
     mut chain: Vec<WordInfo>,
     forbidden_letters: Vec<char>,
     curr_char: char,
+    mut progress: Progress,
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
     let used_stems = chain
@@ -474,21 +765,25 @@ async fn skip_turn(
     .await
     {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
 
-            let next_char = match word.word.chars().last() {
+            let next_char = match chain_letter(&word.word) {
                 Some(c) => c,
                 None => {
-                    error!("Bot's word '{}' has no characters", word.word);
+                    error!(
+                        chat_id = chat_id.0, game = GAME;
+                        "Bot's word '{}' has no characters",
+                        word.word
+                    );
                     bot.send_message(chat_id, "Error in game, please try again.")
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                    let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                    finish_game(bot, chat_id, dialogue, outcome).await?;
                     return Ok(());
                 }
             };
 
+            begin_turn(&mut progress, chain.len());
             chain.push(word.clone());
 
             bot.send_message(
@@ -497,22 +792,22 @@ async fn skip_turn(
             )
             .await?;
 
+            next_player(bot, chat_id, &mut progress).await?;
             let _ = dialogue
                 .update(ForbiddenLetters {
                     chain,
                     forbidden_letters,
                     curr_char: next_char,
+                    progress,
                 })
                 .await;
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
@@ -520,33 +815,84 @@ async fn skip_turn(
 }
 
 /// Show the current score (word count)
-async fn show_score(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
     let player_words = chain.len() / 2;
     let bot_words = chain.len() - player_words;
 
-    bot.send_message(
-        chat_id,
-        format!(
-            "Current score:\nYou: {} words\nBot: {} words",
-            player_words, bot_words
-        ),
-    )
-    .await?;
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Handle /rewind: take the chain back a few turns so the player can try another path
+async fn rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (mut chain, forbidden_letters, mut progress): (Vec<WordInfo>, Vec<char>, Progress),
+) -> ResponseResult<()> {
+    let turns = match rewind_chain(&mut chain, &mut progress, args) {
+        Ok(turns) => turns,
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+    let Some(curr_char) = chain.last().and_then(|w| chain_letter(&w.word)) else {
+        error!(chat_id = chat_id.0, game = GAME; "Rewound chain has no last word");
+        return Ok(());
+    };
+
+    let prompt = say(chat_id, Phrase::YourTurn(curr_char));
+    announce_rewind(bot, chat_id, turns, &chain, &mut progress, &prompt).await?;
+
+    let _ = dialogue
+        .update(ForbiddenLetters {
+            chain,
+            forbidden_letters,
+            curr_char,
+            progress,
+        })
+        .await;
 
     Ok(())
 }
 
 /// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId, forbidden_letters: &[char]) -> ResponseResult<()> {
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    forbidden_letters: &[char],
+    curr_char: char,
+) -> ResponseResult<()> {
+    let letters = letter_list(forbidden_letters);
+
     bot.send_message(
         chat_id,
         format!(
             "Forbidden Letters Rules:\n\
-            1. Each word must start with the last letter of the previous word\n\
-            2. No words may contain these forbidden letters: {:?}\n\
+            1. Each word must start with the last letter of the previous word (next: '{}')\n\
+            2. No words may contain these forbidden letters: {}\n\
             3. No repeating words\n\
-            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
-            forbidden_letters
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            curr_char, letters
         ),
     )
     .await?;
@@ -554,6 +900,15 @@ async fn show_rules(bot: &Bot, chat_id: ChatId, forbidden_letters: &[char]) -> R
     Ok(())
 }
 
+/// Forbidden letters quoted for display, e.g. "'q', 'z'"
+fn letter_list(forbidden_letters: &[char]) -> String {
+    forbidden_letters
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 /// Check if a string contains any of the forbidden characters
 fn contains_forbidden_chars(s: &str, forbidden_chars: &[char]) -> bool {
     for c in s.chars() {
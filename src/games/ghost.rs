@@ -0,0 +1,401 @@
+use crate::achievements::show_badges;
+use crate::command::Command;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_word_details, is_playable};
+use crate::embeddings::{is_valid_word, words_with_prefix, EmbeddingError};
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::{finish_game, game_started, GameOutcome, Progress, Winner};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    begin_processing, define_chain_word, looks_like_command, reply_unknown_command, show_dead_ends,
+    show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::state::MyDialogue;
+use crate::state::State::Ghost;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::IteratorRandom;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "ghost";
+
+/// Shortest word that ends the round when completed; shorter words are just prefixes
+const MIN_WORD_LEN: usize = 4;
+
+/// Whether a vocabulary word can be spelled in Ghost: plain letters, with a known definition
+fn is_ghost_word(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_lowercase()) && is_playable(word)
+}
+
+/// Whether a fragment spells a word long enough to end the round
+fn completes_word(fragment: &str) -> bool {
+    fragment.len() >= MIN_WORD_LEN && is_valid_word(fragment) && is_ghost_word(fragment)
+}
+
+/// A fragment in capitals for display, e.g. "GHO"
+fn show_fragment(fragment: &str) -> String {
+    fragment.to_uppercase()
+}
+
+/// The letter to add next, and whether it's safe: when every letter completes a word, the
+/// least bad one is returned as unsafe. `None` when no word continues the fragment at all.
+///
+/// Each word that continues the fragment ends the round at its first prefix that's a word of
+/// at least `MIN_WORD_LEN` letters. Letters towards words that leave the other player to write
+/// that last letter are preferred.
+fn best_letter(fragment: &str) -> Result<Option<(char, bool)>, EmbeddingError> {
+    let words = words_with_prefix(fragment, |w| w.len() > fragment.len() && is_ghost_word(w))?;
+
+    let mut winning = Vec::new();
+    let mut safe = Vec::new();
+    let mut forced = Vec::new();
+    for word in words {
+        let Some(letter) = word[fragment.len()..].chars().next() else {
+            continue;
+        };
+        // Letters a word is spelled through before the round ends on it
+        let end = (fragment.len() + 1..=word.len())
+            .find(|&len| completes_word(&word[..len]))
+            .unwrap_or(word.len());
+        if end == fragment.len() + 1 {
+            forced.push(letter);
+        } else if (end - fragment.len()).is_multiple_of(2) {
+            winning.push(letter);
+        } else {
+            safe.push(letter);
+        }
+    }
+
+    let mut rng = rand::rng();
+    Ok(match winning.into_iter().choose(&mut rng) {
+        Some(letter) => Some((letter, true)),
+        None => match safe.into_iter().choose(&mut rng) {
+            Some(letter) => Some((letter, true)),
+            None => forced.into_iter().choose(&mut rng).map(|l| (l, false)),
+        },
+    })
+}
+
+/// Start a new Ghost game, with the player adding the first letter
+pub async fn start_ghost(chat_id: ChatId, bot: Bot, dialogue: MyDialogue) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Ghost game");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Ghost! We take turns adding a letter. Keep the letters the start of a real word, but don't be the one to finish a word of {} or more letters.\n\nYou go first: send any letter.",
+            MIN_WORD_LEN
+        ),
+    )
+    .await?;
+
+    game_started(&bot, chat_id).await?;
+    let _ = dialogue
+        .update(Ghost {
+            fragment: String::new(),
+            progress: Progress::default(),
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Handle player input during Ghost game
+pub async fn ghost(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (fragment, progress): (String, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &fragment).await?;
+            }
+            Ok(Command::Skip) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "There's no skipping in Ghost — add a letter, or /stop to give up.",
+                )
+                .await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &fragment).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &[], &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &fragment).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Ghost game");
+
+                if !fragment.is_empty() {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "Game finished! We got as far as {}.",
+                            show_fragment(&fragment)
+                        ),
+                    )
+                    .await?;
+                }
+                bot.send_message(msg.chat.id, say(msg.chat.id, Phrase::GameStopped("Ghost")))
+                    .await?;
+                let outcome = GameOutcome::new(&progress, GAME, None, &[]);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                process_player_letter(text, bot, dialogue, (fragment, progress), msg.chat.id)
+                    .await?;
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Process the letter a player adds to the fragment
+async fn process_player_letter(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (fragment, progress): (String, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let mut letters = text.trim().chars();
+    let letter = match (letters.next(), letters.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => c.to_ascii_lowercase(),
+        _ => {
+            bot.send_message(
+                chat_id,
+                if fragment.is_empty() {
+                    "Please send a single letter to start the word.".to_string()
+                } else {
+                    format!(
+                        "Please send a single letter to add to {}.",
+                        show_fragment(&fragment)
+                    )
+                },
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let fragment = format!("{}{}", fragment, letter);
+    debug!(chat_id = chat_id.0, game = GAME; "Player made Ghost fragment '{}'", fragment);
+
+    // Completing a word loses the round
+    if completes_word(&fragment) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "👻 {} is a word — you spelled it, so you lose this round!",
+                show_fragment(&fragment)
+            ),
+        )
+        .await?;
+        if let Ok(details) = get_word_details(&fragment).await {
+            details.send_message(&bot, chat_id, 0).await?;
+        }
+        let outcome = GameOutcome::new(&progress, GAME, None, &[]);
+        return finish_game(&bot, chat_id, dialogue, outcome).await;
+    }
+
+    // The bot's letter, if any longer word still starts with the fragment
+    match best_letter(&fragment) {
+        Ok(Some((letter, true))) => {
+            let fragment = format!("{}{}", fragment, letter);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "I add '{}': {}\n\nYour letter?",
+                    letter.to_ascii_uppercase(),
+                    show_fragment(&fragment)
+                ),
+            )
+            .await?;
+            let _ = dialogue.update(Ghost { fragment, progress }).await;
+        }
+        Ok(Some((letter, false))) => {
+            let fragment = format!("{}{}", fragment, letter);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Every letter I could add finishes a word... I add '{}': {} 👻 You win!",
+                    letter.to_ascii_uppercase(),
+                    show_fragment(&fragment)
+                ),
+            )
+            .await?;
+            let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &[]);
+            finish_game(&bot, chat_id, dialogue, outcome).await?;
+        }
+        // Adding a letter no longer word continues from loses the round too, even when the
+        // fragment spells a word too short to end it
+        Ok(None) => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "👻 No word I know goes on from {} — you lose this round!",
+                    show_fragment(&fragment)
+                ),
+            )
+            .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &[]);
+            finish_game(&bot, chat_id, dialogue, outcome).await?;
+        }
+        Err(e) => {
+            error!(
+                chat_id = chat_id.0, game = GAME;
+                "Failed to pick a Ghost letter for '{}': {:?}",
+                fragment, e
+            );
+            bot.send_message(chat_id, "Game error - please restart")
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Suggest a letter that keeps the player in the game
+async fn provide_hint(bot: &Bot, chat_id: ChatId, fragment: &str) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    if fragment.is_empty() {
+        bot.send_message(chat_id, "Any letter will do to start!")
+            .await?;
+        return Ok(());
+    }
+
+    let reply = match best_letter(fragment) {
+        Ok(Some((letter, true))) => format!("Try adding '{}'.", letter.to_ascii_uppercase()),
+        Ok(Some(_)) => "I'm afraid every letter finishes a word from here.".to_string(),
+        _ => "I can't think of a hint right now.".to_string(),
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Show the fragment so far
+async fn show_score(bot: &Bot, chat_id: ChatId, fragment: &str) -> ResponseResult<()> {
+    let reply = if fragment.is_empty() {
+        "No letters yet — send one to start.".to_string()
+    } else {
+        format!(
+            "Letters so far: {} ({} letters)",
+            show_fragment(fragment),
+            fragment.len()
+        )
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, fragment: &str) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Ghost rules:\n\
+            1. We take turns adding one letter to the end\n\
+            2. The letters must always be the start of a real word\n\
+            3. Whoever finishes a word of {} or more letters loses\n\
+            4. Adding a letter that no word continues from loses too\n\
+            5. /hint suggests a safe letter, /stop ends the game\n\n\
+            Letters so far: {}",
+            MIN_WORD_LEN,
+            if fragment.is_empty() {
+                "none".to_string()
+            } else {
+                show_fragment(fragment)
+            }
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
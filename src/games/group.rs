@@ -0,0 +1,216 @@
+use crate::games::outcome::Progress;
+use crate::state::MyDialogue;
+use log::info;
+use serde::{Deserialize, Serialize};
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::UserId;
+use teloxide::Bot;
+
+/// Players who joined a group chat's game, in turn order. It's kept in the game's progress, so
+/// it survives a restart and every new game starts with nobody joined; until someone joins,
+/// anyone can answer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Roster {
+    /// Each player's id and display name
+    players: Vec<(UserId, String)>,
+    /// Index of the player whose turn it is
+    current: usize,
+}
+
+impl Roster {
+    /// Name of the player whose turn it is
+    fn current_name(&self) -> Option<&str> {
+        self.players
+            .get(self.current)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Names of all players, in turn order
+    fn names(&self) -> String {
+        self.players
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+}
+
+/// Handle /join or /leave outside a game: players join the game in progress
+pub async fn no_game_to_join(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "Players join a game in progress: start one with /play, then /join to take turns.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Handle /join: add the sender to the game's players, after those already in
+pub async fn handle_join_command(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &MyDialogue,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    if !msg.chat.is_group() && !msg.chat.is_supergroup() {
+        bot.send_message(
+            chat_id,
+            "Taking turns is for group chats — here it's just you and me.",
+        )
+        .await?;
+        return Ok(());
+    }
+    let Some(user) = &msg.from else {
+        return Ok(());
+    };
+    let Ok(Some(mut state)) = dialogue.get().await else {
+        return no_game_to_join(bot, chat_id).await;
+    };
+    let Some(progress) = state.progress_mut() else {
+        return no_game_to_join(bot, chat_id).await;
+    };
+
+    let roster = progress.roster_mut();
+    let reply = if roster.players.iter().any(|(id, _)| *id == user.id) {
+        format!("You're already playing. Players: {}", roster.names())
+    } else {
+        roster.players.push((user.id, user.full_name()));
+        info!(
+            chat_id = chat_id.0;
+            "{} joined the players ({} in total)",
+            user.id, roster.players.len()
+        );
+        format!(
+            "👋 {} joined! Players take turns in this order: {}",
+            user.full_name(),
+            roster.names()
+        )
+    };
+    let _ = dialogue.update(state).await;
+    bot.send_message(chat_id, reply).await?;
+    Ok(())
+}
+
+/// Handle /leave: remove the sender from the game's players. If it was their turn, it passes to
+/// the next player.
+pub async fn handle_leave_command(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &MyDialogue,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some(user) = &msg.from else {
+        return Ok(());
+    };
+    let Ok(Some(mut state)) = dialogue.get().await else {
+        return no_game_to_join(bot, chat_id).await;
+    };
+    let Some(progress) = state.progress_mut() else {
+        return no_game_to_join(bot, chat_id).await;
+    };
+
+    let roster = progress.roster_mut();
+    let Some(seat) = roster.players.iter().position(|(id, _)| *id == user.id) else {
+        bot.send_message(chat_id, "You haven't joined. Use /join to take turns.")
+            .await?;
+        return Ok(());
+    };
+    // Players after the one leaving move up a seat, so the turn stays with whoever holds it;
+    // if it was the leaving player's turn, it falls to the next player in line
+    roster.players.remove(seat);
+    if seat < roster.current {
+        roster.current -= 1;
+    }
+    if roster.current >= roster.players.len() {
+        roster.current = 0;
+    }
+    info!(chat_id = chat_id.0; "{} left the players", user.id);
+    let reply = match roster.current_name() {
+        Some(next) => format!(
+            "{} left. Players: {}\n\n👉 {}, you're up.",
+            user.full_name(),
+            roster.names(),
+            next
+        ),
+        None => format!(
+            "{} left. No players remain, so anyone can answer again.",
+            user.full_name()
+        ),
+    };
+    let _ = dialogue.update(state).await;
+    bot.send_message(chat_id, reply).await?;
+    Ok(())
+}
+
+/// Whether the sender of a word or /skip may play it: always when the game has no players
+/// joined, otherwise only on their turn. Anyone else is told whose turn it is.
+pub async fn check_turn(bot: &Bot, msg: &Message, progress: &Progress) -> ResponseResult<bool> {
+    let roster = progress.roster();
+    let Some((id, name)) = roster.players.get(roster.current) else {
+        return Ok(true);
+    };
+    let sender = msg.from.as_ref().map(|user| user.id);
+    if sender == Some(*id) {
+        return Ok(true);
+    }
+
+    let joined = roster.players.iter().any(|(id, _)| Some(*id) == sender);
+    let reply = if joined {
+        format!("It's {}'s turn — please wait for yours.", name)
+    } else {
+        format!(
+            "It's {}'s turn. Use /join to take turns in this game too.",
+            name
+        )
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(false)
+}
+
+/// Pass the turn to the next joined player once a player's turn is over, whether they played a
+/// word, skipped or ran out of time, and say who's up. Called once the bot's reply and prompt
+/// are out, so the announcement is the last thing the next player reads. Does nothing in games
+/// without at least two players.
+pub async fn next_player(
+    bot: &Bot,
+    chat_id: ChatId,
+    progress: &mut Progress,
+) -> ResponseResult<()> {
+    let roster = progress.roster_mut();
+    if roster.players.len() < 2 {
+        return Ok(());
+    }
+    roster.current = (roster.current + 1) % roster.players.len();
+    let next = roster.current_name().unwrap_or_default().to_string();
+    bot.send_message(chat_id, format!("👉 {}, you're up next.", next))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roster_is_stored_with_the_game() {
+        let mut progress = Progress::default();
+        let roster = progress.roster_mut();
+        roster.players.push((UserId(1), "Ann".to_string()));
+        roster.players.push((UserId(2), "Bo".to_string()));
+        roster.current = 1;
+
+        let stored = serde_json::to_string(&progress).unwrap();
+        let restored: Progress = serde_json::from_str(&stored).unwrap();
+        assert_eq!(restored.roster().names(), "Ann, Bo");
+        assert_eq!(restored.roster().current_name(), Some("Bo"));
+    }
+
+    #[test]
+    fn games_stored_before_rosters_have_nobody_joined() {
+        let mut stored = serde_json::to_value(Progress::default()).unwrap();
+        stored.as_object_mut().unwrap().remove("roster");
+
+        let restored: Progress = serde_json::from_value(stored).unwrap();
+        assert!(restored.roster().players.is_empty());
+    }
+}
@@ -0,0 +1,534 @@
+use crate::achievements::{on_player_word, show_badges};
+use crate::command::Command;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_random_word, WordInfo};
+use crate::frequency::is_rare;
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::{finish_game, game_started, GameOutcome, Progress, Winner};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    accept_submission, begin_processing, looks_like_command, normalize_word, reply_unknown_command,
+    show_dead_ends, show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::state::MyDialogue;
+use crate::state::State::GuessWord;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "guess_word";
+
+/// Shortest and longest words to guess
+const MIN_LETTERS: usize = 4;
+const MAX_LETTERS: usize = 9;
+
+/// Wrong guesses allowed before the word is revealed
+const MAX_ATTEMPTS: u8 = 6;
+
+/// Points for guessing the word straight away, less the cost of each hint and wrong guess
+const MAX_POINTS: usize = 10;
+const HINT_COST: usize = 2;
+const MISS_COST: usize = 1;
+
+/// Senses of the word shown as the clue
+const CLUE_SENSES: usize = 2;
+
+/// Text the headword is hidden behind in the clue
+const MASK: &str = "____";
+
+/// Points a correct guess scores after `revealed` hints and `attempts` wrong guesses, never
+/// less than one
+fn points(revealed: usize, attempts: u8) -> usize {
+    MAX_POINTS
+        .saturating_sub(revealed * HINT_COST + attempts as usize * MISS_COST)
+        .max(1)
+}
+
+/// The word with all but its first `revealed` letters hidden, e.g. "a p _ _ _"
+fn pattern(word: &str, revealed: usize) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| if i < revealed { c } else { '_' })
+        .map(String::from)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Hide every form of the headword in `text`: the word itself, its stems and anything longer
+/// built on the word, such as plurals
+fn mask_headword(text: &str, target: &WordInfo) -> String {
+    let hides = |token: &str| {
+        let token = token.to_lowercase();
+        token.starts_with(&target.word) || target.stems.contains(&token)
+    };
+
+    let mut masked = String::with_capacity(text.len());
+    let mut token = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_alphabetic() {
+            token.push(c);
+            continue;
+        }
+        if !token.is_empty() {
+            masked.push_str(if hides(&token) { MASK } else { &token });
+            token.clear();
+        }
+        masked.push(c);
+    }
+    masked.pop();
+    masked
+}
+
+/// The definitions shown to the player, with the headword hidden
+fn clue(target: &WordInfo) -> Vec<String> {
+    target
+        .defs
+        .iter()
+        .flat_map(|def| {
+            def.definitions
+                .iter()
+                .map(move |definition| (def.functional_label.as_str(), definition))
+        })
+        .filter(|(_, definition)| !definition.trim().is_empty())
+        .take(CLUE_SENSES)
+        .map(|(label, definition)| {
+            let definition = mask_headword(definition, target);
+            if label.is_empty() {
+                definition
+            } else {
+                format!("({}) {}", label, definition)
+            }
+        })
+        .collect()
+}
+
+/// The clue with the letters revealed so far, as shown at the start and by /rules
+fn show_clue(target: &WordInfo, revealed: usize) -> String {
+    format!(
+        "📖 {}\n\nThe word: {} ({} letters)",
+        clue(target).join("\n📖 "),
+        pattern(&target.word, revealed),
+        target.word.chars().count()
+    )
+}
+
+/// Start a new Guess the Word game
+pub async fn start_guess_word(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Guess the Word game");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Guess the Word! I'll give you a definition and you tell me the word. /hint reveals a letter, and you have {} wrong guesses.",
+            MAX_ATTEMPTS
+        ),
+    )
+    .await?;
+
+    for _ in 0..5 {
+        let word = get_random_word(
+            |w| {
+                (MIN_LETTERS..=MAX_LETTERS).contains(&w.len())
+                    && w.chars().all(|c| c.is_ascii_lowercase())
+                    && !is_rare(w)
+            },
+            None,
+        )
+        .await;
+        match word {
+            Ok(target) if !clue(&target).is_empty() => {
+                debug!(chat_id = chat_id.0, game = GAME; "Guess the Word picked '{}'", target.word);
+                bot.send_message(chat_id, show_clue(&target, 0)).await?;
+                game_started(&bot, chat_id).await?;
+                let _ = dialogue
+                    .update(GuessWord {
+                        target,
+                        revealed: 0,
+                        attempts: 0,
+                        progress: Progress::default(),
+                    })
+                    .await;
+                return Ok(());
+            }
+            Ok(target) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Skipping '{}': no definition",
+                    target.word
+                )
+            }
+            Err(e) => {
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e)
+            }
+        }
+    }
+
+    bot.send_message(
+        chat_id,
+        "Sorry, I'm having trouble starting the game. Please try again later.",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle player input during Guess the Word game
+pub async fn guess_word(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (target, revealed, attempts, progress): (WordInfo, usize, u8, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    (target, revealed, attempts, progress),
+                )
+                .await?;
+            }
+            Ok(Command::Skip) => {
+                reveal_word(&bot, msg.chat.id, dialogue, target, &progress, "You passed").await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, revealed, attempts).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "The definition above is all you get — I'll show the full entry once the word is guessed.",
+                )
+                .await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &target, revealed).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Guess the Word game");
+
+                bot.send_message(msg.chat.id, format!("The word was '{}'.", target.word))
+                    .await?;
+                bot.send_message(
+                    msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Guess the Word")),
+                )
+                .await?;
+                let outcome = outcome(&progress, None, None, 0);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                process_guess(
+                    text,
+                    bot,
+                    dialogue,
+                    (target, revealed, attempts, progress),
+                    msg.chat.id,
+                )
+                .await?;
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of a game in which the player guessed `guessed`, if anything, scoring `points`
+pub fn outcome(
+    progress: &Progress,
+    winner: Option<Winner>,
+    guessed: Option<&str>,
+    points: usize,
+) -> GameOutcome {
+    GameOutcome {
+        words: guessed.into_iter().map(String::from).collect(),
+        ..GameOutcome::new(progress, GAME, winner, &[])
+    }
+    .with_scores(points, 0)
+}
+
+/// Process a player's guess
+async fn process_guess(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (target, revealed, attempts, progress): (WordInfo, usize, u8, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if words.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if words.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let guess = normalize_word(words[0]);
+
+    if guess == target.word {
+        let points = points(revealed, attempts);
+        debug!(
+            chat_id = chat_id.0, game = GAME;
+            "Player guessed '{}' for {} points",
+            guess, points
+        );
+        accept_submission(chat_id);
+        bot.send_message(
+            chat_id,
+            format!(
+                "🎉 Yes, it's '{}'! You scored {} points with {} hints and {} wrong guesses.",
+                target.word, points, revealed, attempts
+            ),
+        )
+        .await?;
+        target.send_message(&bot, chat_id, 0).await?;
+        on_player_word(&bot, chat_id, &target.word, 1).await?;
+        let outcome = outcome(&progress, Some(Winner::Player), Some(&target.word), points);
+        return finish_game(&bot, chat_id, dialogue, outcome).await;
+    }
+
+    let attempts = attempts + 1;
+    debug!(
+        chat_id = chat_id.0, game = GAME;
+        "Wrong guess '{}' ({} of {})",
+        guess, attempts, MAX_ATTEMPTS
+    );
+    if attempts >= MAX_ATTEMPTS {
+        return reveal_word(&bot, chat_id, dialogue, target, &progress, "Out of guesses").await;
+    }
+
+    let length_note = if guess.chars().count() != target.word.chars().count() {
+        format!(" My word has {} letters.", target.word.chars().count())
+    } else {
+        String::new()
+    };
+    bot.send_message(
+        chat_id,
+        format!(
+            "Not quite — it isn't '{}'.{} {} guesses left.\n\nThe word: {}",
+            guess,
+            length_note,
+            MAX_ATTEMPTS - attempts,
+            pattern(&target.word, revealed)
+        ),
+    )
+    .await?;
+
+    let _ = dialogue
+        .update(GuessWord {
+            target,
+            revealed,
+            attempts,
+            progress,
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Reveal the next letter of the word, leaving at least the last one to guess
+async fn provide_hint(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    (target, revealed, attempts, progress): (WordInfo, usize, u8, Progress),
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    if revealed + 1 >= target.word.chars().count() {
+        bot.send_message(
+            chat_id,
+            format!(
+                "Only the last letter is left — you can do it!\n\nThe word: {}",
+                pattern(&target.word, revealed)
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let revealed = revealed + 1;
+    bot.send_message(
+        chat_id,
+        format!(
+            "Hint: {}\n\nA right guess now scores {} points.",
+            pattern(&target.word, revealed),
+            points(revealed, attempts)
+        ),
+    )
+    .await?;
+
+    let _ = dialogue
+        .update(GuessWord {
+            target,
+            revealed,
+            attempts,
+            progress,
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Give the word away, after the player passes or runs out of guesses, and end the game
+async fn reveal_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    target: WordInfo,
+    progress: &Progress,
+    reason: &str,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Revealing '{}'", target.word);
+
+    bot.send_message(
+        chat_id,
+        format!("{}! The word was '{}'.", reason, target.word),
+    )
+    .await?;
+    target.send_message(bot, chat_id, 0).await?;
+    let outcome = outcome(progress, None, None, 0);
+    finish_game(bot, chat_id, dialogue, outcome).await
+}
+
+/// Show the current game score
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    revealed: usize,
+    attempts: u8,
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Guess the Word score:\nHints used: {}\nWrong guesses: {} of {}\nA right guess now scores {} points",
+            revealed,
+            attempts,
+            MAX_ATTEMPTS,
+            points(revealed, attempts)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    target: &WordInfo,
+    revealed: usize,
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Guess the Word rules:\n\
+            1. I show a word's definition with the word itself hidden\n\
+            2. Reply with the word you think it is\n\
+            3. A right guess scores {} points, less {} for each hint and {} for each wrong guess\n\
+            4. /hint reveals the next letter\n\
+            5. After {} wrong guesses, or /skip, I reveal the word\n\n\
+            {}",
+            MAX_POINTS,
+            HINT_COST,
+            MISS_COST,
+            MAX_ATTEMPTS,
+            show_clue(target, revealed)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
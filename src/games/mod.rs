@@ -1,6 +1,971 @@
 pub mod alphabet_sprint;
+pub mod anagram_shuffle;
+pub mod boggle;
+pub mod categories;
+pub mod compound_chain;
 pub mod forbidden_letters;
+pub mod ghost;
+pub mod group;
+pub mod guess_word;
+pub mod outcome;
+pub mod preview;
+pub mod relay;
+pub mod rhyme_time;
 pub mod scrambled;
+pub mod self_play;
 pub mod synonym_string;
+pub mod turn_timer;
+pub mod vocab_check;
 pub mod word_chain;
 pub mod word_ladder;
+
+use crate::achievements::on_player_word;
+use crate::command::Command;
+use crate::contains_any;
+use crate::dictionary::{
+    get_known_word_details, get_random_word, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{
+    has_any_word, letter_pool_sizes, letter_pools, most_similar, words_ending_with,
+};
+use crate::games::group::check_turn;
+use crate::games::outcome::{
+    chain_rewound, opener_reseeded, skips_left, take_back_turns, turns_taken, use_skip, Progress,
+};
+use crate::games::turn_timer::arm_turn_timer;
+use crate::preferences::defines_on_demand;
+use crate::settings::get_settings;
+use crate::tone::{say, Phrase};
+use itertools::Itertools;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::{Requester, ResponseResult};
+use teloxide::types::{
+    ChatId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup, Message,
+    MessageId, UserId,
+};
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Letters with fewer words than this starting with them count as dead ends
+const DEAD_END_POOL: usize = 25;
+/// Number of letters /deadends always lists, even when no pool is that small
+const DEAD_END_MIN_SHOWN: usize = 5;
+/// A chain letter is healthy when its pool is at least the average pool divided by this
+const HEALTHY_POOL_DIVISOR: usize = 4;
+/// Nearest words in meaning searched for a playable alternative to a rejected word
+const SUGGESTION_POOL: usize = 50;
+/// Most alternatives offered for a rejected word
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The latest player message in each chat and whether it was accepted as a word
+static SUBMISSIONS: OnceLock<Mutex<HashMap<ChatId, Submission>>> = OnceLock::new();
+
+/// A player message taken as a word
+struct Submission {
+    message: MessageId,
+    /// Whether the word was accepted
+    accepted: bool,
+    /// Who sent it, and their display name
+    sender: Option<(UserId, String)>,
+}
+
+fn get_submissions() -> &'static Mutex<HashMap<ChatId, Submission>> {
+    SUBMISSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Chats with a move still being worked through: a player's message, or the turn timer or game
+/// clock acting on the game
+static PROCESSING: OnceLock<Mutex<HashSet<ChatId>>> = OnceLock::new();
+
+fn get_processing() -> &'static Mutex<HashSet<ChatId>> {
+    PROCESSING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Keeps a chat marked as busy with a move until dropped
+pub struct Processing(ChatId);
+
+impl Drop for Processing {
+    fn drop(&mut self) {
+        get_processing().lock().unwrap().remove(&self.0);
+    }
+}
+
+/// Mark the chat as busy with a move, or `None` if it already is. Every game handler holds this
+/// for the whole message, commands like /skip and /rewind included, and the turn timer and game
+/// clock hold it while they act, so neither changes a game halfway through the other's move.
+pub fn begin_processing(chat_id: ChatId) -> Option<Processing> {
+    if !get_processing().lock().unwrap().insert(chat_id) {
+        return None;
+    }
+    Some(Processing(chat_id))
+}
+
+/// How often a timer waiting on a move the chat is busy with checks whether it's done
+const PROCESSING_POLL: Duration = Duration::from_millis(200);
+
+/// Mark the chat as busy with a move, first waiting out the one it's busy with, if any
+pub async fn wait_processing(chat_id: ChatId) -> Processing {
+    loop {
+        if let Some(processing) = begin_processing(chat_id) {
+            return processing;
+        }
+        tokio::time::sleep(PROCESSING_POLL).await;
+    }
+}
+
+/// Reply to a message sent while the chat is still busy with the move before it
+pub async fn still_processing(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(chat_id, "One moment... I'm still working on the last move.")
+        .await?;
+    Ok(())
+}
+
+/// Whether a message that isn't one of our commands still looks like a command ("/stopp",
+/// "/stop@other_bot"). Words are always typed without a slash, so these are never played;
+/// a bare "stop" is a word like any other.
+pub fn looks_like_command(text: &str) -> bool {
+    text.trim_start().starts_with('/')
+}
+
+/// Answer a command we don't know during a game. Commands addressed to another bot in a
+/// group are left alone.
+pub async fn reply_unknown_command(bot: &Bot, chat_id: ChatId, text: &str) -> ResponseResult<()> {
+    let command = text.split_whitespace().next().unwrap_or_default();
+    if command.contains('@') {
+        return Ok(());
+    }
+    bot.send_message(
+        chat_id,
+        format!(
+            "I don't know the command {}. Words are typed without a slash, and /rules lists \
+            what you can do.",
+            command
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// List every command with its description. Works in and out of games without stopping them.
+pub async fn show_help(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(chat_id, Command::descriptions().to_string())
+        .await?;
+    Ok(())
+}
+
+/// Normalize a player's word for validation and lookup: lowercased, curly apostrophes made
+/// straight and surrounding punctuation trimmed. With STRIP_WORD_PUNCTUATION, hyphens and
+/// apostrophes inside the word are dropped too ("e-mail" → "email").
+pub fn normalize_word(raw: &str) -> String {
+    let word = raw
+        .to_lowercase()
+        .replace(['\u{2018}', '\u{2019}'], "'")
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_string();
+    if get_settings().strip_word_punctuation {
+        word.chars().filter(|c| !matches!(c, '-' | '\'')).collect()
+    } else {
+        word
+    }
+}
+
+/// The letter the next word must start with: the last letter of `word`, skipping any trailing
+/// hyphen or apostrophe ("goin'" → 'n')
+pub fn chain_letter(word: &str) -> Option<char> {
+    word.chars().rev().find(|c| c.is_alphabetic())
+}
+
+/// Whether a chain can comfortably go on from `word`: at least a quarter of the average letter's
+/// vocabulary starts with its chain letter, unlike letters such as 'x' or 'q'
+pub fn has_healthy_continuation(word: &str) -> bool {
+    chain_letter(word).is_none_or(is_healthy_letter)
+}
+
+/// Whether at least a quarter of the average letter's vocabulary starts with `letter`
+pub fn is_healthy_letter(letter: char) -> bool {
+    let Ok(pools) = letter_pools() else {
+        return true;
+    };
+    pools
+        .iter()
+        .any(|&(c, n)| c == letter && n * HEALTHY_POOL_DIVISOR >= average_pool(pools))
+}
+
+/// Letters some playable word satisfying `predicate` starts with, i.e. those a chain can
+/// carry on from. Worked out once per game start, so opening words can be picked by their
+/// chain letter without searching the vocabulary for each candidate.
+pub fn open_letters<P>(predicate: P) -> Vec<char>
+where
+    P: Fn(&str) -> bool,
+{
+    ('a'..='z')
+        .filter(|&letter| has_any_word(letter, |w| predicate(w) && is_playable(w)))
+        .collect()
+}
+
+/// Average number of words starting with a letter
+fn average_pool(pools: &[(char, usize)]) -> usize {
+    pools.iter().map(|(_, n)| n).sum::<usize>() / pools.len().max(1)
+}
+
+/// Which way a chain links each word to the next
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// The next word starts with the previous word's last letter
+    #[default]
+    Forward,
+    /// The next word ends with the previous word's first letter
+    Reverse,
+}
+
+impl Direction {
+    /// Whether a chain can comfortably go on from `word` in this direction
+    pub fn has_healthy_link(self, word: &str) -> bool {
+        match self {
+            Direction::Forward => has_healthy_continuation(word),
+            Direction::Reverse => {
+                let Some(letter) = self.link_letter(word) else {
+                    return true;
+                };
+                match (words_ending_with(letter), letter_pools()) {
+                    (Ok(ending), Ok(pools)) => {
+                        ending.len() * HEALTHY_POOL_DIVISOR >= average_pool(pools)
+                    }
+                    _ => true,
+                }
+            }
+        }
+    }
+
+    /// The letter `word` passes on to the next word
+    pub fn link_letter(self, word: &str) -> Option<char> {
+        match self {
+            Direction::Forward => chain_letter(word),
+            Direction::Reverse => word.chars().find(|c| c.is_alphabetic()),
+        }
+    }
+
+    /// Letters a chain in this direction can carry on from
+    pub fn open_letters(self) -> Vec<char> {
+        match self {
+            Direction::Forward => open_letters(|_| true),
+            Direction::Reverse => ('a'..='z')
+                .filter(|&letter| {
+                    words_ending_with(letter)
+                        .is_ok_and(|words| words.iter().any(|w| is_playable(w)))
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether `word` can open a chain in this direction: its link letter is one of the `open`
+    /// letters the chain can carry on from
+    pub fn opens(self, word: &str, open: &[char]) -> bool {
+        self.link_letter(word).is_some_and(|c| open.contains(&c))
+    }
+
+    /// Whether `word` continues a chain whose link letter is `letter`
+    pub fn fits(self, word: &str, letter: char) -> bool {
+        match self {
+            Direction::Forward => word.starts_with(letter),
+            Direction::Reverse => chain_letter(word) == Some(letter),
+        }
+    }
+
+    /// How the next word has to use `letter`, e.g. "starting with 'a'"
+    pub fn describe(self, letter: char) -> String {
+        match self {
+            Direction::Forward => format!("starting with '{}'", letter),
+            Direction::Reverse => format!("ending with '{}'", letter),
+        }
+    }
+}
+
+/// Record a new player message as the chat's latest submission
+pub fn record_submission(msg: &Message) {
+    get_submissions().lock().unwrap().insert(
+        msg.chat.id,
+        Submission {
+            message: msg.id,
+            accepted: false,
+            sender: msg.from.as_ref().map(|user| (user.id, user.full_name())),
+        },
+    );
+}
+
+/// Who sent the chat's latest submission, and their display name
+pub fn submission_sender(chat_id: ChatId) -> Option<(UserId, String)> {
+    get_submissions()
+        .lock()
+        .unwrap()
+        .get(&chat_id)?
+        .sender
+        .clone()
+}
+
+/// Announce a word the bot played. With /definebutton on, its definition waits behind a
+/// 📖 Define button on the announcement instead of following as a card of its own.
+pub async fn announce_bot_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    phrase: Phrase<'_>,
+    word: &WordInfo,
+) -> ResponseResult<()> {
+    let text = say(chat_id, phrase);
+    if defines_on_demand(chat_id) {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "📖 Define",
+            format!("define_{}", word.word),
+        )]]);
+        bot.send_message(chat_id, text)
+            .reply_markup(keyboard)
+            .await?;
+        return Ok(());
+    }
+    bot.send_message(chat_id, text).await?;
+    word.send_message(bot, chat_id, 0).await
+}
+
+/// The bot couldn't answer the player's word for a reason that may pass, such as the
+/// dictionary being unreachable. Rather than end the game as a win the player didn't earn, the
+/// word is taken back so they can play the turn again: the caller leaves the game's state as it
+/// was before the word.
+pub async fn retry_turn(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        "Sorry, I couldn't look up my reply just now. That one's on me — please send your word again in a moment.",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle /skip in a chain game against the bot: use up one of the player's skips and say how
+/// many are left. Returns whether the turn may be skipped; only the player whose turn it is
+/// may skip it, and once the skips run out the player has to play a word or give up with /stop.
+pub async fn allow_skip(bot: &Bot, msg: &Message, progress: &mut Progress) -> ResponseResult<bool> {
+    let chat_id = msg.chat.id;
+    if !check_turn(bot, msg, progress).await? {
+        return Ok(false);
+    }
+    if !use_skip(progress) {
+        bot.send_message(chat_id, "No skips left — submit a word or /stop.")
+            .await?;
+        return Ok(false);
+    }
+    if let Some(left) = skips_left(progress) {
+        let text = match left {
+            0 => "That was your last skip.".to_string(),
+            1 => "1 skip left.".to_string(),
+            n => format!("{} skips left.", n),
+        };
+        bot.send_message(chat_id, text).await?;
+    }
+
+    Ok(true)
+}
+
+/// Handle /def: show the definition card of a word played in the game in progress, looking it
+/// up afresh when it isn't in the chain
+pub async fn define_chain_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    args: &str,
+) -> ResponseResult<()> {
+    let word = normalize_word(args.trim());
+    if word.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Usage: /def <word> — shows the definition of a word played in this game.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Inflected forms find the word they were played as
+    if let Some(played) = chain
+        .iter()
+        .find(|w| w.word == word || w.stems.contains(&word))
+    {
+        return played.send_message(bot, chat_id, 0).await;
+    }
+
+    match get_word_details(&word).await {
+        Ok(details) => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "'{}' hasn't been played in this game, but here's what it means:",
+                    word
+                ),
+            )
+            .await?;
+            details.send_message(bot, chat_id, 0).await?;
+        }
+        Err(e) => {
+            debug!(chat_id = chat_id.0; "No definition for '{}': {:?}", word, e);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "'{}' hasn't been played in this game, and I couldn't find a definition for it.",
+                    word
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Words in the chain while the bot answers the opening exchange: its opener and the player's
+/// first word
+const OPENING_EXCHANGE: usize = 2;
+
+/// When the bot can't answer the opening exchange, conceding would end the game before any
+/// real play. Instead, start over from a new opener that fits `predicate` and announce it.
+/// Returns `None` once the game is past its opening exchange or no other opener fits either.
+pub async fn reseed_opener<P>(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &mut Progress,
+    predicate: P,
+) -> ResponseResult<Option<WordInfo>>
+where
+    P: Fn(&str) -> bool,
+{
+    if chain.len() > OPENING_EXCHANGE {
+        return Ok(None);
+    }
+
+    // A random letter can come up empty, so give it a few tries like starting a game does
+    let fits = |w: &str| predicate(w) && !chain.iter().any(|played| played.word == w);
+    let mut opener = None;
+    for _ in 0..3 {
+        match get_random_word(&fits, None).await {
+            Ok(word) => {
+                opener = Some(word);
+                break;
+            }
+            Err(e) => debug!(chat_id = chat_id.0; "No replacement opener yet: {:?}", e),
+        }
+    }
+    let Some(opener) = opener else {
+        return Ok(None);
+    };
+
+    info!(
+        chat_id = chat_id.0;
+        "Bot couldn't answer the opening exchange, starting over from '{}'",
+        opener.word
+    );
+    opener_reseeded(progress);
+    bot.send_message(
+        chat_id,
+        "I can't find a reply to that one, and it's too early to call the game. Let's start over from a word with more room to grow.",
+    )
+    .await?;
+    announce_bot_word(bot, chat_id, Phrase::FirstWord(&opener.word), &opener).await?;
+
+    Ok(Some(opener))
+}
+
+/// Reply to /rewind in games where the chain can't branch
+pub const REWIND_UNAVAILABLE: &str = "Rewinding works in Word Chain against me, Last Letter \
+    Scramble, Synonym String, Word Ladder and Forbidden Letters.";
+
+/// Take the chain back the number of turns given to /rewind (one by default), always keeping
+/// the opening word. Each turn goes back to where it began, however many words it added.
+/// Returns the turns taken back, or why the chain can't be rewound.
+pub fn rewind_chain(
+    chain: &mut Vec<WordInfo>,
+    progress: &mut Progress,
+    args: &str,
+) -> Result<usize, String> {
+    let args = args.trim();
+    let turns = match args.parse::<usize>() {
+        _ if args.is_empty() => 1,
+        Ok(turns) if turns > 0 => turns,
+        _ => return Err(
+            "Usage: /rewind [turns], e.g. /rewind 2 takes back your last two words and my replies."
+                .to_string(),
+        ),
+    };
+
+    let available = turns_taken(progress);
+    if available == 0 {
+        return Err("There's nothing to rewind: the chain is at my opening word.".to_string());
+    }
+    if turns > available {
+        return Err(format!(
+            "I can only rewind {} turn(s) — the opening word stays.",
+            available
+        ));
+    }
+
+    chain.truncate(take_back_turns(progress, turns));
+    Ok(turns)
+}
+
+/// Tell the player where a rewound chain picks up again
+pub async fn announce_rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    turns: usize,
+    chain: &[WordInfo],
+    progress: &mut Progress,
+    prompt: &str,
+) -> ResponseResult<()> {
+    chain_rewound(chat_id, progress);
+    let last = chain.last().map(|w| w.word.as_str()).unwrap_or_default();
+    bot.send_message(
+        chat_id,
+        format!(
+            "Rewound {} turn(s). The chain is back at '{}' — try a different path!\n\n{}",
+            turns, last, prompt
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Mark the chat's latest submission as accepted, so editing it won't replay it
+pub fn accept_submission(chat_id: ChatId) {
+    arm_turn_timer(chat_id);
+    if let Some(submission) = get_submissions().lock().unwrap().get_mut(&chat_id) {
+        submission.accepted = true;
+    }
+}
+
+/// Credit a chain game's word once it stands: mark the submission accepted and record any
+/// badges it earns. Called only after the bot has answered or given in, so a word that
+/// `retry_turn` takes back leaves no badge and no running turn timer behind.
+pub async fn credit_player_word(
+    bot: &Bot,
+    chat_id: ChatId,
+    word: &str,
+    chain_len: usize,
+) -> ResponseResult<()> {
+    accept_submission(chat_id);
+    on_player_word(bot, chat_id, word, chain_len).await
+}
+
+/// Whether an edited message should be replayed as a new attempt: it must be the chat's
+/// latest submission, a word rather than a command, and its previous version rejected
+pub fn is_retryable_edit(msg: &Message) -> bool {
+    let is_word = msg.text().is_some_and(|t| !t.trim_start().starts_with('/'));
+    is_word
+        && get_submissions()
+            .lock()
+            .unwrap()
+            .get(&msg.chat.id)
+            .is_some_and(|submission| submission.message == msg.id && !submission.accepted)
+}
+
+/// Whether `word` is the player's most recently accepted word, e.g. a resent message.
+///
+/// `turns_back` is how many entries from the end the player's last word sits: 2 when the
+/// bot has replied since, 1 when the other hotseat player is up.
+pub fn is_just_played(chain: &[WordInfo], word: &str, turns_back: usize) -> bool {
+    chain
+        .len()
+        .checked_sub(turns_back)
+        .and_then(|i| chain.get(i))
+        .is_some_and(|w| w.word == word)
+}
+
+/// Concession when no word in the vocabulary starts with the letter the chain needs
+pub fn dead_end_message(letter: char) -> String {
+    format!(
+        "No word in my vocabulary starts with '{}', so the chain can't go on. You win this round!",
+        letter
+    )
+}
+
+/// Reply when the bot runs out of words but the game carries on, followed by the player's prompt
+pub fn carry_on_message(prompt: &str) -> String {
+    format!(
+        "I'm out of words, so this round is yours! The chain is still going though — {}. \
+        Use /stop when you're done.",
+        prompt
+    )
+}
+
+/// Reply to a resubmission of the word the player just played
+pub fn just_played_message(word: &str) -> String {
+    format!(
+        "You already played '{}' (looks like it was sent twice) — it's still your turn.",
+        word
+    )
+}
+
+/// Handle /deadends: list the letters with the fewest words to continue from. Words containing a
+/// `forbidden` letter don't count, as in Forbidden Letters.
+pub async fn show_dead_ends(bot: &Bot, chat_id: ChatId, forbidden: &[char]) -> ResponseResult<()> {
+    let pools = if forbidden.is_empty() {
+        letter_pools().map(|pools| pools.to_vec())
+    } else {
+        letter_pool_sizes(|w| !w.chars().any(|c| forbidden.contains(&c)))
+    };
+    let pools = match pools {
+        Ok(pools) => pools,
+        Err(e) => {
+            error!("Failed to count words per letter: {:?}", e);
+            bot.send_message(chat_id, "Sorry, I can't check the vocabulary right now.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let lines = pools
+        .iter()
+        .filter(|(c, _)| !forbidden.contains(c))
+        .sorted_by_key(|(_, size)| *size)
+        .enumerate()
+        .take_while(|(i, (_, size))| *i < DEAD_END_MIN_SHOWN || *size < DEAD_END_POOL)
+        .map(|(_, (c, size))| match size {
+            0 => format!("• '{}': no words at all", c),
+            _ => format!("• '{}': {} words", c, size),
+        })
+        .join("\n");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Hardest letters to continue from — ending your word on one of these leaves the next \
+            player few options:\n{}",
+            lines
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Tell the player their word was rejected. If the word is in the embeddings vocabulary, also
+/// offer up to three of the closest words in meaning that can be played, `fit` the game's
+/// rules and haven't been `used` yet, as reply keyboard buttons that resubmit one in one tap.
+pub async fn reject_word<F>(
+    bot: &Bot,
+    chat_id: ChatId,
+    word: &str,
+    error: &DictionaryError,
+    used: &[String],
+    fits: F,
+) -> ResponseResult<()>
+where
+    F: Fn(&str) -> bool,
+{
+    let suggestions = suggest_words(word, used, fits).await;
+
+    let text = match suggestions.as_slice() {
+        [] => {
+            bot.send_message(chat_id, error.rejection_message(word))
+                .await?;
+            return Ok(());
+        }
+        [suggestion] => format!(
+            "{} '{}' is close in meaning, though — tap it to play it instead.",
+            error.rejection_message(word),
+            suggestion
+        ),
+        [rest @ .., last] => format!(
+            "{} {} and '{}' are close in meaning, though — tap one to play it instead.",
+            error.rejection_message(word),
+            rest.iter()
+                .map(|s| format!("'{}'", s))
+                .collect::<Vec<String>>()
+                .join(", "),
+            last
+        ),
+    };
+    let keyboard = KeyboardMarkup::new(vec![suggestions
+        .iter()
+        .map(KeyboardButton::new)
+        .collect::<Vec<KeyboardButton>>()])
+    .one_time_keyboard()
+    .resize_keyboard();
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Words close in meaning to a rejected one that the player could play instead, closest first.
+/// Only words already in the cache or offline dictionary are offered, so a rejection never waits
+/// on dictionary requests, and their stems are checked so nothing already played is offered;
+/// none at all when the rejected word isn't in the embeddings vocabulary.
+async fn suggest_words<F>(word: &str, used: &[String], fits: F) -> Vec<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let Ok(similar) = most_similar(word, SUGGESTION_POOL) else {
+        return Vec::new();
+    };
+
+    let mut suggestions = Vec::new();
+    for (candidate, _) in similar {
+        if !fits(&candidate) || used.contains(&candidate) {
+            continue;
+        }
+        match get_known_word_details(&candidate).await {
+            Some(details) if !contains_any(used, &details.stems) => suggestions.push(candidate),
+            Some(_) => debug!("Not suggesting '{}': already played", candidate),
+            None => continue,
+        }
+        if suggestions.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::{me, player_message, FakeBotApi};
+    use crate::games::outcome::begin_turn;
+    use crate::state::{MyDialogue, State};
+    use crate::{achievements, fake_dictionary};
+    use teloxide::dispatching::dialogue::{InMemStorage, Storage};
+
+    #[test]
+    fn normalize_word_keeps_inner_hyphens_and_apostrophes() {
+        assert_eq!(normalize_word("Jack-o'-Lantern"), "jack-o'-lantern");
+        assert_eq!(normalize_word("rock\u{2019}n\u{2019}roll"), "rock'n'roll");
+        assert_eq!(normalize_word("'twas"), "twas");
+        assert_eq!(normalize_word("goin'"), "goin");
+        assert_eq!(normalize_word("-e-mail-"), "e-mail");
+    }
+
+    #[test]
+    fn chain_letter_skips_trailing_hyphens_and_apostrophes() {
+        assert_eq!(chain_letter("goin'"), Some('n'));
+        assert_eq!(chain_letter("self-"), Some('f'));
+        assert_eq!(chain_letter("jack-o'-lantern"), Some('n'));
+        assert_eq!(chain_letter("'-"), None);
+    }
+
+    #[test]
+    fn command_words_without_a_slash_are_played() {
+        for word in ["stop", "skip", "hint", "Stop"] {
+            assert!(Command::parse(word, "kotosume_bot").is_err());
+            assert!(!looks_like_command(word));
+        }
+        assert!(matches!(
+            Command::parse("/stop", "kotosume_bot"),
+            Ok(Command::Stop)
+        ));
+        assert!(looks_like_command(" /stopp"));
+    }
+
+    #[test]
+    fn nothing_opens_a_chain_when_no_word_qualifies() {
+        let open = open_letters(|_| false);
+        assert!(open.is_empty());
+        for direction in [Direction::Forward, Direction::Reverse] {
+            assert!(!direction.opens("cat", &open));
+        }
+
+        assert!(Direction::Forward.opens("cat", &['t']));
+        assert!(!Direction::Reverse.opens("cat", &['t']));
+        assert!(!Direction::Forward.opens("'-", &['t']));
+    }
+
+    #[test]
+    fn second_submission_waits_for_the_first() {
+        let chat_id = ChatId(-2292);
+        let first = begin_processing(chat_id);
+        assert!(first.is_some());
+        assert!(begin_processing(chat_id).is_none());
+        // Another chat is unaffected
+        assert!(begin_processing(ChatId(-2293)).is_some());
+
+        drop(first);
+        assert!(begin_processing(chat_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn words_sent_mid_move_wait_and_leave_the_chain_alone() {
+        let api = FakeBotApi::start().await;
+        let chat_id = ChatId(2295);
+        let dialogue = MyDialogue::new(InMemStorage::<State>::new().erase(), chat_id);
+        let chain = chain_of(&["cat"]);
+        dialogue
+            .update(State::WordChain {
+                chain: chain.clone(),
+                curr_char: 't',
+                hotseat: None,
+                board: None,
+                direction: Direction::Forward,
+                progress: Progress::default(),
+            })
+            .await
+            .unwrap();
+
+        // The first word is still being worked through when two more arrive
+        let _first = begin_processing(chat_id).unwrap();
+        for (id, word) in [(2, "tiger"), (3, "toad")] {
+            let game = (
+                chain.clone(),
+                't',
+                None,
+                None,
+                Direction::Forward,
+                Progress::default(),
+            );
+            let msg = player_message(id, chat_id.0, word);
+            word_chain::word_chain(api.bot(), dialogue.clone(), game, msg, me())
+                .await
+                .unwrap();
+        }
+
+        let wait = "One moment... I'm still working on the last move.";
+        assert_eq!(api.sent(), [wait, wait]);
+        let state = dialogue.get().await.unwrap().unwrap();
+        assert_eq!(words_of(state.chain()), ["cat"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn word_taken_back_after_a_failed_reply_earns_nothing() {
+        fake_dictionary::install();
+        let api = FakeBotApi::start().await;
+        let chat_id = ChatId(2227);
+        let dialogue = MyDialogue::new(InMemStorage::<State>::new().erase(), chat_id);
+        let mut progress = Progress::default();
+        *progress.roster_mut() = serde_json::from_value(serde_json::json!({
+            "players": [[chat_id.0, "Player"], [2, "Bo"]],
+            "current": 0,
+        }))
+        .unwrap();
+        let roster = serde_json::to_value(progress.roster()).unwrap();
+        let chain = chain_of(&["cat"]);
+        dialogue
+            .update(State::WordChain {
+                chain: chain.clone(),
+                curr_char: 't',
+                hotseat: None,
+                board: None,
+                direction: Direction::Forward,
+                progress: progress.clone(),
+            })
+            .await
+            .unwrap();
+
+        // Every reply to "tiger" fails to look up, as if the dictionary were down
+        let msg = player_message(2, chat_id.0, "tiger");
+        record_submission(&msg);
+        let game = (chain, 't', None, None, Direction::Forward, progress);
+        word_chain::word_chain(api.bot(), dialogue.clone(), game, msg.clone(), me())
+            .await
+            .unwrap();
+
+        assert!(fake_dictionary::lookups("rabbit") > 0);
+        assert!(api
+            .sent()
+            .last()
+            .unwrap()
+            .contains("please send your word again"));
+        assert!(!api
+            .sent()
+            .iter()
+            .any(|text| text.contains("you're up next")));
+        assert!(!achievements::has_progress(chat_id));
+        assert!(is_retryable_edit(&msg));
+        let Some(State::WordChain {
+            chain, progress, ..
+        }) = dialogue.get().await.unwrap()
+        else {
+            panic!("the game should still be on");
+        };
+        assert_eq!(words_of(&chain), ["cat"]);
+        assert_eq!(serde_json::to_value(progress.roster()).unwrap(), roster);
+    }
+
+    #[tokio::test]
+    async fn timer_waits_for_the_move_in_progress() {
+        let chat_id = ChatId(-2294);
+        let word = begin_processing(chat_id).unwrap();
+        let timer = tokio::spawn(async move {
+            let _processing = wait_processing(chat_id).await;
+        });
+
+        tokio::time::sleep(PROCESSING_POLL * 2).await;
+        assert!(!timer.is_finished());
+        drop(word);
+        timer.await.unwrap();
+        assert!(begin_processing(chat_id).is_some());
+    }
+
+    fn chain_of(words: &[&str]) -> Vec<WordInfo> {
+        words
+            .iter()
+            .map(|w| WordInfo {
+                word: w.to_string(),
+                stems: vec![w.to_string()],
+                defs: Vec::new(),
+                audio: None,
+            })
+            .collect()
+    }
+
+    fn words_of(chain: &[WordInfo]) -> Vec<&str> {
+        chain.iter().map(|w| w.word.as_str()).collect()
+    }
+
+    #[test]
+    fn rewind_after_a_skip_takes_back_only_the_bots_word() {
+        let mut progress = Progress::default();
+        let mut chain = chain_of(&["cat"]);
+        // The player plays "tiger" and the bot answers "rabbit"
+        begin_turn(&mut progress, chain.len());
+        chain.extend(chain_of(&["tiger", "rabbit"]));
+        // The player skips, so the bot plays "trout" on its own
+        begin_turn(&mut progress, chain.len());
+        chain.extend(chain_of(&["trout"]));
+
+        assert_eq!(rewind_chain(&mut chain, &mut progress, ""), Ok(1));
+        assert_eq!(words_of(&chain), ["cat", "tiger", "rabbit"]);
+        assert_eq!(chain_letter(&chain.last().unwrap().word), Some('t'));
+
+        assert!(rewind_chain(&mut chain, &mut progress, "2").is_err());
+        assert_eq!(rewind_chain(&mut chain, &mut progress, "1"), Ok(1));
+        assert_eq!(words_of(&chain), ["cat"]);
+        assert!(rewind_chain(&mut chain, &mut progress, "").is_err());
+    }
+
+    #[test]
+    fn rewind_after_a_reseed_stops_at_the_new_opener() {
+        let mut progress = Progress::default();
+        let chain = chain_of(&["fox"]);
+        begin_turn(&mut progress, chain.len());
+        // The bot can't answer "xenon", so the game starts over from a new opener
+        opener_reseeded(&mut progress);
+        let mut chain = chain_of(&["dog"]);
+        assert!(rewind_chain(&mut chain, &mut progress, "").is_err());
+
+        begin_turn(&mut progress, chain.len());
+        chain.extend(chain_of(&["goat", "tapir"]));
+        // The bot concedes "rat", and the player carries on alone with "tern"
+        begin_turn(&mut progress, chain.len());
+        chain.extend(chain_of(&["rat"]));
+        begin_turn(&mut progress, chain.len());
+        chain.extend(chain_of(&["tern"]));
+
+        assert_eq!(rewind_chain(&mut chain, &mut progress, ""), Ok(1));
+        assert_eq!(words_of(&chain), ["dog", "goat", "tapir", "rat"]);
+        assert_eq!(rewind_chain(&mut chain, &mut progress, "2"), Ok(2));
+        assert_eq!(words_of(&chain), ["dog"]);
+        assert_eq!(chain_letter(&chain.last().unwrap().word), Some('g'));
+    }
+}
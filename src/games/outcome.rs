@@ -0,0 +1,495 @@
+use crate::achievements::on_win;
+use crate::dictionary::WordInfo;
+use crate::frequency::rarity_bonus;
+use crate::games::group::Roster;
+use crate::games::turn_timer::{arm_turn_timer, cancel_turn_timer, last_turn_time};
+use crate::games::{relay, submission_sender};
+use crate::persist::unix_now;
+use crate::settings::get_settings;
+use crate::state::State::Start;
+use crate::state::{MyDialogue, State};
+use crate::stats::{on_game_started, record_game, record_player_game};
+use crate::tone::{say, Phrase};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, UserId};
+use teloxide::Bot;
+
+/// Who won a finished game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Winner {
+    Player,
+    /// Hotseat player number (1 or 2)
+    Seat(usize),
+    /// Hotseat game with equal scores
+    Tie,
+}
+
+/// Summary of a finished game, handed to everything that reacts to a game ending
+#[derive(Clone, Debug)]
+pub struct GameOutcome {
+    /// Menu id of the game, e.g. "word_chain"
+    pub game: &'static str,
+    /// `None` when the game was stopped or ended without a result
+    pub winner: Option<Winner>,
+    /// Player's score, or Player 1's in a hotseat game
+    pub player_score: usize,
+    /// Bot's score, or Player 2's in a hotseat game
+    pub bot_score: usize,
+    /// Words played, in order
+    pub words: Vec<String>,
+    pub duration: Duration,
+    /// How the game went, for crediting the player's words
+    pub progress: Progress,
+}
+
+/// Points a word scores when played within the grace period, or always without score decay
+pub const FULL_POINTS: u32 = 10;
+
+/// A word the player had accepted and what it scored
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PlayedWord {
+    /// Points for the word itself: its length plus its rarity bonus
+    points: u32,
+    /// Speed points for how long the turn took
+    speed: u32,
+    /// Who played it, and their display name
+    player: Option<(UserId, String)>,
+}
+
+/// How a game in progress is going. It's kept in the game's state, so a game resumed after a
+/// restart carries on with its points and start time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Progress {
+    /// Unix time in seconds the game started
+    started: u64,
+    /// Whether the bot conceded but the game carried on
+    conceded: bool,
+    /// Each word the player has had accepted, counted towards the word target
+    words: Vec<PlayedWord>,
+    /// Skips the player has left, or `None` when skips are unlimited
+    #[serde(default)]
+    skips_remaining: Option<u32>,
+    /// Where each of the player's turns began, oldest first
+    #[serde(default)]
+    turns: Vec<TurnStart>,
+    /// Players who joined to take turns in a group chat
+    #[serde(default)]
+    roster: Roster,
+}
+
+/// Where a turn of the player's began. A turn adds their word and the bot's reply, only the
+/// bot's word after a /skip, or only their word once the bot has conceded, so /rewind takes
+/// back whatever the chain and the counted words grew by since.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct TurnStart {
+    /// Length of the chain before the turn
+    chain_len: usize,
+    /// Number of words the player had had accepted before the turn
+    words: usize,
+}
+
+/// A game starting now. Games stored before their progress was kept are timed from when
+/// they're resumed.
+impl Default for Progress {
+    fn default() -> Self {
+        Progress {
+            started: unix_now(),
+            conceded: false,
+            words: Vec::new(),
+            skips_remaining: get_settings().skips_per_game,
+            turns: Vec::new(),
+            roster: Roster::default(),
+        }
+    }
+}
+
+/// Points a word scores: one per letter, plus a bonus the rarer it is
+pub fn word_points(word: &str) -> u32 {
+    word.chars().filter(|c| c.is_alphabetic()).count() as u32 + rarity_bonus(word)
+}
+
+/// Record that a game just started in a chat, for its turn timer and the chat's daily streak
+pub async fn game_started(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    arm_turn_timer(chat_id);
+    on_game_started(bot, chat_id).await
+}
+
+/// Called when the bot runs out of words. Returns whether the game should carry on with the
+/// player chaining solo; the player is then credited with the win when the game finishes.
+pub fn bot_concedes(chat_id: ChatId, progress: &mut Progress) -> bool {
+    if !get_settings().continue_after_concession {
+        return false;
+    }
+    info!(chat_id = chat_id.0; "Bot conceded, game continues");
+    progress.conceded = true;
+    true
+}
+
+/// Count a word the player had accepted in a game against the bot, scoring it by its length
+/// and rarity and by how long the turn took. Returns whether it reached the word target, which
+/// wins the game.
+pub fn count_player_word(chat_id: ChatId, progress: &mut Progress, word: &str) -> bool {
+    let settings = get_settings();
+    let speed = match settings.score_decay {
+        Some(decay) => decay.points(FULL_POINTS, last_turn_time(chat_id)),
+        None => FULL_POINTS,
+    };
+    progress.words.push(PlayedWord {
+        points: word_points(word),
+        speed,
+        player: submission_sender(chat_id),
+    });
+    settings
+        .word_target
+        .is_some_and(|target| progress.words.len() >= target)
+}
+
+impl Progress {
+    /// Players taking turns in the game
+    pub fn roster(&self) -> &Roster {
+        &self.roster
+    }
+
+    /// Players taking turns in the game, to join, leave or pass the turn
+    pub fn roster_mut(&mut self) -> &mut Roster {
+        &mut self.roster
+    }
+
+    /// Number of words the player has had accepted, the points those words scored and the
+    /// speed points they earned
+    fn tally(&self) -> (usize, u32, u32) {
+        (
+            self.words.len(),
+            self.words.iter().map(|w| w.points).sum(),
+            self.words.iter().map(|w| w.speed).sum(),
+        )
+    }
+
+    /// Points of the player and of the bot in a game whose words are `words`: the player's
+    /// accepted words score for them, and every other word for the bot
+    fn points_split<'a>(&self, words: impl Iterator<Item = &'a str>) -> (u32, u32) {
+        let (_, player, _) = self.tally();
+        let total = words.map(word_points).sum::<u32>();
+        (player, total.saturating_sub(player))
+    }
+
+    /// Time since the game started
+    fn elapsed(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.started))
+    }
+}
+
+/// The player's turn begins with the chain `chain_len` words long, by playing a word or
+/// skipping
+pub fn begin_turn(progress: &mut Progress, chain_len: usize) {
+    progress.turns.push(TurnStart {
+        chain_len,
+        words: progress.words.len(),
+    });
+}
+
+/// Number of the player's turns /rewind can take back
+pub fn turns_taken(progress: &Progress) -> usize {
+    progress.turns.len()
+}
+
+/// Take back the player's last `turns` turns, at most `turns_taken`: the words they played in
+/// them no longer count towards the word target. Returns how long the chain was before the
+/// first of them.
+pub fn take_back_turns(progress: &mut Progress, turns: usize) -> usize {
+    let first = progress.turns.len() - turns;
+    let start = progress.turns[first];
+    progress.turns.truncate(first);
+    progress.words.truncate(start.words);
+    start.chain_len
+}
+
+/// The player rewound the chain: a concession the bot made along the way no longer stands,
+/// and the turn starts over
+pub fn chain_rewound(chat_id: ChatId, progress: &mut Progress) {
+    progress.conceded = false;
+    arm_turn_timer(chat_id);
+}
+
+/// The game starts over from a new opener because the bot couldn't answer the player's first
+/// word: that word no longer counts, and there's no turn left to take back
+pub fn opener_reseeded(progress: &mut Progress) {
+    progress.words.pop();
+    progress.turns.clear();
+}
+
+/// Skips the player has left in the game in progress, or `None` when skips are unlimited
+pub fn skips_left(progress: &Progress) -> Option<u32> {
+    progress.skips_remaining
+}
+
+/// Use up one of the player's skips. Returns false, using nothing, when they have none left.
+pub fn use_skip(progress: &mut Progress) -> bool {
+    match &mut progress.skips_remaining {
+        Some(0) => false,
+        Some(left) => {
+            *left -= 1;
+            true
+        }
+        None => true,
+    }
+}
+
+/// /score line with the skips the player has left, when skips are limited
+pub fn skips_extra(progress: &Progress) -> Option<String> {
+    let limit = get_settings().skips_per_game?;
+    skips_left(progress).map(|left| format!("Skips left: {}/{}", left, limit))
+}
+
+/// Extra /score lines: points for the words played so far, then progress towards the word
+/// target and the player's speed points, each only when enabled
+pub fn score_extras<'a>(progress: &Progress, words: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let settings = get_settings();
+    let (count, _, speed) = progress.tally();
+    let (player_points, bot_points) = progress.points_split(words);
+    let mut extras = vec![format!(
+        "Points: you {}, bot {} (a point per letter, plus a bonus for rarer words)",
+        player_points, bot_points
+    )];
+    if let Some(target) = settings.word_target {
+        extras.push(format!(
+            "Target: {}/{} words ({} to go)",
+            count,
+            target,
+            target.saturating_sub(count)
+        ));
+    }
+    if let Some(decay) = settings.score_decay {
+        extras.push(format!(
+            "Speed points: {} (full {} per word played within {}s)",
+            speed,
+            FULL_POINTS,
+            decay.grace.as_secs()
+        ));
+    }
+    extras
+}
+
+/// The player reached the word target: congratulate them with a summary of the game and end
+/// it as a win
+pub async fn reach_word_target(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    outcome: GameOutcome,
+) -> ResponseResult<()> {
+    let (count, _, _) = outcome.progress.tally();
+    let longest = outcome
+        .words
+        .iter()
+        .max_by_key(|word| word.chars().count())
+        .cloned()
+        .unwrap_or_default();
+    let secs = outcome.duration.as_secs();
+    bot.send_message(
+        chat_id,
+        format!(
+            "🏁 That's {} words — you hit the target and win!\n\n\
+            The chain ran to {} words in {}m {:02}s. Longest word: {}",
+            count,
+            outcome.words.len(),
+            secs / 60,
+            secs % 60,
+            longest
+        ),
+    )
+    .await?;
+
+    let outcome = GameOutcome {
+        winner: Some(Winner::Player),
+        ..outcome
+    };
+    finish_game(bot, chat_id, dialogue, outcome).await
+}
+
+impl GameOutcome {
+    /// Outcome of a game that went as `progress` says, timed from its start.
+    ///
+    /// Scores default to the usual alternating split where the bot opens: the player played
+    /// every second word.
+    pub fn new(
+        progress: &Progress,
+        game: &'static str,
+        winner: Option<Winner>,
+        chain: &[WordInfo],
+    ) -> Self {
+        let player_score = chain.len() / 2;
+
+        GameOutcome {
+            game,
+            winner,
+            player_score,
+            bot_score: chain.len() - player_score,
+            words: chain.iter().map(|w| w.word.clone()).collect(),
+            duration: progress.elapsed(),
+            progress: progress.clone(),
+        }
+    }
+
+    /// Menu id that starts this kind of game again, hotseat included
+    fn replay_id(&self) -> &'static str {
+        match self.winner {
+            Some(Winner::Seat(_)) | Some(Winner::Tie) => "word_chain_hotseat",
+            _ => self.game,
+        }
+    }
+
+    /// Override the default scores
+    pub fn with_scores(mut self, player_score: usize, bot_score: usize) -> Self {
+        self.player_score = player_score;
+        self.bot_score = bot_score;
+        self
+    }
+}
+
+/// Credit each player with their part in a finished game against the bot: its result, and the
+/// words they had accepted with the points those scored
+fn record_players(chat_id: ChatId, won: bool, played: &[PlayedWord]) {
+    let mut players: HashMap<UserId, (&str, usize, u32)> = HashMap::new();
+    for word in played {
+        if let Some((user_id, name)) = &word.player {
+            let (_, words, points) = players.entry(*user_id).or_insert((name, 0, 0));
+            *words += 1;
+            *points += word.points;
+        }
+    }
+    for (user_id, (name, words, points)) in players {
+        record_player_game(chat_id, (user_id, name), won, words, points);
+    }
+}
+
+/// Drop the game in progress without the end-of-game flow, e.g. to start another one from the
+/// menu. Like /stop, it counts as an unwon game unless the bot had already conceded. The chat
+/// goes back to the menu state, so a game that asks for options before it starts doesn't find
+/// the abandoned one still in progress.
+pub async fn abandon_game(chat_id: ChatId, dialogue: &MyDialogue, state: &State) {
+    cancel_turn_timer(chat_id);
+    let _ = dialogue.update(Start).await;
+    let progress = state.progress().cloned().unwrap_or_default();
+    info!(chat_id = chat_id.0; "Game abandoned: {}", state);
+
+    // Relay matches and hotseat games aren't played against the bot
+    let against_bot = match state {
+        State::Relay { .. } | State::VocabCheck { .. } => false,
+        State::WordChain { hotseat, .. } => hotseat.is_none(),
+        _ => true,
+    };
+    if against_bot {
+        let chain = state.chain();
+        record_game(chat_id, progress.conceded, chain.len(), chain.len() / 2);
+        record_players(chat_id, progress.conceded, &progress.words);
+    }
+}
+
+/// End the game in a chat: notify everything that tracks outcomes and return to the menu state
+pub async fn finish_game(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    mut outcome: GameOutcome,
+) -> ResponseResult<()> {
+    let (count, _, speed) = outcome.progress.tally();
+    let (player_points, bot_points) = outcome
+        .progress
+        .points_split(outcome.words.iter().map(String::as_str));
+    cancel_turn_timer(chat_id);
+    if outcome.progress.conceded && outcome.winner.is_none() {
+        outcome.winner = Some(Winner::Player);
+    }
+    info!(
+        chat_id = chat_id.0, game = outcome.game;
+        "Game finished: winner {:?}, score {}-{}, {} words in {:?}",
+        outcome.winner,
+        outcome.player_score,
+        outcome.bot_score,
+        outcome.words.len(),
+        outcome.duration
+    );
+
+    // Relay matches and hotseat games aren't played against the bot, so they say nothing about skill
+    if outcome.game != relay::GAME && matches!(outcome.winner, None | Some(Winner::Player)) {
+        record_game(
+            chat_id,
+            outcome.winner.is_some(),
+            outcome.words.len(),
+            outcome.player_score,
+        );
+        record_players(chat_id, outcome.winner.is_some(), &outcome.progress.words);
+    }
+
+    if outcome.winner == Some(Winner::Player) {
+        on_win(bot, chat_id).await?;
+    }
+
+    // Only games against the bot have a bot score to compare with
+    let against_bot = outcome.game != relay::GAME
+        && !matches!(outcome.winner, Some(Winner::Seat(_)) | Some(Winner::Tie));
+    if against_bot && !outcome.words.is_empty() {
+        bot.send_message(
+            chat_id,
+            format!("🏅 Points — you: {}, bot: {}", player_points, bot_points),
+        )
+        .await?;
+    }
+
+    if get_settings().score_decay.is_some() && count > 0 {
+        bot.send_message(
+            chat_id,
+            format!(
+                "⚡ Speed points: {} from {} words (out of {})",
+                speed,
+                count,
+                count as u32 * FULL_POINTS
+            ),
+        )
+        .await?;
+    }
+
+    let _ = dialogue.update(Start).await;
+
+    // Starting over goes through the same callback as picking the game from the menu, so a
+    // game started since is offered for abandoning rather than overwritten
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("🔁 Play again", outcome.replay_id()),
+        InlineKeyboardButton::callback("🎮 Choose game", "choose_game"),
+    ]]);
+    bot.send_message(chat_id, say(chat_id, Phrase::AnotherRound))
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewinding_uncounts_only_the_words_of_the_rewound_turns() {
+        let chat_id = ChatId(-2226);
+        let mut progress = Progress::default();
+        // A word and the bot's reply, then a skip, then a word the bot couldn't answer
+        begin_turn(&mut progress, 1);
+        count_player_word(chat_id, &mut progress, "tiger");
+        begin_turn(&mut progress, 3);
+        begin_turn(&mut progress, 4);
+        count_player_word(chat_id, &mut progress, "trout");
+        assert_eq!(turns_taken(&progress), 3);
+
+        assert_eq!(take_back_turns(&mut progress, 2), 3);
+        assert_eq!(progress.words.len(), 1);
+        assert_eq!(take_back_turns(&mut progress, 1), 1);
+        assert!(progress.words.is_empty());
+        assert_eq!(turns_taken(&progress), 0);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::dictionary::{get_word_details, WordInfo};
+use crate::games::{
+    alphabet_sprint, chain_letter, forbidden_letters, normalize_word, scrambled, synonym_string,
+    word_chain, word_ladder, Direction,
+};
+use crate::preferences::{get_bot_style, Difficulty};
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::Bot;
+
+const USAGE: &str = "Usage: /preview <game> <word>\n\n\
+    Games: word_chain, alphabet_sprint, last_letter [level 1-3], synonym_string, \
+    word_ladder, forbidden_letters <letters>\n\n\
+    e.g. /preview last_letter planet 2 or /preview forbidden_letters planet xyz";
+
+/// Handle the preview command - show how the bot would answer a word in a game, without
+/// starting one
+pub async fn handle_preview_command(bot: &Bot, chat_id: ChatId, args: &str) -> ResponseResult<()> {
+    let mut parts = args.split_whitespace();
+    let (Some(game), Some(word)) = (parts.next(), parts.next()) else {
+        bot.send_message(chat_id, USAGE).await?;
+        return Ok(());
+    };
+    let game = game.to_lowercase();
+    let extra = parts.next();
+    let word = normalize_word(word);
+
+    let details = match get_word_details(&word).await {
+        Ok(details) => details,
+        Err(e) => {
+            debug!("Can't preview unknown word '{}': {:?}", word, e);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "I don't know '{}', so I can't preview an answer to it.",
+                    word
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let Some(last_char) = chain_letter(&details.word) else {
+        bot.send_message(chat_id, USAGE).await?;
+        return Ok(());
+    };
+    let used = details.stems.clone();
+    // The closest word is the likeliest answer at any difficulty, so show that one
+    let difficulty = Difficulty::Hard;
+
+    let (response, constraint) = match game.as_str() {
+        word_chain::GAME => {
+            let style = get_bot_style(chat_id);
+            (
+                word_chain::get_bot_response(
+                    &details.word,
+                    &used,
+                    style,
+                    Direction::Forward,
+                    difficulty,
+                )
+                .await
+                .map_err(|e| e.to_string()),
+                format!(
+                    "it starts with '{}', the last letter of '{}'. Bot style {}: {}",
+                    last_char,
+                    details.word,
+                    style.name(),
+                    style.description().to_lowercase()
+                ),
+            )
+        }
+        alphabet_sprint::GAME => {
+            let Some(alphabet) = details.word.chars().next() else {
+                bot.send_message(chat_id, USAGE).await?;
+                return Ok(());
+            };
+            (
+                alphabet_sprint::get_bot_response(&details.word, &used, alphabet, difficulty)
+                    .await
+                    .map_err(|e| e.to_string()),
+                format!("it starts with '{}', like '{}'", alphabet, details.word),
+            )
+        }
+        scrambled::GAME => {
+            let level = extra
+                .and_then(|l| l.parse::<u8>().ok())
+                .unwrap_or(3)
+                .clamp(1, 3);
+            (
+                scrambled::get_bot_response(chat_id, &details.word, &used, level, difficulty)
+                    .await
+                    .map_err(|e| e.to_string()),
+                format!(
+                    "it starts with '{}' and contains at least {} letter(s) from '{}' (level {})",
+                    last_char, level, details.word, level
+                ),
+            )
+        }
+        synonym_string::GAME => {
+            let response = synonym_string::get_bot_response(
+                chat_id,
+                &details.word,
+                &used,
+                synonym_string::DEFAULT_MIN_SIMILARITY,
+                difficulty,
+            )
+            .await
+            .map_err(|e| e.to_string());
+            let mut constraint = format!(
+                "it starts with '{}' and is close in meaning to '{}'",
+                last_char, details.word
+            );
+            if let Ok(answer) = &response {
+                let similarity =
+                    synonym_string::chat_similarity(chat_id, &details.word, &answer.word);
+                constraint.push_str(&format!(" (similarity {:.2})", similarity));
+            }
+            (response, constraint)
+        }
+        word_ladder::GAME => {
+            let target_length = details.word.chars().count() + 1;
+            (
+                word_ladder::get_bot_response(&details.word, &used, target_length, difficulty)
+                    .await
+                    .map_err(|e| e.to_string()),
+                format!(
+                    "it starts with '{}' and is {} letters long, one more than '{}'",
+                    last_char, target_length, details.word
+                ),
+            )
+        }
+        forbidden_letters::GAME => {
+            let Some(letters) = extra.map(|l| {
+                l.to_lowercase()
+                    .chars()
+                    .filter(char::is_ascii_lowercase)
+                    .collect::<Vec<char>>()
+            }) else {
+                bot.send_message(chat_id, USAGE).await?;
+                return Ok(());
+            };
+            (
+                forbidden_letters::get_bot_response(&details.word, &used, &letters, difficulty)
+                    .await
+                    .map_err(|e| e.to_string()),
+                format!(
+                    "it starts with '{}' and avoids the letters {}",
+                    last_char,
+                    letters
+                        .iter()
+                        .map(|c| format!("'{}'", c))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            )
+        }
+        _ => {
+            bot.send_message(chat_id, USAGE).await?;
+            return Ok(());
+        }
+    };
+
+    send_preview(bot, chat_id, &details.word, response, &constraint).await
+}
+
+/// Show the previewed answer, or why there isn't one
+async fn send_preview(
+    bot: &Bot,
+    chat_id: ChatId,
+    word: &str,
+    response: Result<WordInfo, String>,
+    constraint: &str,
+) -> ResponseResult<()> {
+    match response {
+        Ok(answer) => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "To '{}' I'd likely answer: {}\n\nWhy it fits: {}.",
+                    word, answer.word, constraint
+                ),
+            )
+            .await?;
+            answer.send_message(bot, chat_id, 0).await?;
+        }
+        Err(e) => {
+            debug!("No preview answer to '{}': {}", word, e);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "I couldn't find an answer to '{}' — you'd win that round!\n\n\
+                    My word would have to fit: {}.",
+                    word, constraint
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
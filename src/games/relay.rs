@@ -0,0 +1,484 @@
+use crate::achievements::{on_player_word, show_badges};
+use crate::command::Command;
+use crate::contains_any;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_random_word, get_submission_details, WordInfo};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{finish_game, game_started, GameOutcome, Progress};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    accept_submission, begin_processing, chain_letter, define_chain_word, is_just_played,
+    just_played_message, looks_like_command, normalize_word, reject_word, reply_unknown_command,
+    show_dead_ends, show_help, still_processing, REWIND_UNAVAILABLE,
+};
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::settings::get_settings;
+use crate::state::State::{Relay, Start};
+use crate::state::{DialogueStorage, MyDialogue, State};
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{Me, Message};
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "relay";
+
+/// Live state of a relay match, as seen from one of the two chats
+struct Match {
+    chain: Vec<WordInfo>,
+    curr_char: char,
+    partner: ChatId,
+    my_turn: bool,
+    /// Words played by this chat and by the partner chat
+    scores: [usize; 2],
+    progress: Progress,
+}
+
+impl Match {
+    fn into_state(self) -> State {
+        Relay {
+            chain: self.chain,
+            curr_char: self.curr_char,
+            partner: self.partner,
+            my_turn: self.my_turn,
+            scores: self.scores,
+            progress: self.progress,
+        }
+    }
+
+    /// The same match from the partner chat's side
+    fn mirrored(&self, chat_id: ChatId) -> Match {
+        Match {
+            chain: self.chain.clone(),
+            curr_char: self.curr_char,
+            partner: chat_id,
+            my_turn: !self.my_turn,
+            scores: [self.scores[1], self.scores[0]],
+            progress: self.progress.clone(),
+        }
+    }
+}
+
+/// Save a match for this chat and its mirror image for the partner chat
+async fn save_match(
+    dialogue: &MyDialogue,
+    storage: &Arc<DialogueStorage>,
+    chat_id: ChatId,
+    game: Match,
+) {
+    let partner = MyDialogue::new(storage.clone(), game.partner);
+    let _ = partner.update(game.mirrored(chat_id).into_state()).await;
+    let _ = dialogue.update(game.into_state()).await;
+}
+
+/// Start a relay Word Chain between this chat and its configured partner chat
+pub async fn start_relay(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+    storage: Arc<DialogueStorage>,
+) -> ResponseResult<()> {
+    let Some(partner) = get_settings().relay_partner(chat_id) else {
+        bot.send_message(chat_id, "Relay matches aren't set up for this chat.")
+            .await?;
+        return Ok(());
+    };
+
+    let partner_dialogue = MyDialogue::new(storage.clone(), partner);
+    if !matches!(partner_dialogue.get().await, Ok(None) | Ok(Some(Start))) {
+        bot.send_message(
+            chat_id,
+            "Your relay partner is busy with another game. Try again later.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Starting relay match between chats {} and {}",
+        chat_id, partner
+    );
+
+    let word = match get_random_word(|_| true, None).await {
+        Ok(word) => word,
+        Err(e) => {
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
+            bot.send_message(
+                chat_id,
+                "Sorry, I'm having trouble starting the game. Please try again later.",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let Some(curr_char) = chain_letter(&word.word) else {
+        error!(chat_id = chat_id.0, game = GAME; "Selected word '{}' has no characters", word.word);
+        return Ok(());
+    };
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "Relay match started! Your opponent plays from another chat.\n\nFirst word: {}",
+            word.word
+        ),
+    )
+    .await?;
+    word.send_message(&bot, chat_id, 0).await?;
+    bot.send_message(
+        chat_id,
+        format!("You go first: give a word starting with '{}'", curr_char),
+    )
+    .await?;
+
+    bot.send_message(
+        partner,
+        format!(
+            "Another chat challenged you to a relay Word Chain!\n\nFirst word: {}\n\
+            They go first; I'll pass their word on when it's your turn.",
+            word.word
+        ),
+    )
+    .await?;
+
+    game_started(&bot, chat_id).await?;
+    game_started(&bot, partner).await?;
+    let game = Match {
+        chain: vec![word],
+        curr_char,
+        partner,
+        my_turn: true,
+        scores: [0, 0],
+        progress: Progress::default(),
+    };
+    save_match(&dialogue, &storage, chat_id, game).await;
+
+    Ok(())
+}
+
+/// Handle player input during a relay match
+pub async fn relay(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (chain, curr_char, partner, my_turn, scores, progress): (
+        Vec<WordInfo>,
+        char,
+        ChatId,
+        bool,
+        [usize; 2],
+        Progress,
+    ),
+    msg: Message,
+    me: Me,
+    storage: Arc<DialogueStorage>,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let game = Match {
+        chain,
+        curr_char,
+        partner,
+        my_turn,
+        scores,
+        progress,
+    };
+
+    let Some(_processing) = begin_processing(chat_id) else {
+        return still_processing(&bot, chat_id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    chat_id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                bot.send_message(
+                    chat_id,
+                    "No hints in relay matches — it's you against them!",
+                )
+                .await?;
+            }
+            Ok(Command::Skip) => {
+                if !game.my_turn {
+                    bot.send_message(chat_id, "It's not your turn.").await?;
+                    return Ok(());
+                }
+                debug!(chat_id = chat_id.0, game = GAME; "Chat passed its relay turn");
+                bot.send_message(chat_id, "You passed your turn.").await?;
+                bot.send_message(
+                    game.partner,
+                    format!(
+                        "Your opponent passed. Give a word starting with '{}'",
+                        game.curr_char
+                    ),
+                )
+                .await?;
+                let game = Match {
+                    my_turn: false,
+                    ..game
+                };
+                save_match(&dialogue, &storage, chat_id, game).await;
+            }
+            Ok(Command::Score) => {
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "Current score:\nYou: {} words\nOpponent: {} words\nChain length: {}",
+                        game.scores[0],
+                        game.scores[1],
+                        game.chain.len()
+                    ),
+                )
+                .await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, chat_id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, chat_id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, chat_id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, chat_id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, chat_id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(chat_id, MAX_LEN_OUTSIDE_LADDER).await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(chat_id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, chat_id, &game.chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, chat_id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, chat_id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, chat_id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, chat_id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, chat_id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, chat_id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, chat_id, game.curr_char).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = chat_id.0, game = GAME; "Chat stopped its relay match");
+                stop_match(&bot, chat_id, dialogue, &storage, game).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, chat_id, text).await?;
+            }
+            Err(_) => {
+                if check_turn(&bot, &msg, &game.progress).await? {
+                    process_player_word(text, &bot, dialogue, &storage, game, chat_id).await?;
+                }
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Validate a word and, if it's accepted, pass the turn to the partner chat
+async fn process_player_word(
+    text: &str,
+    bot: &Bot,
+    dialogue: MyDialogue,
+    storage: &Arc<DialogueStorage>,
+    mut game: Match,
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    if !game.my_turn {
+        bot.send_message(chat_id, "Waiting for your opponent's move...")
+            .await?;
+        return Ok(());
+    }
+
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+    if words.len() != 1 {
+        bot.send_message(chat_id, "Please enter a single word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(words[0]);
+
+    if is_just_played(&game.chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
+
+    if !word.starts_with(game.curr_char) {
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(game.curr_char)))
+            .await?;
+        return Ok(());
+    }
+
+    let used_stems = game
+        .chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            if contains_any(&used_stems, &word_details.stems) {
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
+                return Ok(());
+            }
+
+            let Some(next_char) = chain_letter(&word_details.word) else {
+                error!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Player word '{}' has no characters",
+                    word_details.word
+                );
+                return Ok(());
+            };
+
+            debug!(chat_id = chat_id.0, game = GAME; "Relay word '{}'", word_details.word);
+            game.chain.push(word_details.clone());
+            accept_submission(chat_id);
+            on_player_word(bot, chat_id, &word_details.word, game.chain.len()).await?;
+
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Sent '{}' to your opponent. Waiting for their move...",
+                    word_details.word
+                ),
+            )
+            .await?;
+            bot.send_message(
+                game.partner,
+                format!("Your opponent played: {}", word_details.word),
+            )
+            .await?;
+            if !word_details.defs.is_empty() {
+                word_details.send_message(bot, game.partner, 0).await?;
+            }
+            bot.send_message(
+                game.partner,
+                format!("Your turn: give a word starting with '{}'", next_char),
+            )
+            .await?;
+            next_player(bot, chat_id, &mut game.progress).await?;
+
+            game.curr_char = next_char;
+            game.my_turn = false;
+            game.scores[0] += 1;
+            save_match(&dialogue, storage, chat_id, game).await;
+        }
+        Err(e) => {
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(game.curr_char)
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// End the match in both chats
+async fn stop_match(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    storage: &Arc<DialogueStorage>,
+    game: Match,
+) -> ResponseResult<()> {
+    let words = game
+        .chain
+        .iter()
+        .map(|w| w.word.clone())
+        .collect::<Vec<String>>()
+        .join(" → ");
+
+    for (chat, mine, theirs) in [
+        (chat_id, game.scores[0], game.scores[1]),
+        (game.partner, game.scores[1], game.scores[0]),
+    ] {
+        bot.send_message(
+            chat,
+            format!(
+                "Relay match finished! Final score:\nYou: {} words\nOpponent: {} words\n\nWord chain: {}",
+                mine, theirs, words
+            ),
+        )
+        .await?;
+    }
+
+    let partner_dialogue = MyDialogue::new(storage.clone(), game.partner);
+    let outcome = GameOutcome::new(&game.progress, GAME, None, &game.chain)
+        .with_scores(game.scores[1], game.scores[0]);
+    finish_game(bot, game.partner, partner_dialogue, outcome).await?;
+
+    let outcome = GameOutcome::new(&game.progress, GAME, None, &game.chain)
+        .with_scores(game.scores[0], game.scores[1]);
+    finish_game(bot, chat_id, dialogue, outcome).await
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, curr_char: char) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
+        format!(
+            "Relay Match Rules:\n\
+            1. You play Word Chain against another chat; I just pass the words along\n\
+            2. Each word must start with the last letter of the previous one (next: '{}')\n\
+            3. No repeating words\n\
+            4. Use /skip to pass your turn or /stop to end the match for both chats\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the match",
+            curr_char
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
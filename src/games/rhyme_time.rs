@@ -0,0 +1,629 @@
+use crate::achievements::show_badges;
+use crate::command::Command;
+use crate::contains_any;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{active_model, is_valid_word, similarity_in, EmbeddingError};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    bot_concedes, count_player_word, finish_game, game_started, reach_word_target, score_extras,
+    skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, begin_processing, carry_on_message, chain_letter,
+    credit_player_word, define_chain_word, is_just_played, just_played_message, looks_like_command,
+    normalize_word, reject_word, reply_unknown_command, retry_turn, show_dead_ends, show_help,
+    still_processing, REWIND_UNAVAILABLE,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
+use crate::rhymes::{rhymes, rhymes_available, rhymes_of};
+use crate::state::MyDialogue;
+use crate::state::State::RhymeTime;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use rand::seq::IndexedRandom;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::Me;
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Error type specific to Rhyme Time game
+#[derive(Debug)]
+pub enum RhymeError {
+    Dictionary(DictionaryError),
+    Embedding(EmbeddingError),
+    NoRhymes(String),
+}
+
+impl From<DictionaryError> for RhymeError {
+    fn from(error: DictionaryError) -> Self {
+        RhymeError::Dictionary(error)
+    }
+}
+
+impl From<EmbeddingError> for RhymeError {
+    fn from(error: EmbeddingError) -> Self {
+        RhymeError::Embedding(error)
+    }
+}
+
+impl RhymeError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// rhyme carries on
+    fn is_transient(&self) -> bool {
+        match self {
+            RhymeError::Dictionary(e) => e.is_transient(),
+            RhymeError::Embedding(e) => e.is_transient(),
+            RhymeError::NoRhymes(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for RhymeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RhymeError::Dictionary(e) => write!(f, "Dictionary error: {}", e),
+            RhymeError::Embedding(e) => write!(f, "Embedding error: {}", e),
+            RhymeError::NoRhymes(word) => write!(f, "No unplayed rhyme follows '{}'", word),
+        }
+    }
+}
+
+/// Menu id of this game
+pub const GAME: &str = "rhyme_time";
+
+/// Replies the bot looks up before giving up on a turn
+const MAX_LOOKUPS: usize = 5;
+
+/// Playable words that can follow `word`: starting with its last letter, rhyming with it and
+/// not `used` yet
+fn rhymes_after(word: &str, used: &[String]) -> Vec<&'static str> {
+    let Some(letter) = chain_letter(word) else {
+        return Vec::new();
+    };
+    rhymes_of(word)
+        .into_iter()
+        .filter(|w| {
+            w.starts_with(letter)
+                && is_valid_word(w)
+                && is_playable(w)
+                && !used.contains(&w.to_string())
+        })
+        .collect()
+}
+
+/// Prompt for the player's next word
+fn prompt(word: &str) -> String {
+    format!(
+        "Now give a word starting with '{}' that rhymes with '{}'",
+        chain_letter(word).unwrap_or(' '),
+        word
+    )
+}
+
+/// Start a new Rhyme Time game
+pub async fn start_rhyme_time(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting Rhyme Time game");
+
+    if !rhymes_available() {
+        bot.send_message(
+            chat_id,
+            "Sorry, Rhyme Time isn't available right now: I don't have a pronouncing dictionary to check rhymes with.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        chat_id,
+        "Rhyme Time starts now! Each word must start with the last letter of the one before and rhyme with it.",
+    )
+    .await?;
+
+    // Open with a word that has a rhyme to answer it with
+    for _ in 0..3 {
+        match get_random_word(|w| !rhymes_after(w, &[]).is_empty(), None).await {
+            Ok(first) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Rhyme Time started with word: {}",
+                    first.word
+                );
+
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&first.word), &first).await?;
+                bot.send_message(chat_id, prompt(&first.word)).await?;
+
+                game_started(&bot, chat_id).await?;
+                let _ = dialogue
+                    .update(RhymeTime {
+                        chain: vec![first],
+                        progress: Progress::default(),
+                    })
+                    .await;
+
+                return Ok(());
+            }
+            Err(e) => {
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get opening word: {:?}", e);
+                // Try again
+            }
+        }
+    }
+
+    bot.send_message(
+        chat_id,
+        "Sorry, I'm having trouble starting the game. Please try again later.",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle player input during Rhyme Time game
+pub async fn rhyme_time(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (chain, mut progress): (Vec<WordInfo>, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
+    match msg.text() {
+        Some(text) => match BotCommands::parse(text, me.username()) {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "Please stop this game first with /stop to use this command.",
+                )
+                .await?;
+            }
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, &chain).await?;
+            }
+            Ok(Command::Skip) => {
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
+                skip_turn(&bot, msg.chat.id, dialogue, chain, progress).await?;
+            }
+            Ok(Command::Score) => {
+                show_score(&bot, msg.chat.id, &chain, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(msg.chat.id, REWIND_UNAVAILABLE).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Rules) => {
+                show_rules(&bot, msg.chat.id, &chain).await?;
+            }
+            Ok(Command::Stop) => {
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Rhyme Time game");
+
+                // Show final score/summary
+                let player_words = chain.len() / 2;
+                let bot_words = chain.len() - player_words;
+
+                bot.send_message(
+                    msg.chat.id,
+                    format!(
+                        "Game finished! Final score:\nYou: {} words\nBot: {} words\n\nRhyme chain: {}",
+                        player_words,
+                        bot_words,
+                        chain.iter().map(|w| w.word.clone()).collect::<Vec<String>>().join(" → ")
+                    ),
+                ).await?;
+
+                bot.send_message(
+                    msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Rhyme Time")),
+                )
+                .await?;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(text, bot, dialogue, (chain, progress), msg.chat.id)
+                        .await?;
+                }
+            }
+        },
+        None => {
+            // Ignore non-text messages
+        }
+    }
+    Ok(())
+}
+
+/// Process a player's word submission
+async fn process_player_word(
+    text: &str,
+    bot: Bot,
+    dialogue: MyDialogue,
+    (mut chain, mut progress): (Vec<WordInfo>, Progress),
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+
+    // Check for valid input (single word)
+    if words.is_empty() {
+        bot.send_message(chat_id, "Please enter a word.").await?;
+        return Ok(());
+    }
+
+    if words.len() > 1 {
+        bot.send_message(chat_id, "Please enter only one word.")
+            .await?;
+        return Ok(());
+    }
+
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
+
+    let Some(prev_word) = chain.last().map(|w| w.word.clone()) else {
+        error!(chat_id = chat_id.0, game = GAME; "Rhyme Time has no words in its chain");
+        return Ok(());
+    };
+    let Some(curr_char) = chain_letter(&prev_word) else {
+        error!(
+            chat_id = chat_id.0, game = GAME;
+            "Previous word '{}' has no characters",
+            prev_word
+        );
+        return Ok(());
+    };
+
+    // The word must start on the last letter of the previous one and rhyme with it
+    if !word.starts_with(curr_char) {
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(curr_char)))
+            .await?;
+        return Ok(());
+    }
+    if !rhymes(&word, &prev_word) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "'{}' doesn't rhyme with '{}'. Try another word.",
+                word, prev_word
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Get list of already used words/stems
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+
+    // Validate the player's word
+    match get_submission_details(&word, GAME).await {
+        Ok(word_details) => {
+            // Check if word has already been used
+            if contains_any(&used_stems, &word_details.stems) {
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
+                return Ok(());
+            }
+
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
+            let mut updated_stems = used_stems.clone();
+            updated_stems.push(word.clone());
+
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            chain.push(word_details.clone());
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            // Get the bot's response word
+            let response = get_bot_response(
+                chat_id,
+                &word_details.word,
+                &updated_stems,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
+                Ok(reply) => {
+                    chain.push(reply.clone());
+                    announce_bot_word(&bot, chat_id, Phrase::BotWord(&reply.word), &reply).await?;
+                    bot.send_message(chat_id, prompt(&reply.word)).await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
+
+                    let _ = dialogue.update(RhymeTime { chain, progress }).await;
+                }
+                Err(e) => {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    if bot_concedes(chat_id, &mut progress) {
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a word starting with '{}' that rhymes with '{}'",
+                                chain_letter(&word_details.word).unwrap_or(curr_char),
+                                word_details.word
+                            )),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue.update(RhymeTime { chain, progress }).await;
+                        return Ok(());
+                    }
+                    bot.send_message(chat_id, say(chat_id, Phrase::BotStumped("a rhyme")))
+                        .await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
+                }
+            }
+        }
+        Err(e) => {
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(curr_char) && rhymes(w, &prev_word)
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the bot's rhyme for `word`: one of the closest in meaning among the unplayed words that
+/// start with its last letter and rhyme with it
+pub async fn get_bot_response(
+    chat_id: ChatId,
+    word: &str,
+    used_words: &[String],
+    difficulty: Difficulty,
+) -> Result<WordInfo, RhymeError> {
+    let mut used_words = used_words.to_vec();
+    let model = active_model(chat_id)?;
+    let mut lookup_error = None;
+
+    for _ in 0..MAX_LOOKUPS {
+        let candidates = rhymes_after(word, &used_words);
+        if candidates.is_empty() {
+            break;
+        }
+
+        let mut ranked = candidates
+            .into_iter()
+            .map(|w| (w.to_string(), similarity_in(model, word, w).unwrap_or(0.0)))
+            .collect::<Vec<(String, f64)>>();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(difficulty.choices());
+        let reply = difficulty.choose(ranked)?;
+
+        match get_word_details(&reply).await {
+            Ok(details) if contains_any(&used_words, &details.stems) => {
+                used_words.extend(details.stems);
+            }
+            Ok(details) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Bot found rhyme '{}' after '{}'",
+                    details.word, word
+                );
+                return Ok(details);
+            }
+            Err(e) => {
+                // Running out of words because lookups failed isn't a real dead end
+                if e.is_transient() {
+                    lookup_error = Some(e);
+                }
+                used_words.push(reply);
+            }
+        }
+    }
+
+    if let Some(e) = lookup_error {
+        return Err(RhymeError::Dictionary(e));
+    }
+    Err(RhymeError::NoRhymes(word.to_string()))
+}
+
+/// Provide a hint for the current turn
+async fn provide_hint(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+    let prev_word = chain.last().map_or("", |w| w.word.as_str());
+
+    let hint = rhymes_after(prev_word, &used_stems)
+        .choose(&mut rand::rng())
+        .copied();
+    let reply = match hint {
+        Some(hint) => format!(
+            "{} It rhymes with '{}'.",
+            hint_message(chat_id, hint),
+            prev_word
+        ),
+        None => format!(
+            "I can't think of a rhyme for '{}' either. Try /skip.",
+            prev_word
+        ),
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Skip the current turn
+pub async fn skip_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    mut chain: Vec<WordInfo>,
+    mut progress: Progress,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
+
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
+
+    let used_stems = chain
+        .iter()
+        .flat_map(|x| x.stems.clone())
+        .collect::<Vec<String>>();
+    let prev_word = chain.last().map(|w| w.word.clone()).unwrap_or_default();
+
+    match get_bot_response(chat_id, &prev_word, &used_stems, get_difficulty(chat_id)).await {
+        Ok(word) => {
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
+            bot.send_message(chat_id, prompt(&word.word)).await?;
+            chain.push(word);
+
+            next_player(bot, chat_id, &mut progress).await?;
+            let _ = dialogue.update(RhymeTime { chain, progress }).await;
+        }
+        Err(e) => {
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get rhyme for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the current score (word count)
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let player_words = chain.len() / 2;
+    let bot_words = chain.len() - player_words;
+
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
+    let prev_word = chain.last().map_or("", |w| w.word.as_str());
+    bot.send_message(
+        chat_id,
+        format!(
+            "Rhyme Time Rules:\n\
+            1. Each word must start with the last letter of the previous word (next: '{}')\n\
+            2. It must also rhyme with it, by the CMU Pronouncing Dictionary: after 'light', 'tight' fits\n\
+            3. No repeating words\n\
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            chain_letter(prev_word).unwrap_or(' ')
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,10 +1,35 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
 use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, EmbeddingError};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{active_model, get_top_similar_words_in, Chaining, EmbeddingError};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    begin_turn, bot_concedes, count_player_word, finish_game, game_started, reach_word_target,
+    score_extras, skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, announce_rewind, begin_processing, carry_on_message,
+    chain_letter, credit_player_word, dead_end_message, define_chain_word,
+    has_healthy_continuation, is_just_played, just_played_message, looks_like_command,
+    normalize_word, open_letters, reject_word, reply_unknown_command, reseed_opener, retry_turn,
+    rewind_chain, show_dead_ends, show_help, still_processing, Direction,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
+use crate::settings::get_settings;
 use crate::state::MyDialogue;
-use crate::state::State::{LastLetterScramble, Start};
-use log::{error, info, warn};
+use crate::state::State::LastLetterScramble;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
 use std::collections::HashSet;
 use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
 use teloxide::types::Me;
@@ -13,7 +38,7 @@ use teloxide::Bot;
 
 /// Error type specific to Last Letter Scramble game
 #[derive(Debug)]
-enum ScrambledError {
+pub enum ScrambledError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
     InvalidInput(String),
@@ -32,6 +57,18 @@ impl From<EmbeddingError> for ScrambledError {
     }
 }
 
+impl ScrambledError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            ScrambledError::Dictionary(e) => e.is_transient(),
+            ScrambledError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for ScrambledError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -43,56 +80,67 @@ impl std::fmt::Display for ScrambledError {
     }
 }
 
+/// Menu id of this game
+pub const GAME: &str = "last_letter";
+
 /// Start a new Last Letter Scramble game
 pub async fn start_last_letter_scramble(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
 ) -> ResponseResult<()> {
-    info!("Starting Last Letter Scramble game for chat {}", chat_id);
+    info!(chat_id = chat_id.0, game = GAME; "Starting Last Letter Scramble game");
 
     bot.send_message(chat_id, "Last Letter Scramble! Let's twist those endings.")
         .await?;
 
+    // Open with a word the chain can carry on from, so the first reply isn't a dead end
+    let open = open_letters(|_| true);
+
     // Try to get a random word to start the game
     for _ in 0..3 {
         // Try up to 3 times
-        match get_random_word(|_| true, None).await {
+        match get_random_word(|w| Direction::Forward.opens(w, &open), None).await {
             Ok(word) => {
-                let curr_char = match word.word.chars().last() {
+                let curr_char = match chain_letter(&word.word) {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                info!("Last Letter Scramble started with word: {}", word.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Last Letter Scramble started with word: {}",
+                    word.word
+                );
 
-                bot.send_message(chat_id, format!("First word: {}", word.word))
-                    .await?;
-                word.send_message(&bot, chat_id, 0).await?;
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
-                bot.send_message(
-                    chat_id,
-                    format!("Now give a word starting with '{}'", curr_char),
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::YourTurn(curr_char)))
+                    .await?;
 
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(LastLetterScramble {
                         chain: vec![word],
                         level: 3,
                         curr_char,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
                 // Try again
             }
         }
@@ -112,35 +160,108 @@ pub async fn start_last_letter_scramble(
 pub async fn last_letter_scramble(
     bot: Bot,
     dialogue: MyDialogue,
-    (level, chain, curr_char): (u8, Vec<WordInfo>, char),
+    (level, chain, curr_char, mut progress): (u8, Vec<WordInfo>, char, Progress),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
+            Ok(Command::Hint(_)) => {
                 provide_hint(&bot, msg.chat.id, curr_char, level, &chain).await?;
             }
             Ok(Command::Skip) => {
-                skip_turn(&bot, msg.chat.id, dialogue, chain, level, curr_char).await?;
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
+                skip_turn(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    chain,
+                    level,
+                    curr_char,
+                    progress,
+                )
+                .await?;
             }
             Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &chain).await?;
+                show_score(&bot, msg.chat.id, &chain, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(args)) => {
+                rewind(&bot, msg.chat.id, dialogue, &args, (chain, level, progress)).await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id, level).await?;
+                show_rules(&bot, msg.chat.id, level, curr_char).await?;
             }
             Ok(Command::Stop) => {
                 info!(
-                    "Player stopped Last Letter Scramble game in chat {}",
-                    msg.chat.id
+                    chat_id = msg.chat.id.0, game = GAME;
+                    "Player stopped Last Letter Scramble game"
                 );
 
                 // Show final score/summary
@@ -166,14 +287,20 @@ pub async fn last_letter_scramble(
 
                 bot.send_message(
                     msg.chat.id,
-                    "Last Letter Scramble game stopped. Thanks for playing!",
+                    say(msg.chat.id, Phrase::GameStopped("Last Letter Scramble")),
                 )
                 .await?;
-                let _ = dialogue.update(Start).await;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
             }
             Err(_) => {
-                process_player_word(text, bot, dialogue, chain, level, curr_char, msg.chat.id)
-                    .await?;
+                if check_turn(&bot, &msg, &progress).await? {
+                    let game = (chain, level, curr_char, progress);
+                    process_player_word(text, bot, dialogue, game, msg.chat.id).await?;
+                }
             }
         },
         None => {
@@ -188,9 +315,7 @@ async fn process_player_word(
     text: &str,
     bot: Bot,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    level: u8,
-    curr_char: char,
+    (mut chain, level, curr_char, mut progress): (Vec<WordInfo>, u8, char, Progress),
     chat_id: ChatId,
 ) -> ResponseResult<()> {
     let words = text.split_whitespace().collect::<Vec<&str>>();
@@ -207,14 +332,22 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
     let prev_word = match chain.last() {
         Some(w) => w,
         None => {
-            error!("Chain is empty when processing player word");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when processing player word");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
-            let _ = dialogue.update(Start).await;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(&bot, chat_id, dialogue, outcome).await?;
             return Ok(());
         }
     };
@@ -222,11 +355,8 @@ async fn process_player_word(
     // Check if word starts with the last letter of previous word
     // and contains at least N characters from the previous word
     if !word.starts_with(curr_char) {
-        bot.send_message(
-            chat_id,
-            format!("Your word must start with '{}'", curr_char),
-        )
-        .await?;
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(curr_char)))
+            .await?;
         return Ok(());
     }
 
@@ -249,44 +379,76 @@ async fn process_player_word(
         .collect::<Vec<String>>();
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
             if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!("Player used word: {} in chat {}", word, chat_id);
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
             let mut updated_stems = used_stems.clone();
             updated_stems.push(word.clone());
 
-            word_details.send_message(&bot, chat_id, 0).await?;
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            begin_turn(&mut progress, chain.len());
             chain.push(word_details.clone());
 
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
             // Get the bot's response word
-            match get_bot_response(&word, &updated_stems, level).await {
+            let response = get_bot_response(
+                chat_id,
+                &word_details.word,
+                &updated_stems,
+                level,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
-                    let next_char = match next_word_details.word.chars().last() {
+                    let next_char = match chain_letter(&next_word_details.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot's word '{}' has no characters", next_word_details.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot's word '{}' has no characters",
+                                next_word_details.word
+                            );
                             bot.send_message(chat_id, "Error in game, please try again.")
                                 .await?;
-                            let _ = dialogue.update(Start).await;
+                            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                            finish_game(&bot, chat_id, dialogue, outcome).await?;
                             return Ok(());
                         }
                     };
 
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
+                    announce_bot_word(
+                        &bot,
+                        chat_id,
+                        Phrase::BotWord(&next_word_details.word),
+                        &next_word_details,
+                    )
+                    .await?;
 
                     // Prompt for the next word
                     bot.send_message(
@@ -298,6 +460,7 @@ async fn process_player_word(
                             next_word_details.word
                         ),
                     ).await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
 
                     // Update game state
                     let _ = dialogue
@@ -305,29 +468,72 @@ async fn process_player_word(
                             chain,
                             level,
                             curr_char: next_char,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // Conceding to the very first reply would end the game before any real play
+                    if let Some(opener) = reseed_opener(
+                        &bot,
                         chat_id,
-                        "I can't think of a word that meets the criteria! You win this round!",
+                        &chain,
+                        &mut progress,
+                        has_healthy_continuation,
                     )
-                    .await?;
-                    let _ = dialogue.update(Start).await;
+                    .await?
+                    {
+                        let curr_char = chain_letter(&opener.word).unwrap_or(curr_char);
+                        bot.send_message(chat_id, say(chat_id, Phrase::YourTurn(curr_char)))
+                            .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(LastLetterScramble {
+                                chain: vec![opener],
+                                level,
+                                curr_char,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = chain_letter(&word_details.word).unwrap_or(curr_char);
+                        bot.send_message(chat_id, carry_on_message(&format!("give a word starting with '{}' that contains at least {} letter(s) from '{}'", next_char, level, word_details.word)))
+                            .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(LastLetterScramble {
+                                chain,
+                                level,
+                                curr_char: next_char,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    let reply = match &e {
+                        ScrambledError::Embedding(EmbeddingError::NoWordsForLetter(c)) => {
+                            dead_end_message(*c)
+                        }
+                        _ => say(
+                            chat_id,
+                            Phrase::BotStumped("a word that meets the criteria"),
+                        ),
+                    };
+                    bot.send_message(chat_id, reply).await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(curr_char)
+                    && contains_at_least_n_chars(w, &prev_word.word, level as usize)
+            })
             .await?;
         }
     }
@@ -336,13 +542,16 @@ async fn process_player_word(
 }
 
 /// Get the bot's response word with specific letter constraints
-async fn get_bot_response(
+pub async fn get_bot_response(
+    chat_id: ChatId,
     player_word: &str,
     used_words: &[String],
     level: u8,
+    difficulty: Difficulty,
 ) -> Result<WordInfo, ScrambledError> {
     let mut used_words = used_words.to_vec();
-    let last_char = match player_word.chars().last() {
+    let model = active_model(chat_id).map_err(ScrambledError::Embedding)?;
+    let last_char = match chain_letter(player_word) {
         Some(c) => c,
         None => {
             return Err(ScrambledError::InvalidInput(
@@ -354,15 +563,49 @@ async fn get_bot_response(
     // Get a similar word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
-        // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, last_char, |x| {
-            !used_words.contains(&x.to_string())
+        // Try to find a similar word, preferring one the chain can go on from at harder levels
+        let candidate = |x: &str| {
+            is_playable(x)
+                && !used_words.contains(&x.to_string())
                 && contains_at_least_n_chars(player_word, x, level as usize)
-        });
+        };
+        let next_word_result = if plays_to_continue(level) {
+            get_top_similar_words_in(
+                model,
+                player_word,
+                last_char,
+                Chaining::Strict,
+                difficulty.choices(),
+                |x| candidate(x) && has_healthy_continuation(x),
+            )
+            .and_then(|words| difficulty.choose(words))
+            .or_else(|_| {
+                get_top_similar_words_in(
+                    model,
+                    player_word,
+                    last_char,
+                    Chaining::Strict,
+                    difficulty.choices(),
+                    candidate,
+                )
+                .and_then(|words| difficulty.choose(words))
+            })
+        } else {
+            get_top_similar_words_in(
+                model,
+                player_word,
+                last_char,
+                Chaining::Strict,
+                difficulty.choices(),
+                candidate,
+            )
+            .and_then(|words| difficulty.choose(words))
+        };
 
         match next_word_result {
             Ok(word) => {
@@ -375,10 +618,14 @@ async fn get_bot_response(
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
-                        continue
-                    }, // Try another word
+                        continue;
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -390,6 +637,9 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(ScrambledError::Dictionary(e));
+    }
     Err(ScrambledError::NoValidWords(format!(
         "Could not find a valid word that contains {} letters from '{}'",
         level, player_word
@@ -404,12 +654,12 @@ async fn provide_hint(
     level: u8,
     chain: &[WordInfo],
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
 
     let prev_word = match chain.last() {
         Some(w) => &w.word,
         None => {
-            error!("Chain is empty when providing hint");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when providing hint");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
             return Ok(());
@@ -432,14 +682,8 @@ async fn provide_hint(
     .await
     {
         Ok(hint) => {
-            bot.send_message(
-                chat_id,
-                format!(
-                    "Hint: You could try a word like '{}' or something similar.",
-                    hint.word
-                ),
-            )
-            .await?;
+            bot.send_message(chat_id, hint_message(chat_id, &hint.word))
+                .await?;
         }
         Err(_) => {
             bot.send_message(
@@ -456,17 +700,19 @@ async fn provide_hint(
 }
 
 /// Skip the current turn
-async fn skip_turn(
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
     mut chain: Vec<WordInfo>,
     level: u8,
     curr_char: char,
+    mut progress: Progress,
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
     let used_stems = chain
@@ -477,39 +723,75 @@ async fn skip_turn(
     let prev_word = match chain.last() {
         Some(w) => &w.word,
         None => {
-            error!("Chain is empty when skipping turn");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when skipping turn");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
             return Ok(());
         }
     };
 
-    // Try to get a word for the bot
-    match get_random_word(
-        |w| {
-            contains_at_least_n_chars(w, prev_word, level as usize)
-                && !used_stems.contains(&w.to_string())
-        },
-        Some(curr_char),
-    )
-    .await
+    // Try to get a word for the bot. The dictionary may resolve a pick to a different
+    // headword, so check the resolved word against the rules just like a player's word.
+    const MAX_ATTEMPTS: usize = 5;
+    let mut rejected: Vec<String> = Vec::new();
+    let mut result = Err(DictionaryError::NotFound(
+        "No matching word found".to_string(),
+    ));
+    for _ in 0..MAX_ATTEMPTS {
+        result = get_random_word(
+            |w| {
+                contains_at_least_n_chars(w, prev_word, level as usize)
+                    && !used_stems.contains(&w.to_string())
+                    && !rejected.contains(&w.to_string())
+            },
+            Some(curr_char),
+        )
+        .await;
+
+        match &result {
+            Ok(word) if !fits_level(word, prev_word, curr_char, level, &used_stems) => {
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Skip word '{}' doesn't fit the rules, picking another",
+                    word.word
+                );
+                rejected.push(word.word.clone());
+                rejected.extend(word.stems.clone());
+            }
+            _ => break,
+        }
+    }
+    if result
+        .as_ref()
+        .is_ok_and(|word| rejected.contains(&word.word))
     {
+        result = Err(DictionaryError::NotFound(format!(
+            "No word fits after {} attempts",
+            MAX_ATTEMPTS
+        )));
+    }
+
+    match result {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
 
-            let next_char = match word.word.chars().last() {
+            let next_char = match chain_letter(&word.word) {
                 Some(c) => c,
                 None => {
-                    error!("Bot's word '{}' has no characters", word.word);
+                    error!(
+                        chat_id = chat_id.0, game = GAME;
+                        "Bot's word '{}' has no characters",
+                        word.word
+                    );
                     bot.send_message(chat_id, "Error in game, please try again.")
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                    let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                    finish_game(bot, chat_id, dialogue, outcome).await?;
                     return Ok(());
                 }
             };
 
+            begin_turn(&mut progress, chain.len());
             chain.push(word.clone());
 
             bot.send_message(
@@ -520,22 +802,22 @@ async fn skip_turn(
                 ),
             ).await?;
 
+            next_player(bot, chat_id, &mut progress).await?;
             let _ = dialogue
                 .update(LastLetterScramble {
                     chain,
                     level,
                     curr_char: next_char,
+                    progress,
                 })
                 .await;
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
@@ -543,33 +825,83 @@ async fn skip_turn(
 }
 
 /// Show the current score (word count)
-async fn show_score(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
     let player_words = chain.len() / 2;
     let bot_words = chain.len() - player_words;
 
-    bot.send_message(
-        chat_id,
-        format!(
-            "Current score:\nYou: {} words\nBot: {} words",
-            player_words, bot_words
-        ),
-    )
-    .await?;
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Handle /rewind: take the chain back a few turns so the player can try another path
+async fn rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (mut chain, level, mut progress): (Vec<WordInfo>, u8, Progress),
+) -> ResponseResult<()> {
+    let turns = match rewind_chain(&mut chain, &mut progress, args) {
+        Ok(turns) => turns,
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+    let Some((last, curr_char)) = chain
+        .last()
+        .and_then(|w| Some((w.word.clone(), chain_letter(&w.word)?)))
+    else {
+        error!(chat_id = chat_id.0, game = GAME; "Rewound chain has no last word");
+        return Ok(());
+    };
+
+    let prompt = format!(
+        "Now give a word starting with '{}' that contains at least {} letter(s) from '{}'",
+        curr_char, level, last
+    );
+    announce_rewind(bot, chat_id, turns, &chain, &mut progress, &prompt).await?;
+
+    let _ = dialogue
+        .update(LastLetterScramble {
+            level,
+            chain,
+            curr_char,
+            progress,
+        })
+        .await;
 
     Ok(())
 }
 
 /// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId, level: u8) -> ResponseResult<()> {
+async fn show_rules(bot: &Bot, chat_id: ChatId, level: u8, curr_char: char) -> ResponseResult<()> {
     bot.send_message(
         chat_id,
         format!(
-            "Last Letter Scramble Rules:\n\
-            1. Each word must start with the last letter of the previous word\n\
+            "Last Letter Scramble Rules (level {}):\n\
+            1. Each word must start with the last letter of the previous word (next: '{}')\n\
             2. Each word must contain at least {} letter(s) from the previous word\n\
             3. No repeating words\n\
-            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
-            level
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            level, curr_char, level
         ),
     )
     .await?;
@@ -577,8 +909,27 @@ async fn show_rules(bot: &Bot, chat_id: ChatId, level: u8) -> ResponseResult<()>
     Ok(())
 }
 
+/// Whether the bot avoids replies ending on letters with few words to continue from at a level
+pub fn plays_to_continue(level: u8) -> bool {
+    level >= get_settings().play_to_continue_level
+}
+
+/// Whether `word` follows `prev_word` by the rules of the level: it starts with `curr_char`,
+/// shares at least `level` letters with `prev_word` and none of its stems were played
+fn fits_level(
+    word: &WordInfo,
+    prev_word: &str,
+    curr_char: char,
+    level: u8,
+    used_stems: &[String],
+) -> bool {
+    word.word.starts_with(curr_char)
+        && contains_at_least_n_chars(&word.word, prev_word, level as usize)
+        && !contains_any(used_stems, &word.stems)
+}
+
 /// Check if string contains at least n characters from another string
-fn contains_at_least_n_chars(chars: &str, s: &str, n: usize) -> bool {
+pub fn contains_at_least_n_chars(chars: &str, s: &str, n: usize) -> bool {
     let char_set: HashSet<_> = chars.chars().collect();
     let mut found = HashSet::new();
 
@@ -592,3 +943,62 @@ fn contains_at_least_n_chars(chars: &str, s: &str, n: usize) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, stems: &[&str]) -> WordInfo {
+        WordInfo {
+            word: word.to_string(),
+            stems: stems.iter().map(|s| s.to_string()).collect(),
+            defs: Vec::new(),
+            audio: None,
+        }
+    }
+
+    #[test]
+    fn skip_word_must_fit_the_level() {
+        let used = vec!["planet".to_string(), "planets".to_string()];
+
+        // Shares 't' and 'e' with "planet"
+        assert!(fits_level(
+            &word("tiger", &["tiger"]),
+            "planet",
+            't',
+            2,
+            &used
+        ));
+        // Shares only 't', enough for level 1 but not level 2
+        assert!(fits_level(
+            &word("tomb", &["tomb"]),
+            "planet",
+            't',
+            1,
+            &used
+        ));
+        assert!(!fits_level(
+            &word("tomb", &["tomb"]),
+            "planet",
+            't',
+            2,
+            &used
+        ));
+        // A pick the dictionary resolved to a headword starting with another letter
+        assert!(!fits_level(
+            &word("eaten", &["eaten", "eat"]),
+            "planet",
+            't',
+            2,
+            &used
+        ));
+        // A stem that was already played
+        assert!(!fits_level(
+            &word("tablets", &["tablet", "planets"]),
+            "planet",
+            't',
+            2,
+            &used
+        ));
+    }
+}
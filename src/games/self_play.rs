@@ -0,0 +1,81 @@
+use crate::dictionary::is_playable;
+use crate::embeddings::{get_any_word, get_similar_word, Chaining, EmbeddingError};
+use crate::games::scrambled::contains_at_least_n_chars;
+use crate::games::{chain_letter, has_healthy_continuation};
+use log::info;
+use rand::seq::IteratorRandom;
+
+/// Longest chain a self-play round builds before it's cut off
+const MAX_CHAIN: usize = 200;
+
+/// Play Last Letter Scramble against itself from random starting words at every level, with and
+/// without playing to continue, and report the average chain lengths. Chains are built from
+/// the embeddings alone, so a word's other forms don't count as used.
+pub fn self_play_report(rounds: usize) -> Result<String, EmbeddingError> {
+    let mut report = vec![format!(
+        "Average Last Letter Scramble self-play chain length over {} rounds (capped at {}):",
+        rounds, MAX_CHAIN
+    )];
+
+    for level in 1..=3 {
+        let starts = (0..rounds)
+            .map(|_| {
+                let letter = ('a'..='z').choose(&mut rand::rng()).unwrap_or('a');
+                get_any_word(letter, Chaining::Lenient, is_playable)
+            })
+            .collect::<Result<Vec<String>, EmbeddingError>>()?;
+
+        let average = |play_to_continue: bool| {
+            let total: usize = starts
+                .iter()
+                .map(|start| chain_length(start, level, play_to_continue))
+                .sum();
+            total as f64 / rounds.max(1) as f64
+        };
+        let (plain, continuing) = (average(false), average(true));
+        info!(
+            "Self-play level {}: {:.1} words, {:.1} playing to continue",
+            level, plain, continuing
+        );
+        report.push(format!(
+            "Level {}: {:.1} words, {:.1} playing to continue",
+            level, plain, continuing
+        ));
+    }
+
+    Ok(report.join("\n"))
+}
+
+/// Length of the chain the bot builds from `start` by answering itself until it's stuck
+fn chain_length(start: &str, level: u8, play_to_continue: bool) -> usize {
+    let mut chain = vec![start.to_string()];
+
+    while chain.len() < MAX_CHAIN {
+        let Some(prev) = chain.last().cloned() else {
+            break;
+        };
+        let Some(last_char) = chain_letter(&prev) else {
+            break;
+        };
+        let candidate = |x: &str| {
+            is_playable(x)
+                && !chain.iter().any(|w| w == x)
+                && contains_at_least_n_chars(&prev, x, level as usize)
+        };
+        let next = if play_to_continue {
+            get_similar_word(&prev, last_char, Chaining::Strict, |x| {
+                candidate(x) && has_healthy_continuation(x)
+            })
+            .or_else(|_| get_similar_word(&prev, last_char, Chaining::Strict, candidate))
+        } else {
+            get_similar_word(&prev, last_char, Chaining::Strict, candidate)
+        };
+
+        match next {
+            Ok(word) => chain.push(word),
+            Err(_) => break,
+        }
+    }
+
+    chain.len()
+}
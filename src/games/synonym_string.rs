@@ -1,18 +1,45 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
 use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, similarity, EmbeddingError};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_submission_details, get_word_details, is_playable, DictionaryError,
+    WordInfo,
+};
+use crate::embeddings::{
+    active_model, get_top_similar_words_in, similarity_in, Chaining, EmbeddingError,
+};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    begin_turn, bot_concedes, count_player_word, finish_game, game_started, reach_word_target,
+    score_extras, skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, announce_rewind, begin_processing, carry_on_message,
+    chain_letter, credit_player_word, dead_end_message, define_chain_word,
+    has_healthy_continuation, is_just_played, just_played_message, looks_like_command,
+    normalize_word, open_letters, reject_word, reply_unknown_command, reseed_opener, retry_turn,
+    rewind_chain, show_dead_ends, show_help, still_processing, Direction,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
 use crate::state::MyDialogue;
-use crate::state::State::{Start, SynonymString};
-use log::{error, info, warn};
+use crate::state::State::SynonymString;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info};
+use teloxide::payloads::SendMessageSetters;
 use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
-use teloxide::types::Me;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me};
 use teloxide::utils::command::BotCommands;
 use teloxide::Bot;
 
 /// Error type specific to Synonym String game
 #[derive(Debug)]
-enum SynonymError {
+pub enum SynonymError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
     InvalidInput(String),
@@ -31,6 +58,18 @@ impl From<EmbeddingError> for SynonymError {
     }
 }
 
+impl SynonymError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            SynonymError::Dictionary(e) => e.is_transient(),
+            SynonymError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for SynonymError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -42,37 +81,84 @@ impl std::fmt::Display for SynonymError {
     }
 }
 
-/// Start a new Synonym String game
+/// Minimum similarity between consecutive words at each difficulty the player can pick, easiest
+/// first. How close words score depends on the embeddings file, so no one threshold suits them
+/// all.
+pub const SIMILARITY_LEVELS: [(&str, f64); 3] = [("Easy", 0.6), ("Medium", 0.75), ("Hard", 0.85)];
+
+/// Minimum similarity for games stored before the threshold could be picked, and for previews
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.8;
+
+/// Menu id of this game
+pub const GAME: &str = "synonym_string";
+
+/// Ask how close in meaning words must be before the game starts
+pub async fn ask_similarity_level(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let keyboard = InlineKeyboardMarkup::new(vec![SIMILARITY_LEVELS
+        .iter()
+        .enumerate()
+        .map(|(level, (name, threshold))| {
+            InlineKeyboardButton::callback(
+                format!("{} ({:.0}%)", name, threshold * 100.0),
+                format!("synonym_level_{}", level),
+            )
+        })
+        .collect::<Vec<_>>()]);
+    bot.send_message(
+        chat_id,
+        "Synonym String! How close in meaning must each word be? Easy accepts loosely related words, Hard only near-synonyms.",
+    )
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
+/// Start a new Synonym String game where words must be at least `min_similarity` alike
 pub async fn start_synonym_string(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
+    min_similarity: f64,
 ) -> ResponseResult<()> {
-    info!("Starting Synonym String game for chat {}", chat_id);
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Starting Synonym String game (minimum similarity {:.2})",
+        min_similarity
+    );
 
     bot.send_message(chat_id, "Synonym String starts now! Link those meanings.")
         .await?;
 
+    // Open with a word the chain can carry on from, so the first reply isn't a dead end
+    let open = open_letters(|_| true);
+
     // Try to get a random word to start the game
     for _ in 0..3 {
         // Try up to 3 times
-        match get_random_word(|_| true, None).await {
+        match get_random_word(|w| Direction::Forward.opens(w, &open), None).await {
             Ok(word) => {
-                let curr_char = match word.word.chars().last() {
+                let curr_char = match chain_letter(&word.word) {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                info!("Synonym String started with word: {}", word.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Synonym String started with word: {}",
+                    word.word
+                );
 
-                bot.send_message(chat_id, format!("First word: {}", word.word))
-                    .await?;
-                word.send_message(&bot, chat_id, 0).await?;
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
                 bot.send_message(
                     chat_id,
@@ -83,17 +169,20 @@ pub async fn start_synonym_string(
                 )
                 .await?;
 
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(SynonymString {
                         chain: vec![word],
                         curr_char,
+                        min_similarity,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
                 // Try again
             }
         }
@@ -113,33 +202,110 @@ pub async fn start_synonym_string(
 pub async fn synonym_string(
     bot: Bot,
     dialogue: MyDialogue,
-    (chain, curr_char): (Vec<WordInfo>, char),
+    (chain, curr_char, min_similarity, mut progress): (Vec<WordInfo>, char, f64, Progress),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
-                provide_hint(&bot, msg.chat.id, curr_char, &chain).await?;
+            Ok(Command::Hint(_)) => {
+                provide_hint(&bot, msg.chat.id, curr_char, &chain, min_similarity).await?;
             }
             Ok(Command::Skip) => {
-                skip_turn(&bot, msg.chat.id, dialogue, chain, curr_char).await?;
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
+                skip_turn(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    (chain, curr_char, min_similarity, progress),
+                )
+                .await?;
             }
             Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &chain).await?;
+                show_score(&bot, msg.chat.id, &chain, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(args)) => {
+                rewind(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    &args,
+                    (chain, min_similarity, progress),
+                )
+                .await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id).await?;
+                show_rules(&bot, msg.chat.id, curr_char, min_similarity).await?;
             }
             Ok(Command::Stop) => {
-                info!("Player stopped Synonym String game in chat {}", msg.chat.id);
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Synonym String game");
 
                 // Show final score/summary
                 let player_words = chain.len() / 2;
@@ -157,13 +323,26 @@ pub async fn synonym_string(
 
                 bot.send_message(
                     msg.chat.id,
-                    "Synonym String game stopped. Thanks for playing!",
+                    say(msg.chat.id, Phrase::GameStopped("Synonym String")),
                 )
                 .await?;
-                let _ = dialogue.update(Start).await;
+                let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
             }
             Err(_) => {
-                process_player_word(text, bot, dialogue, chain, curr_char, msg.chat.id).await?;
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(
+                        text,
+                        bot,
+                        dialogue,
+                        (chain, curr_char, min_similarity, progress),
+                        msg.chat.id,
+                    )
+                    .await?;
+                }
             }
         },
         None => {
@@ -178,8 +357,7 @@ async fn process_player_word(
     text: &str,
     bot: Bot,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    curr_char: char,
+    (mut chain, curr_char, min_similarity, mut progress): (Vec<WordInfo>, char, f64, Progress),
     chat_id: ChatId,
 ) -> ResponseResult<()> {
     let words = text.split_whitespace().collect::<Vec<&str>>();
@@ -196,14 +374,22 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
     let prev_word = match chain.last() {
         Some(w) => &w.word,
         None => {
-            error!("Chain is empty when processing player word");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when processing player word");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
-            let _ = dialogue.update(Start).await;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(&bot, chat_id, dialogue, outcome).await?;
             return Ok(());
         }
     };
@@ -211,16 +397,13 @@ async fn process_player_word(
     // Check if word starts with the last letter of previous word
     // and is similar to the previous word
     if !word.starts_with(curr_char) {
-        bot.send_message(
-            chat_id,
-            format!("Your word must start with '{}'", curr_char),
-        )
-        .await?;
+        bot.send_message(chat_id, say(chat_id, Phrase::MustStartWith(curr_char)))
+            .await?;
         return Ok(());
     }
 
-    let sim_score = similarity(&word, prev_word).unwrap_or(0.0);
-    if sim_score < 0.8 {
+    let sim_score = chat_similarity(chat_id, &word, prev_word);
+    if sim_score < min_similarity {
         bot.send_message(
             chat_id,
             format!(
@@ -239,47 +422,80 @@ async fn process_player_word(
         .collect::<Vec<String>>();
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
             if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!(
-                "Player used word: {} in chat {} (similarity: {:.2})",
-                word, chat_id, sim_score
+            debug!(
+                chat_id = chat_id.0, game = GAME;
+                "Player used word: {} (similarity: {:.2})",
+                word, sim_score
             );
             let mut updated_stems = used_stems.clone();
             updated_stems.push(word.clone());
 
-            word_details.send_message(&bot, chat_id, 0).await?;
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            begin_turn(&mut progress, chain.len());
             chain.push(word_details.clone());
 
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
             // Get the bot's response word
-            match get_bot_response(&word, &updated_stems).await {
+            let response = get_bot_response(
+                chat_id,
+                &word_details.word,
+                &updated_stems,
+                min_similarity,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
-                    let next_char = match next_word_details.word.chars().last() {
+                    let next_char = match chain_letter(&next_word_details.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot's word '{}' has no characters", next_word_details.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot's word '{}' has no characters",
+                                next_word_details.word
+                            );
                             bot.send_message(chat_id, "Error in game, please try again.")
                                 .await?;
-                            let _ = dialogue.update(Start).await;
+                            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                            finish_game(&bot, chat_id, dialogue, outcome).await?;
                             return Ok(());
                         }
                     };
 
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
+                    announce_bot_word(
+                        &bot,
+                        chat_id,
+                        Phrase::BotWord(&next_word_details.word),
+                        &next_word_details,
+                    )
+                    .await?;
 
                     // Prompt for the next word
                     bot.send_message(
@@ -290,35 +506,88 @@ async fn process_player_word(
                         ),
                     )
                     .await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
 
                     // Update game state
                     let _ = dialogue
                         .update(SynonymString {
                             chain,
                             curr_char: next_char,
+                            min_similarity,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // Conceding to the very first reply would end the game before any real play
+                    if let Some(opener) = reseed_opener(
+                        &bot,
                         chat_id,
-                        "I can't think of a similar word! You win this round!",
+                        &chain,
+                        &mut progress,
+                        has_healthy_continuation,
                     )
-                    .await?;
-                    let _ = dialogue.update(Start).await;
+                    .await?
+                    {
+                        let curr_char = chain_letter(&opener.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            format!(
+                                "Now give a word starting with '{}' similar to {}",
+                                curr_char, opener.word
+                            ),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(SynonymString {
+                                chain: vec![opener],
+                                curr_char,
+                                min_similarity,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = chain_letter(&word_details.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a word starting with '{}' similar to '{}'",
+                                next_char, word_details.word
+                            )),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(SynonymString {
+                                chain,
+                                curr_char: next_char,
+                                min_similarity,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    let reply = match &e {
+                        SynonymError::Embedding(EmbeddingError::NoWordsForLetter(c)) => {
+                            dead_end_message(*c)
+                        }
+                        _ => say(chat_id, Phrase::BotStumped("a similar word")),
+                    };
+                    bot.send_message(chat_id, reply).await?;
+                    let outcome = GameOutcome::new(&progress, GAME, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(curr_char)
+            })
             .await?;
         }
     }
@@ -326,14 +595,25 @@ async fn process_player_word(
     Ok(())
 }
 
+/// Similarity of two words in the model the chat compares words with, or 0 if either is unknown
+pub fn chat_similarity(chat_id: ChatId, a: &str, b: &str) -> f64 {
+    active_model(chat_id)
+        .and_then(|model| similarity_in(model, a, b))
+        .unwrap_or(0.0)
+}
+
 /// Get the bot's response word with similarity constraints
-async fn get_bot_response(
+pub async fn get_bot_response(
+    chat_id: ChatId,
     player_word: &str,
     used_words: &[String],
+    min_similarity: f64,
+    difficulty: Difficulty,
 ) -> Result<WordInfo, SynonymError> {
     let mut used_words = used_words.to_vec();
+    let model = active_model(chat_id).map_err(SynonymError::Embedding)?;
 
-    let last_char = match player_word.chars().last() {
+    let last_char = match chain_letter(player_word) {
         Some(c) => c,
         None => {
             return Err(SynonymError::InvalidInput(
@@ -345,22 +625,34 @@ async fn get_bot_response(
     // Get a similar word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
         // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, last_char, |x| {
-            !used_words.contains(&x.to_string()) && similarity(player_word, x).unwrap_or(0.0) > 0.8
-        });
+        let next_word_result = get_top_similar_words_in(
+            model,
+            player_word,
+            last_char,
+            Chaining::Strict,
+            difficulty.choices(),
+            |x| {
+                is_playable(x)
+                    && !used_words.contains(&x.to_string())
+                    && similarity_in(model, player_word, x).unwrap_or(0.0) > min_similarity
+            },
+        )
+        .and_then(|words| difficulty.choose(words));
 
         match next_word_result {
             Ok(word) => {
                 // Try to get details for this word
                 match get_word_details(&word).await {
                     Ok(details) => {
-                        let sim_score = similarity(player_word, &word).unwrap_or(0.0);
-                        info!(
+                        let sim_score = similarity_in(model, player_word, &word).unwrap_or(0.0);
+                        debug!(
+                            chat_id = chat_id.0, game = GAME;
                             "Bot found similar word '{}' (similarity: {:.2})",
                             word, sim_score
                         );
@@ -371,10 +663,14 @@ async fn get_bot_response(
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
-                        continue
-                    }, // Try another word
+                        continue;
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -386,6 +682,9 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(SynonymError::Dictionary(e));
+    }
     Err(SynonymError::NoValidWords(format!(
         "Could not find a valid word similar to '{}'",
         player_word
@@ -398,13 +697,14 @@ async fn provide_hint(
     chat_id: ChatId,
     curr_char: char,
     chain: &[WordInfo],
+    min_similarity: f64,
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
 
     let prev_word = match chain.last() {
         Some(w) => &w.word,
         None => {
-            error!("Chain is empty when providing hint");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when providing hint");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
             return Ok(());
@@ -418,7 +718,10 @@ async fn provide_hint(
 
     // Get a random word starting with the current character and similar to previous word
     match get_random_word(
-        |w| similarity(w, prev_word).unwrap_or(0.0) > 0.8 && !used_stems.contains(&w.to_string()),
+        |w| {
+            chat_similarity(chat_id, w, prev_word) > min_similarity
+                && !used_stems.contains(&w.to_string())
+        },
         Some(curr_char),
     )
     .await
@@ -427,8 +730,9 @@ async fn provide_hint(
             bot.send_message(
                 chat_id,
                 format!(
-                    "Hint: You could try a word like '{}' or something similar to '{}'.",
-                    hint.word, prev_word
+                    "{} It should mean something close to '{}'.",
+                    hint_message(chat_id, &hint.word),
+                    prev_word
                 ),
             )
             .await?;
@@ -448,16 +752,16 @@ async fn provide_hint(
 }
 
 /// Skip the current turn
-async fn skip_turn(
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    curr_char: char,
+    (mut chain, curr_char, min_similarity, mut progress): (Vec<WordInfo>, char, f64, Progress),
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
     let used_stems = chain
@@ -468,7 +772,7 @@ async fn skip_turn(
     let prev_word = match chain.last() {
         Some(w) => &w.word,
         None => {
-            error!("Chain is empty when skipping turn");
+            error!(chat_id = chat_id.0, game = GAME; "Chain is empty when skipping turn");
             bot.send_message(chat_id, "Game error - please restart")
                 .await?;
             return Ok(());
@@ -477,27 +781,34 @@ async fn skip_turn(
 
     // Try to get a word for the bot
     match get_random_word(
-        |w| similarity(w, prev_word).unwrap_or(0.0) > 0.8 && !used_stems.contains(&w.to_string()),
+        |w| {
+            chat_similarity(chat_id, w, prev_word) > min_similarity
+                && !used_stems.contains(&w.to_string())
+        },
         Some(curr_char),
     )
     .await
     {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
 
-            let next_char = match word.word.chars().last() {
+            let next_char = match chain_letter(&word.word) {
                 Some(c) => c,
                 None => {
-                    error!("Bot's word '{}' has no characters", word.word);
+                    error!(
+                        chat_id = chat_id.0, game = GAME;
+                        "Bot's word '{}' has no characters",
+                        word.word
+                    );
                     bot.send_message(chat_id, "Error in game, please try again.")
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                    let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+                    finish_game(bot, chat_id, dialogue, outcome).await?;
                     return Ok(());
                 }
             };
 
+            begin_turn(&mut progress, chain.len());
             chain.push(word.clone());
 
             bot.send_message(
@@ -509,21 +820,22 @@ async fn skip_turn(
             )
             .await?;
 
+            next_player(bot, chat_id, &mut progress).await?;
             let _ = dialogue
                 .update(SynonymString {
                     chain,
                     curr_char: next_char,
+                    min_similarity,
+                    progress,
                 })
                 .await;
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, GAME, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
@@ -531,31 +843,90 @@ async fn skip_turn(
 }
 
 /// Show the current score (word count)
-async fn show_score(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
     let player_words = chain.len() / 2;
     let bot_words = chain.len() - player_words;
 
-    bot.send_message(
-        chat_id,
-        format!(
-            "Current score:\nYou: {} words\nBot: {} words",
-            player_words, bot_words
-        ),
-    )
-    .await?;
+    let mut score = format!(
+        "Current score:\nYou: {} words\nBot: {} words",
+        player_words, bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Handle /rewind: take the chain back a few turns so the player can try another path
+async fn rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (mut chain, min_similarity, mut progress): (Vec<WordInfo>, f64, Progress),
+) -> ResponseResult<()> {
+    let turns = match rewind_chain(&mut chain, &mut progress, args) {
+        Ok(turns) => turns,
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+    let Some((last, curr_char)) = chain
+        .last()
+        .and_then(|w| Some((w.word.clone(), chain_letter(&w.word)?)))
+    else {
+        error!(chat_id = chat_id.0, game = GAME; "Rewound chain has no last word");
+        return Ok(());
+    };
+
+    let prompt = format!(
+        "Now give a word starting with '{}' similar to '{}'",
+        curr_char, last
+    );
+    announce_rewind(bot, chat_id, turns, &chain, &mut progress, &prompt).await?;
+
+    let _ = dialogue
+        .update(SynonymString {
+            chain,
+            curr_char,
+            min_similarity,
+            progress,
+        })
+        .await;
 
     Ok(())
 }
 
 /// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    curr_char: char,
+    min_similarity: f64,
+) -> ResponseResult<()> {
     bot.send_message(
         chat_id,
-        "Synonym String Rules:\n\
-        1. Each word must start with the last letter of the previous word\n\
-        2. Each word must be similar in meaning to the previous word\n\
-        3. No repeating words\n\
-        4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
+        format!(
+            "Synonym String Rules:\n\
+            1. Each word must start with the last letter of the previous word (next: '{}')\n\
+            2. Each word must be similar in meaning to the previous word (similarity of at least {:.0}%)\n\
+            3. No repeating words\n\
+            4. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            5. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            curr_char,
+            min_similarity * 100.0
+        ),
     )
     .await?;
 
@@ -0,0 +1,403 @@
+use crate::games::outcome::{finish_game, use_skip, GameOutcome};
+use crate::games::{
+    alphabet_sprint, anagram_shuffle, boggle, categories, compound_chain, forbidden_letters,
+    rhyme_time, scrambled, synonym_string, wait_processing, word_chain, word_ladder,
+};
+use crate::settings::get_settings;
+use crate::state::{DialogueStorage, MyDialogue, State};
+use log::{error, info};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::Bot;
+
+/// Share of the turn timeout after which the player gets a nudge
+const NUDGE_AT: f64 = 0.75;
+
+/// Turns skipped in a row for lack of a word before the game ends anyway, so an abandoned game
+/// doesn't carry on forever
+const MAX_IDLE_SKIPS: u32 = 3;
+
+/// Bot and dialogue storage the timers act through, set once at startup
+static CONTEXT: OnceLock<(Bot, Arc<DialogueStorage>)> = OnceLock::new();
+
+/// Turn number in each chat; a timer only acts while its turn is still the current one
+static TURNS: OnceLock<Mutex<HashMap<ChatId, u64>>> = OnceLock::new();
+
+fn get_turns() -> &'static Mutex<HashMap<ChatId, u64>> {
+    TURNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// When the current turn began in each chat, and how long the turn before it lasted
+static TURN_CLOCKS: OnceLock<Mutex<HashMap<ChatId, (Instant, Duration)>>> = OnceLock::new();
+
+fn get_turn_clocks() -> &'static Mutex<HashMap<ChatId, (Instant, Duration)>> {
+    TURN_CLOCKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Enables turn timers, which act through this bot and storage
+pub fn init_turn_timers(bot: Bot, storage: Arc<DialogueStorage>) {
+    let _ = CONTEXT.set((bot, storage));
+}
+
+/// Start a new turn in a chat, replacing any running timer. Does nothing unless TURN_TIMEOUT_SECS
+/// is set.
+pub fn arm_turn_timer(chat_id: ChatId) {
+    arm(chat_id, 0);
+}
+
+/// Start a new turn following `idle_skips` turns in a row that timed out and were skipped
+fn arm(chat_id: ChatId, idle_skips: u32) {
+    let turn = next_turn(chat_id);
+    start_turn_clock(chat_id);
+    let Some(timeout) = get_settings().turn_timeout else {
+        return;
+    };
+    if CONTEXT.get().is_none() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = run_turn_timer(chat_id, turn, timeout, idle_skips).await {
+            error!(chat_id = chat_id.0; "Turn timer failed: {}", e);
+        }
+    });
+}
+
+/// Run the clock of a game timed as a whole rather than turn by turn, i.e. a Boggle round
+/// ending at `deadline` (Unix time in seconds): warn the player when it's nearly up, then end
+/// the round. The clock does nothing once that round is over.
+pub fn arm_game_clock(chat_id: ChatId, deadline: u64) {
+    let Some((bot, storage)) = CONTEXT.get() else {
+        return;
+    };
+    let dialogue = MyDialogue::new(storage.clone(), chat_id);
+
+    tokio::spawn(async move {
+        if let Err(e) = run_game_clock(bot, chat_id, dialogue, deadline).await {
+            error!(chat_id = chat_id.0; "Game clock failed: {}", e);
+        }
+    });
+}
+
+/// Whether the chat is still playing the Boggle round ending at `deadline`
+async fn round_in_progress(dialogue: &MyDialogue, deadline: u64) -> Option<State> {
+    match dialogue.get().await.ok()?? {
+        state @ State::Boggle { deadline: ends, .. } if ends == deadline => Some(state),
+        _ => None,
+    }
+}
+
+async fn run_game_clock(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    deadline: u64,
+) -> ResponseResult<()> {
+    let left = boggle::time_left(deadline);
+    if left > boggle::WARN_AT {
+        tokio::time::sleep(left - boggle::WARN_AT).await;
+        if round_in_progress(&dialogue, deadline).await.is_none() {
+            return Ok(());
+        }
+        bot.send_message(
+            chat_id,
+            format!("⏳ {} seconds left!", boggle::WARN_AT.as_secs()),
+        )
+        .await?;
+    }
+
+    tokio::time::sleep(boggle::time_left(deadline)).await;
+    // Hold the chat while ending the round, so a word still being checked lands first
+    let _processing = wait_processing(chat_id).await;
+    if let Some(State::Boggle {
+        words,
+        found,
+        progress,
+        ..
+    }) = round_in_progress(&dialogue, deadline).await
+    {
+        boggle::time_up(bot, chat_id, dialogue, &words, found, progress).await?;
+    }
+    Ok(())
+}
+
+/// Stop any running timer in a chat
+pub fn cancel_turn_timer(chat_id: ChatId) {
+    next_turn(chat_id);
+    get_turn_clocks().lock().unwrap().remove(&chat_id);
+}
+
+/// How long the chat's last finished turn lasted, i.e. how long the player took over the word
+/// that was just accepted
+pub fn last_turn_time(chat_id: ChatId) -> Duration {
+    get_turn_clocks()
+        .lock()
+        .unwrap()
+        .get(&chat_id)
+        .map(|&(_, took)| took)
+        .unwrap_or_default()
+}
+
+fn start_turn_clock(chat_id: ChatId) {
+    let mut clocks = get_turn_clocks().lock().unwrap();
+    let now = Instant::now();
+    let took = clocks
+        .get(&chat_id)
+        .map(|&(started, _)| now - started)
+        .unwrap_or_default();
+    clocks.insert(chat_id, (now, took));
+}
+
+fn next_turn(chat_id: ChatId) -> u64 {
+    let mut turns = get_turns().lock().unwrap();
+    let turn = turns.entry(chat_id).or_default();
+    *turn += 1;
+    *turn
+}
+
+/// The chat's game state if `turn` is still the current turn of a timed game
+async fn current_game(chat_id: ChatId, turn: u64) -> Option<(MyDialogue, State)> {
+    if get_turns().lock().unwrap().get(&chat_id) != Some(&turn) {
+        return None;
+    }
+    let (_, storage) = CONTEXT.get()?;
+    let dialogue = MyDialogue::new(storage.clone(), chat_id);
+    let state = dialogue.get().await.ok()??;
+    state.timed_game()?;
+    Some((dialogue, state))
+}
+
+/// Wait out a turn: nudge the player partway through, then skip the turn or end the game if
+/// still no move
+async fn run_turn_timer(
+    chat_id: ChatId,
+    turn: u64,
+    timeout: Duration,
+    idle_skips: u32,
+) -> ResponseResult<()> {
+    let Some((bot, _)) = CONTEXT.get() else {
+        return Ok(());
+    };
+    let mut waited = Duration::ZERO;
+
+    if get_settings().turn_nudge {
+        let nudge_at = timeout.mul_f64(NUDGE_AT);
+        tokio::time::sleep(nudge_at).await;
+        waited = nudge_at;
+
+        let Some((_, state)) = current_game(chat_id, turn).await else {
+            return Ok(());
+        };
+        if let Some(letter) = state.turn_letter() {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Still there? {} seconds left to play a word {}.",
+                    (timeout - nudge_at).as_secs(),
+                    state.turn_direction().describe(letter)
+                ),
+            )
+            .await?;
+        }
+    }
+
+    tokio::time::sleep(timeout - waited).await;
+    // Hold the chat while skipping the turn or ending the game, so the player's move, if one
+    // is still being worked through, lands first and nothing played meanwhile races the timer
+    let _processing = wait_processing(chat_id).await;
+    let Some((dialogue, mut state)) = current_game(chat_id, turn).await else {
+        return Ok(());
+    };
+    let Some(game) = state.timed_game() else {
+        return Ok(());
+    };
+
+    let may_skip = get_settings().turn_timeout_skip && idle_skips < MAX_IDLE_SKIPS;
+    if may_skip && !take_idle_skip(&mut state) {
+        info!(chat_id = chat_id.0; "Turn timed out with no skips left");
+        bot.send_message(
+            chat_id,
+            "Time's up, and you have no skips left, so this game is over.",
+        )
+        .await?;
+        return finish_game(bot, chat_id, dialogue, timed_out(&state, game)).await;
+    }
+    if may_skip {
+        info!(chat_id = chat_id.0; "Turn timed out, skipping it");
+        bot.send_message(chat_id, "⏰ Time's up! Moving on without your word.")
+            .await?;
+        skip_idle_turn(bot, chat_id, dialogue, state).await?;
+        // The skip started the next turn, which gets a timer of its own
+        if current_game(chat_id, turn).await.is_some() {
+            arm(chat_id, idle_skips + 1);
+        }
+        return Ok(());
+    }
+
+    info!(chat_id = chat_id.0; "Turn timed out");
+    bot.send_message(
+        chat_id,
+        "Time's up! No word came in time, so this game is over.",
+    )
+    .await?;
+    finish_game(bot, chat_id, dialogue, timed_out(&state, game)).await
+}
+
+/// Use up one of the player's skips for a turn that timed out, the same as /skip would.
+/// Returns false, using nothing, when they have none left.
+fn take_idle_skip(state: &mut State) -> bool {
+    state.progress_mut().is_none_or(use_skip)
+}
+
+/// Unwon outcome of a game that ended because no word came in time
+fn timed_out(state: &State, game: &'static str) -> GameOutcome {
+    let progress = state.progress().cloned().unwrap_or_default();
+    match state {
+        State::AnagramShuffle { solved, .. } => anagram_shuffle::outcome(&progress, None, solved),
+        State::Categories { words, .. } => categories::outcome(&progress, None, words),
+        _ => GameOutcome::new(&progress, game, None, state.chain()),
+    }
+}
+
+/// Skip the turn of a player who didn't play in time, the same way /skip would
+async fn skip_idle_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    state: State,
+) -> ResponseResult<()> {
+    match state {
+        State::WordChain {
+            chain,
+            curr_char,
+            hotseat: Some(seat),
+            direction,
+            progress,
+            ..
+        } => {
+            let game = (chain, curr_char, seat, direction, progress);
+            word_chain::pass_turn(bot, chat_id, dialogue, game).await
+        }
+        State::WordChain {
+            chain,
+            curr_char,
+            board,
+            direction,
+            progress,
+            ..
+        } => {
+            let game = (chain, curr_char, board, direction, progress);
+            word_chain::skip_turn(bot, chat_id, dialogue, game).await
+        }
+        State::AlphabetSprint {
+            alphabet,
+            words,
+            progress,
+        } => alphabet_sprint::skip_turn(bot, chat_id, dialogue, words, alphabet, progress).await,
+        State::LastLetterScramble {
+            level,
+            chain,
+            curr_char,
+            progress,
+        } => scrambled::skip_turn(bot, chat_id, dialogue, chain, level, curr_char, progress).await,
+        State::SynonymString {
+            chain,
+            curr_char,
+            min_similarity,
+            progress,
+        } => {
+            let game = (chain, curr_char, min_similarity, progress);
+            synonym_string::skip_turn(bot, chat_id, dialogue, game).await
+        }
+        State::WordLengthLadder {
+            curr_len,
+            max_len,
+            chain,
+            curr_char,
+            direction,
+            progress,
+        } => {
+            word_ladder::skip_turn(
+                bot, chat_id, dialogue, chain, curr_len, max_len, curr_char, direction, progress,
+            )
+            .await
+        }
+        State::ForbiddenLetters {
+            forbidden_letters,
+            chain,
+            curr_char,
+            progress,
+        } => {
+            forbidden_letters::skip_turn(
+                bot,
+                chat_id,
+                dialogue,
+                chain,
+                forbidden_letters,
+                curr_char,
+                progress,
+            )
+            .await
+        }
+        State::AnagramShuffle {
+            target,
+            solved,
+            progress,
+            ..
+        } => anagram_shuffle::skip_turn(bot, chat_id, dialogue, target, solved, progress).await,
+        State::Categories {
+            theme,
+            threshold,
+            words,
+            progress,
+        } => {
+            let words = (words, progress);
+            categories::skip_turn(bot, chat_id, dialogue, theme, threshold, words).await
+        }
+        State::CompoundChain {
+            chain,
+            current_tail,
+            progress,
+        } => compound_chain::skip_turn(bot, chat_id, dialogue, chain, current_tail, progress).await,
+        State::RhymeTime { chain, progress } => {
+            rhyme_time::skip_turn(bot, chat_id, dialogue, chain, progress).await
+        }
+        // Never timed
+        State::Start
+        | State::Relay { .. }
+        | State::VocabCheck { .. }
+        | State::Ghost { .. }
+        | State::Boggle { .. }
+        | State::GuessWord { .. } => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::outcome::{skips_left, Progress};
+    use crate::games::Direction;
+
+    #[test]
+    fn timed_out_turns_use_up_skips_like_skip_does() {
+        let mut progress = Progress::default();
+        // Skips are limited by default
+        while skips_left(&progress).unwrap() > 1 {
+            use_skip(&mut progress);
+        }
+        let mut state = State::WordChain {
+            chain: Vec::new(),
+            curr_char: 'a',
+            hotseat: None,
+            board: None,
+            direction: Direction::Forward,
+            progress,
+        };
+
+        assert!(take_idle_skip(&mut state));
+        assert!(!take_idle_skip(&mut state));
+        assert_eq!(state.progress().and_then(skips_left), Some(0));
+    }
+}
@@ -0,0 +1,365 @@
+use crate::command::Command;
+use crate::dictionary::{get_word_details, WordInfo};
+use crate::frequency::random_word_ranked;
+use crate::games::group::{handle_join_command, handle_leave_command};
+use crate::games::outcome::Progress;
+use crate::games::{looks_like_command, reply_unknown_command, show_help};
+use crate::state::MyDialogue;
+use crate::state::State::{Start, VocabCheck};
+use log::{debug, info, warn};
+use std::ops::RangeInclusive;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me};
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+
+/// Menu id of this game
+pub const GAME: &str = "vocab_check";
+
+/// Difficulty tiers the check walks through, from the most common words to rare ones, by
+/// frequency rank
+const TIERS: [(&str, RangeInclusive<u32>); 5] = [
+    ("Everyday", 1..=1_000),
+    ("Common", 1_001..=5_000),
+    ("Intermediate", 5_001..=10_000),
+    ("Advanced", 10_001..=20_000),
+    ("Rare", 20_001..=50_000),
+];
+
+/// Words asked from each tier
+const WORDS_PER_TIER: usize = 4;
+
+/// Words tried per question before giving up on finding one the dictionary defines
+const LOOKUP_ATTEMPTS: usize = 5;
+
+/// Words too vague to show that a definition matches
+const FILLER_WORDS: [&str; 14] = [
+    "that",
+    "this",
+    "with",
+    "from",
+    "which",
+    "what",
+    "when",
+    "where",
+    "into",
+    "have",
+    "being",
+    "thing",
+    "something",
+    "someone",
+];
+
+/// Total number of questions in a full check
+fn total_questions() -> usize {
+    TIERS.len() * WORDS_PER_TIER
+}
+
+/// Start a vocabulary check: words of increasing rarity, each one the player says they know or not
+pub async fn start_vocab_check(
+    chat_id: ChatId,
+    bot: Bot,
+    dialogue: MyDialogue,
+) -> ResponseResult<()> {
+    info!(chat_id = chat_id.0, game = GAME; "Starting vocabulary check");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "📚 Vocabulary check! I'll show you {} words, from everyday ones to rare ones. For each, tap whether you know it — or reply with what it means — and at the end I'll estimate how many words you know.",
+            total_questions()
+        ),
+    )
+    .await?;
+
+    ask_next(&bot, chat_id, dialogue, Vec::new(), Progress::default()).await
+}
+
+/// Handle player input during a vocabulary check
+pub async fn vocab_check(
+    bot: Bot,
+    dialogue: MyDialogue,
+    (word, answers, progress): (WordInfo, Vec<bool>, Progress),
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some(text) = msg.text() else {
+        bot.send_message(chat_id, "Please send a text message.")
+            .await?;
+        return Ok(());
+    };
+
+    match BotCommands::parse(text, me.username()) {
+        Ok(Command::Stop) => {
+            info!(chat_id = chat_id.0, game = GAME; "Player stopped the vocabulary check");
+            finish(&bot, chat_id, dialogue, &answers).await?;
+        }
+        Ok(Command::Skip) => {
+            record_answer(&bot, chat_id, dialogue, (word, answers, progress), false).await?;
+        }
+        Ok(Command::Score) => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Word {} of {}. You've known {} of the {} so far.",
+                    answers.len() + 1,
+                    total_questions(),
+                    answers.iter().filter(|&&known| known).count(),
+                    answers.len()
+                ),
+            )
+            .await?;
+        }
+        Ok(Command::Rules) => {
+            bot.send_message(
+                chat_id,
+                "Vocabulary Check rules:\n\
+                1. Each word is a little rarer than the last\n\
+                2. Tap \"I know it\" or \"Don't know\", or reply yes or no\n\
+                3. Or reply with what the word means, and I'll check it against the dictionary\n\
+                4. /skip counts as not knowing the word\n\
+                5. /stop ends the check early with an estimate from the words so far",
+            )
+            .await?;
+        }
+        Ok(Command::Help) => {
+            show_help(&bot, chat_id).await?;
+        }
+        Ok(Command::Join) => {
+            handle_join_command(&bot, &msg, &dialogue).await?;
+        }
+        Ok(Command::Leave) => {
+            handle_leave_command(&bot, &msg, &dialogue).await?;
+        }
+        Ok(_) => {
+            bot.send_message(
+                chat_id,
+                "Please finish the vocabulary check or end it with /stop to use this command.",
+            )
+            .await?;
+        }
+        Err(_) if looks_like_command(text) => {
+            reply_unknown_command(&bot, chat_id, text).await?;
+        }
+        Err(_) => {
+            let reply = text.trim().to_lowercase();
+            let known = match reply.as_str() {
+                "yes" | "y" | "yeah" | "yep" => true,
+                "no" | "n" | "nope" => false,
+                _ => {
+                    let matches = matches_definition(&reply, &word);
+                    let verdict = if matches {
+                        "✅ That matches the dictionary."
+                    } else {
+                        "❌ That's not quite it, so I'll count this one as unknown."
+                    };
+                    bot.send_message(chat_id, verdict).await?;
+                    matches
+                }
+            };
+            record_answer(&bot, chat_id, dialogue, (word, answers, progress), known).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record whether the player knows the word in play, show what it means, and move on
+pub async fn record_answer(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    (word, mut answers, progress): (WordInfo, Vec<bool>, Progress),
+    known: bool,
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Vocabulary check: '{}' known: {}", word.word, known);
+    answers.push(known);
+    if let Some(def) = word.defs.first() {
+        bot.send_message(
+            chat_id,
+            format!(
+                "📖 {} ({}): {}",
+                word.word,
+                def.functional_label,
+                def.definitions.first().map(String::as_str).unwrap_or("")
+            ),
+        )
+        .await?;
+    }
+
+    ask_next(bot, chat_id, dialogue, answers, progress).await
+}
+
+/// Ask about the next word, or wrap up once every tier has been covered
+async fn ask_next(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    answers: Vec<bool>,
+    progress: Progress,
+) -> ResponseResult<()> {
+    if answers.len() >= total_questions() {
+        return finish(bot, chat_id, dialogue, &answers).await;
+    }
+
+    let (tier, ranks) = &TIERS[answers.len() / WORDS_PER_TIER];
+    let Some(word) = pick_word(ranks).await else {
+        warn!(
+            chat_id = chat_id.0, game = GAME;
+            "Vocabulary check found no word ranked {:?}",
+            ranks
+        );
+        if answers.is_empty() {
+            bot.send_message(
+                chat_id,
+                "Sorry, I can't find words to test you on right now. Please try again later.",
+            )
+            .await?;
+            let _ = dialogue.update(Start).await;
+            return Ok(());
+        }
+        bot.send_message(
+            chat_id,
+            "I couldn't find any more words to ask about, so let's wrap up here.",
+        )
+        .await?;
+        return finish(bot, chat_id, dialogue, &answers).await;
+    };
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("✅ I know it", format!("vocab_yes_{}", word.word)),
+        InlineKeyboardButton::callback("❌ Don't know", format!("vocab_no_{}", word.word)),
+    ]]);
+    bot.send_message(
+        chat_id,
+        format!(
+            "Word {} of {} ({}): {}",
+            answers.len() + 1,
+            total_questions(),
+            tier.to_lowercase(),
+            word.word
+        ),
+    )
+    .reply_markup(keyboard)
+    .await?;
+
+    let _ = dialogue
+        .update(VocabCheck {
+            word,
+            answers,
+            progress,
+        })
+        .await;
+    Ok(())
+}
+
+/// A defined word of plain letters ranked within `ranks`
+async fn pick_word(ranks: &RangeInclusive<u32>) -> Option<WordInfo> {
+    for _ in 0..LOOKUP_ATTEMPTS {
+        let word = random_word_ranked(ranks.clone(), |w| {
+            w.len() >= 3 && w.chars().all(|c| c.is_ascii_lowercase())
+        })?;
+        match get_word_details(&word).await {
+            Ok(info) if !info.defs.is_empty() => return Some(info),
+            Ok(_) => debug!(game = GAME; "Skipping '{}': no definition", word),
+            Err(e) => debug!(game = GAME; "Skipping '{}': {}", word, e),
+        }
+    }
+    None
+}
+
+/// Whether the player's attempt at a definition shares a meaningful word with one of the word's
+/// senses. Words sharing their first five letters count as the same, so "runs" doesn't need to
+/// match "running" exactly.
+fn matches_definition(attempt: &str, word: &WordInfo) -> bool {
+    fn key_words(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphabetic())
+            .map(str::to_lowercase)
+            .filter(|w| w.chars().count() >= 4 && !FILLER_WORDS.contains(&w.as_str()))
+            .map(|w| w.chars().take(5).collect())
+            .collect()
+    }
+
+    let attempt = key_words(attempt);
+    word.defs
+        .iter()
+        .flat_map(|def| &def.definitions)
+        .flat_map(|sense| key_words(sense))
+        .any(|w| attempt.contains(&w))
+}
+
+/// Estimated number of words the player knows: each tier's share of known words, scaled to the
+/// size of the tier. Tiers the check didn't reach count for nothing.
+fn estimate(answers: &[bool]) -> u32 {
+    answers
+        .chunks(WORDS_PER_TIER)
+        .zip(TIERS.iter())
+        .map(|(tier_answers, (_, ranks))| {
+            let known = tier_answers.iter().filter(|&&known| known).count() as f64;
+            let size = f64::from(ranks.end() - ranks.start() + 1);
+            known / tier_answers.len() as f64 * size
+        })
+        .sum::<f64>()
+        .round() as u32
+}
+
+/// Report the estimate with a breakdown by tier and end the check
+async fn finish(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    answers: &[bool],
+) -> ResponseResult<()> {
+    let _ = dialogue.update(Start).await;
+
+    if answers.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Vocabulary check ended before any words. Use /start to try again.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let breakdown = answers
+        .chunks(WORDS_PER_TIER)
+        .zip(TIERS.iter())
+        .map(|(tier_answers, (tier, ranks))| {
+            format!(
+                "{} (ranks {}-{}): {}/{}",
+                tier,
+                ranks.start(),
+                ranks.end(),
+                tier_answers.iter().filter(|&&known| known).count(),
+                tier_answers.len()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    // Round to the nearest hundred; a handful of words per tier can't be more precise
+    let estimate = (estimate(answers) + 50) / 100 * 100;
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Vocabulary check finished: about {} words from {} answers",
+        estimate, answers.len()
+    );
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "📚 Vocabulary check complete!\n\n{}\n\nEstimated vocabulary: about {} words{}",
+            breakdown,
+            estimate,
+            if answers.len() < total_questions() {
+                " (the rarer tiers weren't reached, so this is a floor)"
+            } else {
+                ""
+            }
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
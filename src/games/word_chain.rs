@@ -1,19 +1,47 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
 use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, EmbeddingError};
-use crate::state::MyDialogue;
-use crate::state::State::{Start, WordChain};
-use log::{error, info, warn};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word, get_random_word_ending, get_random_word_with_pos, get_submission_details,
+    get_word_details, is_playable, DictionaryError, WordInfo,
+};
+use crate::embeddings::{
+    get_any_word, get_dissimilar_word, get_dissimilar_word_ending, get_top_similar_words,
+    get_top_similar_words_ending, random_word_ending_with, Chaining, EmbeddingError,
+};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    begin_turn, bot_concedes, count_player_word, finish_game, game_started, reach_word_target,
+    score_extras, skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::{
+    allow_skip, announce_bot_word, announce_rewind, begin_processing, carry_on_message,
+    credit_player_word, define_chain_word, is_just_played, just_played_message, looks_like_command,
+    normalize_word, reject_word, reply_unknown_command, reseed_opener, retry_turn, rewind_chain,
+    show_dead_ends, show_help, still_processing, Direction,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_bot_style, get_difficulty, handle_bot_style_command, handle_compact_command,
+    handle_define_button_command, handle_difficulty_command, handle_examples_command,
+    handle_tone_command, is_compact, BotStyle, Difficulty,
+};
+use crate::settings::get_settings;
+use crate::state::State::WordChain;
+use crate::state::{Hotseat, MyDialogue};
+use crate::tone::{say, Phrase};
+use log::{debug, error, info, warn};
 use teloxide::prelude::ResponseResult;
 use teloxide::prelude::*;
-use teloxide::types::{Me, Message};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me, Message, MessageId};
 use teloxide::utils::command::BotCommands;
 use teloxide::Bot;
 
 /// Error type specific to Word Chain game
 #[derive(Debug)]
-enum WordChainError {
+pub enum WordChainError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
     InvalidInput(String),
@@ -32,6 +60,18 @@ impl From<EmbeddingError> for WordChainError {
     }
 }
 
+impl WordChainError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            WordChainError::Dictionary(e) => e.is_transient(),
+            WordChainError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for WordChainError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -43,62 +83,107 @@ impl std::fmt::Display for WordChainError {
     }
 }
 
-/// Start a new Word Chain game
+/// Menu id of this game
+pub const GAME: &str = "word_chain";
+/// Menu id of Word Chain with reverse chaining
+pub const REVERSE_GAME: &str = "word_chain_reverse";
+
+/// Menu id of a Word Chain game linking words in `direction`
+fn game_id(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Forward => GAME,
+        Direction::Reverse => REVERSE_GAME,
+    }
+}
+
+/// Start a new Word Chain game, either against the bot or as a two-player hotseat game, with
+/// words linking in the given direction
 pub async fn start_word_chain(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
+    hotseat: bool,
+    direction: Direction,
 ) -> ResponseResult<()> {
-    info!("Starting Word Chain game for chat {}", chat_id);
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Starting Word Chain game (hotseat: {}, direction: {:?})",
+        hotseat, direction
+    );
 
-    bot.send_message(
-        chat_id,
-        "You selected Word Chain! Let's start linking words.",
-    )
-    .await?;
+    let intro = match (hotseat, direction) {
+        (true, _) => "You selected two-player Word Chain! Pass the device back and forth and take turns linking words.",
+        (false, Direction::Forward) => "You selected Word Chain! Let's start linking words.",
+        (false, Direction::Reverse) => "You selected Reverse Word Chain! Each word has to end with the first letter of the one before it.",
+    };
+    bot.send_message(chat_id, intro).await?;
+    let hotseat = hotseat.then(Hotseat::default);
+
+    // Open with a word the chain can carry on from, so the first reply isn't a dead end
+    let open = direction.open_letters();
 
     // Try to get a random word to start the game
     for _ in 0..3 {
         // Try up to 3 times
-        match get_random_word(|_| true, None).await {
+        match get_random_word(|w| direction.opens(w, &open), None).await {
             Ok(word) => {
-                info!("Word Chain started with word: {}", word.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Word Chain started with word: {}",
+                    word.word
+                );
 
-                // Get the last character of the word for the next word
-                let curr_char = match word.word.chars().last() {
+                // Get the letter the next word links on
+                let curr_char = match direction.link_letter(&word.word) {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                // Send the first word
-                bot.send_message(chat_id, format!("First word: {}", word.word))
-                    .await?;
-                word.send_message(&bot, chat_id, 0).await?;
+                // Send the first word and prompt, on a single board in compact mode
+                let board = if hotseat.is_none() && is_compact(chat_id) {
+                    let prompt = turn_prompt(chat_id, curr_char, direction, None);
+                    Some(
+                        update_board(&bot, chat_id, None, &[("First word", &word)], &prompt)
+                            .await?,
+                    )
+                } else {
+                    announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
-                // Prompt user for the next word
-                bot.send_message(
-                    chat_id,
-                    format!("Now give a word starting with '{}'", curr_char),
-                )
-                .await?;
+                    // Prompt user for the next word
+                    bot.send_message(
+                        chat_id,
+                        turn_prompt(chat_id, curr_char, direction, hotseat.as_ref()),
+                    )
+                    .await?;
+                    None
+                };
 
                 // Update dialogue state
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(WordChain {
                         chain: vec![word],
                         curr_char,
+                        hotseat,
+                        board,
+                        direction,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
                 // Try again
             }
         }
@@ -118,54 +203,187 @@ pub async fn start_word_chain(
 pub async fn word_chain(
     bot: Bot,
     dialogue: MyDialogue,
-    (chain, curr_char): (Vec<WordInfo>, char),
+    (chain, curr_char, hotseat, board, direction, mut progress): (
+        Vec<WordInfo>,
+        char,
+        Option<Hotseat>,
+        Option<MessageId>,
+        Direction,
+        Progress,
+    ),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
-                provide_hint(&bot, msg.chat.id, curr_char, &chain).await?;
+            Ok(Command::Hint(pos)) => {
+                provide_hint(&bot, msg.chat.id, curr_char, direction, &chain, &pos).await?;
+            }
+            Ok(Command::Skip) => match hotseat {
+                Some(seat) => {
+                    let game = (chain, curr_char, seat, direction, progress);
+                    pass_turn(&bot, msg.chat.id, dialogue, game).await?;
+                }
+                None => {
+                    if !allow_skip(&bot, &msg, &mut progress).await? {
+                        return Ok(());
+                    }
+                    let game = (chain, curr_char, board, direction, progress);
+                    skip_turn(&bot, msg.chat.id, dialogue, game).await?;
+                }
+            },
+            Ok(Command::Score) => match &hotseat {
+                Some(seat) => show_hotseat_score(&bot, msg.chat.id, seat).await?,
+                None => show_score(&bot, msg.chat.id, &chain, &progress).await?,
+            },
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Rewind(args)) => match hotseat {
+                Some(_) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        "Rewinding isn't available in two-player games.",
+                    )
+                    .await?;
+                }
+                None => {
+                    rewind(
+                        &bot,
+                        msg.chat.id,
+                        dialogue,
+                        &args,
+                        (chain, direction, progress),
+                    )
+                    .await?
+                }
+            },
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
             }
-            Ok(Command::Skip) => {
-                skip_turn(&bot, msg.chat.id, dialogue, chain, curr_char).await?;
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
             }
-            Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &chain).await?;
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id).await?;
+                show_rules(&bot, msg.chat.id, curr_char, direction, hotseat.as_ref()).await?;
             }
             Ok(Command::Stop) => {
-                info!("Player stopped Word Chain game in chat {}", msg.chat.id);
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Word Chain game");
 
                 // Show final score/summary
-                let player_words = chain.len() / 2;
-                let bot_words = chain.len() - player_words;
+                let words = chain
+                    .iter()
+                    .map(|w| w.word.clone())
+                    .collect::<Vec<String>>()
+                    .join(" → ");
+
+                match &hotseat {
+                    Some(seat) => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "Game finished! Final score:\nPlayer 1: {} words\nPlayer 2: {} words\n\n{}\n\nWord chain: {}",
+                                seat.scores[0],
+                                seat.scores[1],
+                                seat.result(),
+                                words
+                            ),
+                        )
+                        .await?;
+                    }
+                    None => {
+                        let player_words = chain.len() / 2;
+                        let bot_words = chain.len() - player_words;
+
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "Game finished! Final score:\nYou: {} words\nBot: {} words\n\nWord chain: {}",
+                                player_words, bot_words, words
+                            ),
+                        )
+                        .await?;
+                    }
+                }
 
                 bot.send_message(
                     msg.chat.id,
-                    format!(
-                        "Game finished! Final score:\nYou: {} words\nBot: {} words\n\nWord chain: {}",
-                        player_words,
-                        bot_words,
-                        chain.iter().map(|w| w.word.clone()).collect::<Vec<String>>().join(" → ")
-                    ),
-                ).await?;
-
-                bot.send_message(msg.chat.id, "Word Chain game stopped. Thanks for playing!")
-                    .await?;
-                let _ = dialogue.update(Start).await;
+                    say(msg.chat.id, Phrase::GameStopped("Word Chain")),
+                )
+                .await?;
+                let outcome = match &hotseat {
+                    Some(seat) => {
+                        GameOutcome::new(&progress, game_id(direction), Some(seat.winner()), &chain)
+                            .with_scores(seat.scores[0], seat.scores[1])
+                    }
+                    None => GameOutcome::new(&progress, game_id(direction), None, &chain),
+                };
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
             }
             Err(_) => {
-                process_player_word(text, bot, dialogue, chain, curr_char, msg.chat.id).await?;
+                if check_turn(&bot, &msg, &progress).await? {
+                    let game = (chain, curr_char, hotseat, board, direction, progress);
+                    process_player_word(text, bot, dialogue, game, msg.chat.id).await?;
+                }
             }
         },
         None => {
@@ -180,8 +398,14 @@ async fn process_player_word(
     text: &str,
     bot: Bot,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    curr_char: char,
+    (mut chain, curr_char, hotseat, board, direction, mut progress): (
+        Vec<WordInfo>,
+        char,
+        Option<Hotseat>,
+        Option<MessageId>,
+        Direction,
+        Progress,
+    ),
     chat_id: ChatId,
 ) -> ResponseResult<()> {
     let words = text.split_whitespace().collect::<Vec<&str>>();
@@ -198,15 +422,31 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
 
-    // Check if word starts with the current character
-    if !word.starts_with(curr_char) {
-        bot.send_message(
-            chat_id,
-            format!("Your word must start with '{}'", curr_char),
-        )
-        .await?;
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    let turns_back = if hotseat.is_some() { 1 } else { 2 };
+    if is_just_played(&chain, &word, turns_back) {
+        let reply = match &hotseat {
+            Some(seat) => format!(
+                "Player {} already played '{}' — it's Player {}'s turn now.",
+                3 - seat.player(),
+                word,
+                seat.player()
+            ),
+            None => just_played_message(&word),
+        };
+        bot.send_message(chat_id, reply).await?;
+        return Ok(());
+    }
+
+    // Check if word links on the current character
+    if !direction.fits(&word, curr_char) {
+        let phrase = match direction {
+            Direction::Forward => Phrase::MustStartWith(curr_char),
+            Direction::Reverse => Phrase::MustEndWith(curr_char),
+        };
+        bot.send_message(chat_id, say(chat_id, phrase)).await?;
         return Ok(());
     }
 
@@ -217,75 +457,218 @@ async fn process_player_word(
         .collect::<Vec<String>>();
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
             if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!("Player used word: {} in chat {}", word, chat_id);
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
             let mut updated_stems = used_stems.clone();
             updated_stems.push(word.clone());
 
-            word_details.send_message(&bot, chat_id, 0).await?;
+            // In compact mode the player's word only shows up on the board
+            let compact = hotseat.is_none() && is_compact(chat_id);
+            if !compact && !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            begin_turn(&mut progress, chain.len());
             chain.push(word_details.clone());
 
+            // In hotseat mode the other player answers instead of the bot
+            if let Some(mut seat) = hotseat {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let next_char = match direction.link_letter(&word_details.word) {
+                    Some(c) => c,
+                    None => {
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Player word '{}' has no characters",
+                            word_details.word
+                        );
+                        return Ok(());
+                    }
+                };
+                seat.record_word();
+
+                bot.send_message(
+                    chat_id,
+                    turn_prompt(chat_id, next_char, direction, Some(&seat)),
+                )
+                .await?;
+                next_player(&bot, chat_id, &mut progress).await?;
+
+                let _ = dialogue
+                    .update(WordChain {
+                        chain,
+                        curr_char: next_char,
+                        hotseat: Some(seat),
+                        board: None,
+                        direction,
+                        progress,
+                    })
+                    .await;
+                return Ok(());
+            }
+
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome =
+                    GameOutcome::new(&progress, game_id(direction), Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
             // Get the bot's response word
-            match get_bot_response(&word, &updated_stems).await {
+            let style = get_bot_style(chat_id);
+            let response = get_bot_response(
+                &word_details.word,
+                &updated_stems,
+                style,
+                direction,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
 
                     // Get the next character for the player's turn
-                    let next_char = match next_word_details.word.chars().last() {
+                    let next_char = match direction.link_letter(&next_word_details.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot word '{}' has no characters", next_word_details.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot word '{}' has no characters",
+                                next_word_details.word
+                            );
                             return Ok(());
                         }
                     };
+                    let prompt = turn_prompt(chat_id, next_char, direction, None);
 
-                    // Prompt for the next word
-                    bot.send_message(
-                        chat_id,
-                        format!("Now give a word starting with '{}'", next_char),
-                    )
-                    .await?;
+                    // Lenient chaining may have had to pick a word starting with another letter
+                    if let Some(wanted) = direction.link_letter(&word_details.word) {
+                        if !direction.fits(&next_word_details.word, wanted) {
+                            bot.send_message(
+                                chat_id,
+                                format!(
+                                    "No word in my vocabulary starts with '{}', so I picked another letter.",
+                                    wanted
+                                ),
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let board = if compact {
+                        let exchange = [("You", &word_details), ("Me", &next_word_details)];
+                        Some(update_board(&bot, chat_id, board, &exchange, &prompt).await?)
+                    } else {
+                        announce_bot_word(
+                            &bot,
+                            chat_id,
+                            Phrase::BotWord(&next_word_details.word),
+                            &next_word_details,
+                        )
+                        .await?;
+
+                        // Prompt for the next word
+                        bot.send_message(chat_id, prompt).await?;
+                        None
+                    };
+                    next_player(&bot, chat_id, &mut progress).await?;
 
                     // Update game state
                     let _ = dialogue
                         .update(WordChain {
                             chain,
                             curr_char: next_char,
+                            hotseat: None,
+                            board,
+                            direction,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(chat_id, "I can't think of a word! You win this round!")
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // Conceding to the very first reply would end the game before any real play
+                    if let Some(opener) = reseed_opener(&bot, chat_id, &chain, &mut progress, |w| {
+                        direction.has_healthy_link(w)
+                    })
+                    .await?
+                    {
+                        let curr_char = direction.link_letter(&opener.word).unwrap_or(curr_char);
+                        bot.send_message(chat_id, turn_prompt(chat_id, curr_char, direction, None))
+                            .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(WordChain {
+                                chain: vec![opener],
+                                curr_char,
+                                hotseat: None,
+                                board: None,
+                                direction,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = direction
+                            .link_letter(&word_details.word)
+                            .unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a word {}",
+                                direction.describe(next_char)
+                            )),
+                        )
                         .await?;
-                    let _ = dialogue.update(Start).await;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(WordChain {
+                                chain,
+                                curr_char: next_char,
+                                hotseat: None,
+                                board,
+                                direction,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    bot.send_message(chat_id, say(chat_id, Phrase::BotStumped("a word")))
+                        .await?;
+                    let outcome = GameOutcome::new(
+                        &progress,
+                        game_id(direction),
+                        Some(Winner::Player),
+                        &chain,
+                    );
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                direction.fits(w, curr_char)
+            })
             .await?;
         }
     }
@@ -294,29 +677,57 @@ async fn process_player_word(
 }
 
 /// Get the bot's response word
-async fn get_bot_response(
+pub async fn get_bot_response(
     player_word: &str,
     used_words: &[String],
+    style: BotStyle,
+    direction: Direction,
+    difficulty: Difficulty,
 ) -> Result<WordInfo, WordChainError> {
     let mut used_words = used_words.to_vec();
 
-    // Get the last character of the player's word
-    let last_char = player_word
-        .chars()
-        .last()
+    // Get the letter the player's word passes on
+    let link_char = direction
+        .link_letter(player_word)
         .ok_or_else(|| WordChainError::InvalidInput("Player word has no characters".to_string()))?;
 
-    // Get a similar word that hasn't been used
+    // Get a word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
-        // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, last_char, |x| {
-            !used_words.contains(&x.to_string())
-        });
+        // Try to find a word in the chat's chosen style
+        let predicate = |x: &str| is_playable(x) && !used_words.contains(&x.to_string());
+        let next_word_result = match (direction, style) {
+            (Direction::Forward, BotStyle::Similar) => get_top_similar_words(
+                player_word,
+                link_char,
+                Chaining::Lenient,
+                difficulty.choices(),
+                predicate,
+            )
+            .and_then(|words| difficulty.choose(words)),
+            (Direction::Forward, BotStyle::Dissimilar) => {
+                get_dissimilar_word(player_word, link_char, Chaining::Lenient, predicate)
+            }
+            (Direction::Forward, BotStyle::Random) => {
+                get_any_word(link_char, Chaining::Lenient, predicate)
+            }
+            (Direction::Reverse, BotStyle::Similar) => get_top_similar_words_ending(
+                player_word,
+                link_char,
+                difficulty.choices(),
+                predicate,
+            )
+            .and_then(|words| difficulty.choose(words)),
+            (Direction::Reverse, BotStyle::Dissimilar) => {
+                get_dissimilar_word_ending(player_word, link_char, predicate)
+            }
+            (Direction::Reverse, BotStyle::Random) => random_word_ending_with(link_char, predicate),
+        };
 
         match next_word_result {
             Ok(word) => {
@@ -329,10 +740,14 @@ async fn get_bot_response(
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
                         continue;
-                    }, // Try another word
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -344,9 +759,12 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(WordChainError::Dictionary(e));
+    }
     Err(WordChainError::NoValidWords(format!(
-        "Could not find a valid word starting with '{}'",
-        last_char
+        "Could not find a valid word {}",
+        direction.describe(link_char)
     )))
 }
 
@@ -355,33 +773,57 @@ async fn provide_hint(
     bot: &Bot,
     chat_id: ChatId,
     curr_char: char,
+    direction: Direction,
     chain: &[WordInfo],
+    pos: &str,
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
+
+    let pos = pos.trim().to_lowercase();
+    if !pos.is_empty() && !HINT_PARTS_OF_SPEECH.contains(&pos.as_str()) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "I can hint at a {}. Or just /hint for any word.",
+                HINT_PARTS_OF_SPEECH.join(", ")
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
 
     let used_stems = chain
         .iter()
         .flat_map(|x| x.stems.clone())
         .collect::<Vec<String>>();
 
-    // Get a random word starting with the current character (not used before)
-    match get_random_word(|w| !used_stems.contains(&w.to_string()), Some(curr_char)).await {
-        Ok(hint) => {
+    // Get a random word linking on the current character (not used before)
+    let hint = match (pos.as_str(), direction) {
+        ("", _) => random_link_word(&used_stems, curr_char, direction).await,
+        (pos, Direction::Forward) => {
+            let unused = |w: &str| !used_stems.contains(&w.to_string());
+            get_random_word_with_pos(unused, Some(curr_char), pos).await
+        }
+        (_, Direction::Reverse) => {
             bot.send_message(
                 chat_id,
-                format!(
-                    "Hint: You could try a word like '{}' or something similar.",
-                    hint.word
-                ),
+                "Hints for a kind of word only work in the regular Word Chain. Try /hint on its own.",
             )
             .await?;
+            return Ok(());
+        }
+    };
+    match hint {
+        Ok(hint) => {
+            bot.send_message(chat_id, hint_message(chat_id, &hint.word))
+                .await?;
         }
         Err(_) => {
             bot.send_message(
                 chat_id,
                 format!(
-                    "I can't think of a hint right now. Just try any word starting with '{}'.",
-                    curr_char
+                    "I can't think of a hint right now. Just try any word {}.",
+                    direction.describe(curr_char)
                 ),
             )
             .await?;
@@ -391,17 +833,87 @@ async fn provide_hint(
     Ok(())
 }
 
+/// Parts of speech /hint can be asked for
+const HINT_PARTS_OF_SPEECH: [&str; 4] = ["noun", "verb", "adjective", "adverb"];
+
+/// A random unused word that links on `curr_char` in the given direction
+async fn random_link_word(
+    used_stems: &[String],
+    curr_char: char,
+    direction: Direction,
+) -> Result<WordInfo, DictionaryError> {
+    let unused = |w: &str| !used_stems.contains(&w.to_string());
+    match direction {
+        Direction::Forward => get_random_word(unused, Some(curr_char)).await,
+        Direction::Reverse => get_random_word_ending(unused, curr_char).await,
+    }
+}
+
+/// Show the latest exchange and the prompt on the compact game board, editing the existing
+/// board in place when possible, and return the board's message id
+async fn update_board(
+    bot: &Bot,
+    chat_id: ChatId,
+    board: Option<MessageId>,
+    exchange: &[(&str, &WordInfo)],
+    prompt: &str,
+) -> ResponseResult<MessageId> {
+    let text = format!(
+        "{}\n\n{}",
+        exchange
+            .iter()
+            .map(|(who, word)| format!("{}: {}", who, word.summary()))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        prompt
+    );
+
+    // Definitions open as separate cards so their navigation keyboard doesn't replace the board
+    let keyboard = InlineKeyboardMarkup::new(vec![exchange
+        .iter()
+        .map(|(_, word)| {
+            InlineKeyboardButton::callback(
+                format!("📖 {}", word.word),
+                format!("show_{}", word.word),
+            )
+        })
+        .collect::<Vec<_>>()]);
+
+    if let Some(id) = board {
+        match bot
+            .edit_message_text(chat_id, id, &text)
+            .reply_markup(keyboard.clone())
+            .await
+        {
+            Ok(_) => return Ok(id),
+            Err(e) => warn!(chat_id = chat_id.0, game = GAME; "Failed to edit game board: {}", e),
+        }
+    }
+
+    let msg = bot
+        .send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(msg.id)
+}
+
 /// Skip the current turn
-async fn skip_turn(
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    curr_char: char,
+    (mut chain, curr_char, board, direction, mut progress): (
+        Vec<WordInfo>,
+        char,
+        Option<MessageId>,
+        Direction,
+        Progress,
+    ),
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
     let used_stems = chain
@@ -410,74 +922,244 @@ async fn skip_turn(
         .collect::<Vec<String>>();
 
     // Try to get a word for the bot
-    match get_random_word(|w| !used_stems.contains(&w.to_string()), Some(curr_char)).await {
+    match random_link_word(&used_stems, curr_char, direction).await {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            begin_turn(&mut progress, chain.len());
             chain.push(word.clone());
 
             // Get next character
-            let next_char = word.word.chars().last().unwrap_or('a');
+            let next_char = direction.link_letter(&word.word).unwrap_or('a');
+            let prompt = turn_prompt(chat_id, next_char, direction, None);
 
-            bot.send_message(
-                chat_id,
-                format!("Now give a word starting with '{}'", next_char),
-            )
-            .await?;
+            let board = if is_compact(chat_id) {
+                let exchange = [("Me (you skipped)", &word)];
+                Some(update_board(bot, chat_id, board, &exchange, &prompt).await?)
+            } else {
+                announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
+                bot.send_message(chat_id, prompt).await?;
+                None
+            };
 
+            next_player(bot, chat_id, &mut progress).await?;
             let _ = dialogue
                 .update(WordChain {
                     chain,
                     curr_char: next_char,
+                    hotseat: None,
+                    board,
+                    direction,
+                    progress,
                 })
                 .await;
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, game_id(direction), None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
     Ok(())
 }
 
-/// Show the current score (chain length)
-async fn show_score(bot: &Bot, chat_id: ChatId, chain: &[WordInfo]) -> ResponseResult<()> {
-    let player_words = chain.len() / 2;
-    let bot_words = chain.len() - player_words;
+/// Pass the turn to the other player in hotseat mode, keeping the required letter
+pub async fn pass_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    (chain, curr_char, mut seat, direction, progress): (
+        Vec<WordInfo>,
+        char,
+        Hotseat,
+        Direction,
+        Progress,
+    ),
+) -> ResponseResult<()> {
+    debug!(chat_id = chat_id.0, game = GAME; "Player {} passed", seat.player());
 
+    bot.send_message(chat_id, format!("Player {} passes.", seat.player()))
+        .await?;
+    seat.pass();
     bot.send_message(
         chat_id,
-        format!(
-            "Current chain has {} words total.\nYou: {} words\nBot: {} words",
-            chain.len(),
-            player_words,
-            bot_words
-        ),
+        turn_prompt(chat_id, curr_char, direction, Some(&seat)),
     )
     .await?;
 
+    let _ = dialogue
+        .update(WordChain {
+            chain,
+            curr_char,
+            hotseat: Some(seat),
+            board: None,
+            direction,
+            progress,
+        })
+        .await;
+
     Ok(())
 }
 
-/// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+/// Prompt for the next word, addressed to the current player in hotseat mode
+fn turn_prompt(
+    chat_id: ChatId,
+    curr_char: char,
+    direction: Direction,
+    hotseat: Option<&Hotseat>,
+) -> String {
+    match (hotseat, direction) {
+        (Some(seat), _) => format!(
+            "Player {}, give a word {}",
+            seat.player(),
+            direction.describe(curr_char)
+        ),
+        (None, Direction::Forward) => say(chat_id, Phrase::YourTurn(curr_char)),
+        (None, Direction::Reverse) => say(chat_id, Phrase::YourTurnEnding(curr_char)),
+    }
+}
+
+/// Show both players' scores in hotseat mode
+async fn show_hotseat_score(bot: &Bot, chat_id: ChatId, seat: &Hotseat) -> ResponseResult<()> {
     bot.send_message(
         chat_id,
-        "Word Chain Rules:\n\
-        1. I'll start with a word\n\
-        2. You must respond with a word that starts with the last letter of my word\n\
-        3. We take turns continuing the chain\n\
-        4. No repeating words\n\
-        5. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
+        format!(
+            "Current score:\nPlayer 1: {} words\nPlayer 2: {} words\n\nIt's Player {}'s turn.",
+            seat.scores[0],
+            seat.scores[1],
+            seat.player()
+        ),
     )
     .await?;
 
     Ok(())
 }
+
+/// Show the current score (chain length)
+async fn show_score(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    progress: &Progress,
+) -> ResponseResult<()> {
+    let player_words = chain.len() / 2;
+    let bot_words = chain.len() - player_words;
+
+    let mut score = format!(
+        "Current chain has {} words total.\nYou: {} words\nBot: {} words",
+        chain.len(),
+        player_words,
+        bot_words
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Handle /rewind: take the chain back a few turns so the player can try another path
+async fn rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (mut chain, direction, mut progress): (Vec<WordInfo>, Direction, Progress),
+) -> ResponseResult<()> {
+    let turns = match rewind_chain(&mut chain, &mut progress, args) {
+        Ok(turns) => turns,
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+    let Some(curr_char) = chain.last().and_then(|w| direction.link_letter(&w.word)) else {
+        error!(chat_id = chat_id.0, game = GAME; "Rewound chain has no last word");
+        return Ok(());
+    };
+
+    let prompt = turn_prompt(chat_id, curr_char, direction, None);
+    announce_rewind(bot, chat_id, turns, &chain, &mut progress, &prompt).await?;
+
+    // A fresh board picks up from the rewound word in compact mode
+    let _ = dialogue
+        .update(WordChain {
+            chain,
+            curr_char,
+            hotseat: None,
+            board: None,
+            direction,
+            progress,
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Show game rules
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    curr_char: char,
+    direction: Direction,
+    hotseat: Option<&Hotseat>,
+) -> ResponseResult<()> {
+    let settings = get_settings();
+    let link_rule = match direction {
+        Direction::Forward => "start with the last letter of the previous one",
+        Direction::Reverse => "end with the first letter of the previous one",
+    };
+    let mut rules = vec![
+        "I'll start with a word".to_string(),
+        format!("Each word must {} (next: '{}')", link_rule, curr_char),
+        "No repeating words".to_string(),
+    ];
+
+    match hotseat {
+        Some(seat) => rules.push(format!(
+            "Player 1 and Player 2 take turns instead of playing against me (Player {} is up)",
+            seat.player()
+        )),
+        None => {
+            let style = get_bot_style(chat_id);
+            rules.push(format!(
+                "We take turns; my style is {}: {} (change it with /botstyle)",
+                style.name(),
+                style.description()
+            ));
+            rules.push(format!(
+                "Compact mode is {} (toggle it with /compact)",
+                if is_compact(chat_id) { "on" } else { "off" }
+            ));
+        }
+    }
+    if settings.chain_on_headword {
+        rules.push("Inflected words count as their dictionary headword".to_string());
+    }
+    if !settings.require_definition(GAME) {
+        rules.push("Any known word counts, even without a dictionary definition".to_string());
+    }
+    rules.push(
+        "Use /hint for a hint, /skip to skip your turn, or /stop to end the game".to_string(),
+    );
+    rules.push(
+        "Type words without a slash: \"stop\" is played as a word, /stop ends the game".to_string(),
+    );
+
+    let text = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| format!("{}. {}", i + 1, rule))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    bot.send_message(chat_id, format!("Word Chain Rules:\n{}", text))
+        .await?;
+
+    Ok(())
+}
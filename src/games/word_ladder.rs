@@ -1,22 +1,52 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
 use crate::contains_any;
-use crate::dictionary::{get_random_word, get_word_details, DictionaryError, WordInfo};
-use crate::embeddings::{get_similar_word, EmbeddingError};
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{
+    get_random_word_of_length, get_submission_details, get_word_details, is_playable,
+    DictionaryError, WordInfo,
+};
+use crate::embeddings::{
+    count_words_of_length, get_top_similar_words_of_length, words_of_length, EmbeddingError,
+};
+use crate::games::group::{check_turn, handle_join_command, handle_leave_command, next_player};
+use crate::games::outcome::{
+    begin_turn, bot_concedes, count_player_word, finish_game, game_started, reach_word_target,
+    score_extras, skips_extra, GameOutcome, Progress, Winner,
+};
+use crate::games::{
+    allow_skip, announce_bot_word, announce_rewind, begin_processing, carry_on_message,
+    chain_letter, credit_player_word, dead_end_message, define_chain_word,
+    has_healthy_continuation, is_just_played, just_played_message, looks_like_command,
+    normalize_word, reject_word, reply_unknown_command, reseed_opener, retry_turn, rewind_chain,
+    show_dead_ends, show_help, still_processing,
+};
+use crate::hints::hint_message;
+use crate::preferences::{
+    get_difficulty, handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command, Difficulty,
+};
+use crate::settings::get_settings;
 use crate::state::MyDialogue;
-use crate::state::State::{Start, WordLengthLadder};
-use log::{error, info, warn};
+use crate::state::State::WordLengthLadder;
+use crate::tone::{say, Phrase};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use teloxide::payloads::SendMessageSetters;
 use teloxide::prelude::{ChatId, Message, Requester, ResponseResult};
-use teloxide::types::Me;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me};
 use teloxide::utils::command::BotCommands;
 use teloxide::Bot;
 
 /// Error type specific to Word Ladder game
 #[derive(Debug)]
-enum WordLadderError {
+pub enum WordLadderError {
     Dictionary(DictionaryError),
     Embedding(EmbeddingError),
     InvalidInput(String),
     NoValidWords(String),
+    /// The vocabulary has no word of this length starting with this letter
+    NoWordsOfLength(char, usize),
 }
 
 impl From<DictionaryError> for WordLadderError {
@@ -31,6 +61,18 @@ impl From<EmbeddingError> for WordLadderError {
     }
 }
 
+impl WordLadderError {
+    /// Whether the bot failed to answer for a reason that may pass, rather than because no
+    /// word fits
+    fn is_transient(&self) -> bool {
+        match self {
+            WordLadderError::Dictionary(e) => e.is_transient(),
+            WordLadderError::Embedding(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for WordLadderError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -38,97 +80,296 @@ impl std::fmt::Display for WordLadderError {
             WordLadderError::Embedding(e) => write!(f, "Embedding error: {}", e),
             WordLadderError::InvalidInput(msg) => write!(f, "{}", msg),
             WordLadderError::NoValidWords(msg) => write!(f, "{}", msg),
+            WordLadderError::NoWordsOfLength(c, len) => {
+                write!(f, "No word of length {} starts with '{}'", len, c)
+            }
+        }
+    }
+}
+
+/// Shortest word length a ladder may start from
+const MIN_START_LEN: u8 = 2;
+/// Ladder lengths offered before a game starts: the longest word of a climb, or the first
+/// word of a descent
+pub const LADDER_LENGTHS: [u8; 3] = [6, 8, 10];
+
+/// Menu id of this game
+pub const GAME: &str = "word_ladder";
+/// Menu id of Word Ladder shrinking from long words to short ones
+pub const DOWN_GAME: &str = "word_ladder_down";
+
+/// Which way word lengths move from one turn to the next
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LadderDirection {
+    /// Start with a short word and add a letter every turn
+    #[default]
+    Up,
+    /// Start with a long word and drop a letter every turn
+    Down,
+}
+
+impl LadderDirection {
+    /// Length the word after one of `len` letters needs
+    fn next_len(self, len: u8) -> u8 {
+        match self {
+            LadderDirection::Up => len + 1,
+            LadderDirection::Down => len.saturating_sub(1),
+        }
+    }
+
+    /// Whether a ladder whose next word needs `curr_len` letters has reached its end at `end_len`
+    fn reached(self, curr_len: u8, end_len: u8) -> bool {
+        match self {
+            LadderDirection::Up => curr_len >= end_len,
+            LadderDirection::Down => curr_len <= end_len,
+        }
+    }
+
+    /// The word the bot is after when it answers, e.g. for when it can't find one
+    fn next_word(self) -> &'static str {
+        match self {
+            LadderDirection::Up => "a longer word",
+            LadderDirection::Down => "a shorter word",
+        }
+    }
+
+    /// Menu id of a ladder going this way
+    pub fn game_id(self) -> &'static str {
+        match self {
+            LadderDirection::Up => GAME,
+            LadderDirection::Down => DOWN_GAME,
+        }
+    }
+
+    /// Start of the callback data choosing the length of a ladder going this way
+    pub fn length_prefix(self) -> &'static str {
+        match self {
+            LadderDirection::Up => "ladder_up_",
+            LadderDirection::Down => "ladder_down_",
         }
     }
 }
 
-/// Start a new Word Ladder game
+/// Reply to /maxlen when no Word Ladder game is running
+pub const MAX_LEN_OUTSIDE_LADDER: &str =
+    "/maxlen changes how long a Word Ladder has to climb, so it only works during a Word Ladder game.";
+
+/// Ask how long the ladder should be before starting a Word Ladder going `direction`
+pub async fn ask_ladder_length(
+    bot: &Bot,
+    chat_id: ChatId,
+    direction: LadderDirection,
+) -> ResponseResult<()> {
+    let keyboard = InlineKeyboardMarkup::new(vec![LADDER_LENGTHS
+        .iter()
+        .map(|len| {
+            InlineKeyboardButton::callback(
+                format!("{} letters", len),
+                format!("{}{}", direction.length_prefix(), len),
+            )
+        })
+        .collect::<Vec<_>>()]);
+    let question = match direction {
+        LadderDirection::Up => {
+            "Word Length Ladder! How long should the last word be? Longer ladders take more turns."
+        }
+        LadderDirection::Down => {
+            "Descending Word Ladder! How long should the first word be? Longer ladders take more turns."
+        }
+    };
+    bot.send_message(chat_id, question)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Start a new Word Ladder game, climbing from short words up to `max_len` letters or, going
+/// down, from `max_len` letters to short ones
 pub async fn start_word_ladder(
     chat_id: ChatId,
     bot: Bot,
     dialogue: MyDialogue,
+    direction: LadderDirection,
+    max_len: u8,
 ) -> ResponseResult<()> {
-    info!("Starting Word Ladder game for chat {}", chat_id);
+    info!(
+        chat_id = chat_id.0, game = GAME;
+        "Starting Word Ladder game ({:?}, {} letters)",
+        direction, max_len
+    );
 
-    bot.send_message(chat_id, "Word Length Ladder! Climb up the word sizes.")
+    let Some(short_len) = feasible_start_len(max_len) else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "I don't know enough words of every length up to {} letters for this ladder. Please pick a shorter one from /play.",
+                max_len
+            ),
+        )
         .await?;
+        return Ok(());
+    };
+
+    // A descending ladder runs the same rungs from the top, ending where a climb would start
+    let (start_len, end_len) = match direction {
+        LadderDirection::Up => (short_len, max_len),
+        LadderDirection::Down => (max_len, short_len),
+    };
+    let intro = match direction {
+        LadderDirection::Up => "Word Length Ladder! Climb up the word sizes.".to_string(),
+        LadderDirection::Down => format!(
+            "Descending Word Ladder! Shrink the word sizes from {} letters down to {}.",
+            start_len, end_len
+        ),
+    };
+    bot.send_message(chat_id, intro).await?;
 
     // Try to get a random word to start the game
+    let mut lookup_failed = false;
     for _ in 0..3 {
         // Try up to 3 times
-        match get_random_word(|w| w.len() == 2, None).await {
+        match get_random_word_of_length(start_len as usize, None, |_| true).await {
             Ok(word) => {
-                let curr_char = match word.word.chars().last() {
+                let curr_char = match chain_letter(&word.word) {
                     Some(c) => c,
                     None => {
-                        error!("Selected word '{}' has no characters", word.word);
+                        error!(
+                            chat_id = chat_id.0, game = GAME;
+                            "Selected word '{}' has no characters",
+                            word.word
+                        );
                         bot.send_message(chat_id, "Error starting game, please try again.")
                             .await?;
                         return Ok(());
                     }
                 };
 
-                info!("Word Ladder started with word: {} (length 2)", word.word);
+                debug!(
+                    chat_id = chat_id.0, game = GAME;
+                    "Word Ladder started with word: {} (length {})",
+                    word.word, start_len
+                );
 
-                bot.send_message(chat_id, format!("First word: {}", word.word))
-                    .await?;
-                word.send_message(&bot, chat_id, 0).await?;
+                announce_bot_word(&bot, chat_id, Phrase::FirstWord(&word.word), &word).await?;
 
                 bot.send_message(
                     chat_id,
-                    format!("Now give a word starting with '{}' of length 2", curr_char),
+                    format!(
+                        "Now give a word starting with '{}' of length {}",
+                        curr_char, start_len
+                    ),
                 )
                 .await?;
 
+                game_started(&bot, chat_id).await?;
                 let _ = dialogue
                     .update(WordLengthLadder {
                         chain: vec![word],
-                        curr_len: 2,
-                        max_len: 8,
+                        curr_len: start_len,
+                        max_len: end_len,
                         curr_char,
+                        direction,
+                        progress: Progress::default(),
                     })
                     .await;
 
                 return Ok(());
             }
             Err(e) => {
-                error!("Failed to get random word: {:?}", e);
+                error!(chat_id = chat_id.0, game = GAME; "Failed to get random word: {:?}", e);
+                lookup_failed |= e.is_transient();
                 // Try again
             }
         }
     }
 
     // Failed after multiple attempts
-    bot.send_message(
-        chat_id,
-        "Sorry, I'm having trouble starting the game. Please try again later.",
-    )
-    .await?;
+    let reply = if lookup_failed {
+        "Sorry, I'm having trouble starting the game. Please try again later.".to_string()
+    } else {
+        format!(
+            "I couldn't find a playable {}-letter word to open the ladder. Please try a different length from /play.",
+            start_len
+        )
+    };
+    bot.send_message(chat_id, reply).await?;
 
     Ok(())
 }
 
+/// Pick the shortest start length from which every rung up to `max_len` has enough words.
+///
+/// A rung with fewer words than the configured minimum pool would dead-end the ladder, so the
+/// start moves past it; `None` if not even `max_len` itself has enough words.
+fn feasible_start_len(max_len: u8) -> Option<u8> {
+    let min_pool = get_settings().min_word_pool;
+    let mut start = MIN_START_LEN;
+
+    while start < max_len {
+        let short_rung = (start..=max_len)
+            .find(|&len| count_words_of_length(len as usize).unwrap_or(0) < min_pool);
+
+        match short_rung {
+            None => return Some(start),
+            Some(len) => {
+                debug!(
+                    game = GAME;
+                    "Fewer than {} words of length {}, retrying ladder setup from length {}",
+                    min_pool, len, len + 1
+                );
+                start = len + 1;
+            }
+        }
+    }
+
+    warn!(game = GAME; "No ladder start length up to {} has enough words", max_len);
+    None
+}
+
 /// Handle player input during Word Ladder game
 pub async fn word_ladder(
     bot: Bot,
     dialogue: MyDialogue,
-    (curr_len, max_len, chain, curr_char): (u8, u8, Vec<WordInfo>, char),
+    (curr_len, max_len, chain, curr_char, direction, mut progress): (
+        u8,
+        u8,
+        Vec<WordInfo>,
+        char,
+        LadderDirection,
+        Progress,
+    ),
     msg: Message,
     me: Me,
 ) -> ResponseResult<()> {
+    let Some(_processing) = begin_processing(msg.chat.id) else {
+        return still_processing(&bot, msg.chat.id).await;
+    };
+
     match msg.text() {
         Some(text) => match BotCommands::parse(text, me.username()) {
-            Ok(Command::Start) | Ok(Command::Play) | Ok(Command::Stats) => {
+            Ok(Command::Start)
+            | Ok(Command::Play)
+            | Ok(Command::Stats)
+            | Ok(Command::Leaderboard(_))
+            | Ok(Command::Similar(_))
+            | Ok(Command::LoadModel(_))
+            | Ok(Command::Model(_))
+            | Ok(Command::Preview(_))
+            | Ok(Command::Define(_)) => {
                 bot.send_message(
                     msg.chat.id,
                     "Please stop this game first with /stop to use this command.",
                 )
                 .await?;
             }
-            Ok(Command::Hint) => {
+            Ok(Command::Hint(_)) => {
                 provide_hint(&bot, msg.chat.id, curr_char, curr_len).await?;
             }
             Ok(Command::Skip) => {
+                if !allow_skip(&bot, &msg, &mut progress).await? {
+                    return Ok(());
+                }
                 skip_turn(
                     &bot,
                     msg.chat.id,
@@ -137,22 +378,86 @@ pub async fn word_ladder(
                     curr_len,
                     max_len,
                     curr_char,
+                    direction,
+                    progress,
                 )
                 .await?;
             }
             Ok(Command::Score) => {
-                show_score(&bot, msg.chat.id, &chain, curr_len).await?;
+                show_score(&bot, msg.chat.id, &chain, curr_len, direction, &progress).await?;
+            }
+            Ok(Command::Badges) => {
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Help) => {
+                show_help(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                handle_join_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::Leave) => {
+                handle_leave_command(&bot, &msg, &dialogue).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Rewind(args)) => {
+                rewind(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    &args,
+                    (chain, max_len, direction, progress),
+                )
+                .await?;
+            }
+            Ok(Command::Def(args)) => {
+                define_chain_word(&bot, msg.chat.id, &chain, &args).await?;
+            }
+            Ok(Command::Compact) => {
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Rules) => {
-                show_rules(&bot, msg.chat.id).await?;
+                show_rules(&bot, msg.chat.id, &chain, curr_len, max_len, direction).await?;
+            }
+            Ok(Command::MaxLen(args)) => {
+                change_max_len(
+                    &bot,
+                    msg.chat.id,
+                    dialogue,
+                    &args,
+                    (curr_len, max_len, chain, curr_char, direction, progress),
+                )
+                .await?;
             }
             Ok(Command::Stop) => {
-                info!("Player stopped Word Ladder game in chat {}", msg.chat.id);
+                info!(chat_id = msg.chat.id.0, game = GAME; "Player stopped Word Ladder game");
 
                 // Show final score/summary
                 let player_words = chain.len() / 2;
                 let bot_words = chain.len() - player_words;
-                let max_length_reached = if chain.is_empty() {
+                let length_reached = if chain.is_empty() {
                     0
                 } else {
                     chain.last().unwrap().word.len()
@@ -161,30 +466,41 @@ pub async fn word_ladder(
                 bot.send_message(
                     msg.chat.id,
                     format!(
-                        "Game finished! Final score:\nYou: {} words\nBot: {} words\n\nMax word length reached: {}\n\nWords played: {}",
+                        "Game finished! Final score:\nYou: {} words\nBot: {} words\n\n{} word length reached: {}\n\nWords played: {}",
                         player_words,
                         bot_words,
-                        max_length_reached,
+                        match direction {
+                            LadderDirection::Up => "Max",
+                            LadderDirection::Down => "Min",
+                        },
+                        length_reached,
                         chain.iter().map(|w| w.word.clone()).collect::<Vec<String>>().join(", ")
                     ),
                 ).await?;
 
-                bot.send_message(msg.chat.id, "Word Ladder game stopped. Thanks for playing!")
-                    .await?;
-                let _ = dialogue.update(Start).await;
-            }
-            Err(_) => {
-                process_player_word(
-                    text,
-                    bot,
-                    dialogue,
-                    chain,
-                    curr_len,
-                    max_len,
-                    curr_char,
+                bot.send_message(
                     msg.chat.id,
+                    say(msg.chat.id, Phrase::GameStopped("Word Ladder")),
                 )
-                .await?
+                .await?;
+                let outcome = GameOutcome::new(&progress, direction.game_id(), None, &chain);
+                finish_game(&bot, msg.chat.id, dialogue, outcome).await?;
+            }
+            Err(_) if looks_like_command(text) => {
+                reply_unknown_command(&bot, msg.chat.id, text).await?;
+            }
+            Err(_) => {
+                if check_turn(&bot, &msg, &progress).await? {
+                    process_player_word(
+                        text,
+                        bot,
+                        dialogue,
+                        (chain, progress),
+                        (curr_len, max_len, curr_char, direction),
+                        msg.chat.id,
+                    )
+                    .await?
+                }
             }
         },
         None => {
@@ -194,17 +510,128 @@ pub async fn word_ladder(
     Ok(())
 }
 
+/// Handle /maxlen: show the ladder's target length, or move it to the length given.
+///
+/// The target can't drop below the length the player's next word needs, and can only rise as
+/// far as every rung on the way has enough words.
+async fn change_max_len(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (curr_len, max_len, chain, curr_char, direction, progress): (
+        u8,
+        u8,
+        Vec<WordInfo>,
+        char,
+        LadderDirection,
+        Progress,
+    ),
+) -> ResponseResult<()> {
+    if direction == LadderDirection::Down {
+        bot.send_message(
+            chat_id,
+            format!(
+                "/maxlen moves the top of a climbing ladder. This one shrinks down to {} letters, and your next word needs {}.",
+                max_len, curr_len
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let longest = longest_feasible_len(curr_len);
+
+    let args = args.trim();
+    if args.is_empty() {
+        bot.send_message(
+            chat_id,
+            format!(
+                "This ladder ends at {} letters and your next word needs {}.\n\n\
+                Change the target with /maxlen <letters> (from {} to {}).",
+                max_len, curr_len, curr_len, longest
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Ok(new_len) = args.parse::<u8>() else {
+        bot.send_message(chat_id, "Usage: /maxlen <letters>, e.g. /maxlen 10")
+            .await?;
+        return Ok(());
+    };
+
+    let reply = if new_len < curr_len {
+        format!(
+            "Your next word already needs {} letters, so the ladder can't end below that.",
+            curr_len
+        )
+    } else if new_len > longest {
+        format!(
+            "I don't know enough {}-letter words to climb that far. The highest target I can \
+            offer is {} letters.",
+            longest + 1,
+            longest
+        )
+    } else if new_len == max_len {
+        format!("The ladder already ends at {} letters.", max_len)
+    } else {
+        info!(
+            chat_id = chat_id.0, game = GAME;
+            "Word Ladder target changed from {} to {} letters",
+            max_len, new_len
+        );
+        let _ = dialogue
+            .update(WordLengthLadder {
+                chain,
+                curr_len,
+                max_len: new_len,
+                curr_char,
+                direction,
+                progress,
+            })
+            .await;
+        format!(
+            "Target {}: the ladder now ends at {} letters instead of {}. Play a {}-letter word \
+            to win.",
+            if new_len > max_len {
+                "raised"
+            } else {
+                "lowered"
+            },
+            new_len,
+            max_len,
+            new_len
+        )
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// The longest target a ladder at `curr_len` can climb to before reaching a rung with fewer
+/// words than the configured minimum pool
+fn longest_feasible_len(curr_len: u8) -> u8 {
+    let min_pool = get_settings().min_word_pool;
+    (curr_len..=u8::MAX)
+        .find(|&len| count_words_of_length(len as usize).unwrap_or(0) < min_pool)
+        .map_or(u8::MAX, |short_rung| short_rung.saturating_sub(1))
+        .max(curr_len)
+}
+
 /// Process a player's word submission
 async fn process_player_word(
     text: &str,
     bot: Bot,
     dialogue: MyDialogue,
-    mut chain: Vec<WordInfo>,
-    curr_len: u8,
-    max_len: u8,
-    curr_char: char,
+    (mut chain, mut progress): (Vec<WordInfo>, Progress),
+    (curr_len, max_len, curr_char, direction): (u8, u8, char, LadderDirection),
     chat_id: ChatId,
 ) -> ResponseResult<()> {
+    let game = direction.game_id();
+    let next_len = direction.next_len(curr_len);
+
     let words = text.split_whitespace().collect::<Vec<&str>>();
 
     // Check for valid input (single word)
@@ -219,7 +646,14 @@ async fn process_player_word(
         return Ok(());
     }
 
-    let word = words[0].to_lowercase();
+    let word = normalize_word(words[0]);
+
+    // A resend of the word just accepted gets a clearer reply than the used-word check
+    if is_just_played(&chain, &word, 2) {
+        bot.send_message(chat_id, just_played_message(&word))
+            .await?;
+        return Ok(());
+    }
 
     // Check if word starts with the current character and has correct length
     if !word.starts_with(curr_char) || word.len() != curr_len as usize {
@@ -241,100 +675,193 @@ async fn process_player_word(
         .collect::<Vec<String>>();
 
     // Validate the player's word
-    match get_word_details(&word).await {
+    match get_submission_details(&word, GAME).await {
         Ok(word_details) => {
             // Check if word has already been used
             if contains_any(&used_stems, &word_details.stems) {
-                bot.send_message(
-                    chat_id,
-                    "That word (or a form of it) has already been used.",
-                )
-                .await?;
+                bot.send_message(chat_id, say(chat_id, Phrase::AlreadyUsed))
+                    .await?;
                 return Ok(());
             }
 
             // Add the player's word to the chain
-            info!("Player used word: {} in chat {}", word, chat_id);
+            debug!(chat_id = chat_id.0, game = GAME; "Player used word: {}", word);
             let mut updated_stems = used_stems.clone();
             updated_stems.push(word.clone());
 
-            word_details.send_message(&bot, chat_id, 0).await?;
+            if !word_details.defs.is_empty() {
+                word_details.send_message(&bot, chat_id, 0).await?;
+            }
+            begin_turn(&mut progress, chain.len());
             chain.push(word_details.clone());
 
-            // Check if we've reached the maximum word length
-            if curr_len >= max_len {
+            // Reaching the word target wins outright
+            if count_player_word(chat_id, &mut progress, &word_details.word) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+                let outcome = GameOutcome::new(&progress, game, Some(Winner::Player), &chain);
+                return reach_word_target(&bot, chat_id, dialogue, outcome).await;
+            }
+
+            // Check if we've reached the end of the ladder
+            if direction.reached(curr_len, max_len) {
+                credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
                 bot.send_message(
                     chat_id,
                     format!(
-                        "Congratulations! You've reached the maximum length of {} letters!",
+                        "Congratulations! You've reached the {} length of {} letters!",
+                        match direction {
+                            LadderDirection::Up => "maximum",
+                            LadderDirection::Down => "minimum",
+                        },
                         max_len
                     ),
                 )
                 .await?;
-                let _ = dialogue.update(Start).await;
+                let outcome = GameOutcome::new(&progress, game, Some(Winner::Player), &chain);
+                finish_game(&bot, chat_id, dialogue, outcome).await?;
                 return Ok(());
             }
 
-            // Get the bot's response word (one letter longer)
-            match get_bot_response(&word, &updated_stems, curr_len as usize + 1).await {
+            // Get the bot's response word (one letter longer, or shorter going down)
+            let response = get_bot_response(
+                &word_details.word,
+                &updated_stems,
+                next_len as usize,
+                get_difficulty(chat_id),
+            )
+            .await;
+            // A reply that couldn't be looked up takes the word back, so it earns nothing yet
+            if let Err(e) = &response {
+                if e.is_transient() {
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    return retry_turn(&bot, chat_id).await;
+                }
+            }
+            credit_player_word(&bot, chat_id, &word_details.word, chain.len()).await?;
+            match response {
                 Ok(next_word_details) => {
-                    let next_char = match next_word_details.word.chars().last() {
+                    let next_char = match chain_letter(&next_word_details.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot's word '{}' has no characters", next_word_details.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot's word '{}' has no characters",
+                                next_word_details.word
+                            );
                             bot.send_message(chat_id, "Error in game, please try again.")
                                 .await?;
-                            let _ = dialogue.update(Start).await;
+                            let outcome = GameOutcome::new(&progress, game, None, &chain);
+                            finish_game(&bot, chat_id, dialogue, outcome).await?;
                             return Ok(());
                         }
                     };
 
                     chain.push(next_word_details.clone());
-                    bot.send_message(chat_id, format!("My word: {}", next_word_details.word))
-                        .await?;
-                    next_word_details.send_message(&bot, chat_id, 0).await?;
+                    announce_bot_word(
+                        &bot,
+                        chat_id,
+                        Phrase::BotWord(&next_word_details.word),
+                        &next_word_details,
+                    )
+                    .await?;
 
                     // Prompt for the next word
                     bot.send_message(
                         chat_id,
                         format!(
                             "Now give a word starting with '{}' of length {}",
-                            next_char,
-                            curr_len as usize + 1
+                            next_char, next_len
                         ),
                     )
                     .await?;
+                    next_player(&bot, chat_id, &mut progress).await?;
 
                     // Update game state
                     let _ = dialogue
                         .update(WordLengthLadder {
                             chain,
-                            curr_len: curr_len + 1,
+                            curr_len: next_len,
                             max_len,
                             curr_char: next_char,
+                            direction,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get bot response: {:?}", e);
-                    bot.send_message(
-                        chat_id,
-                        "I can't think of a longer word! You win this round!",
-                    )
-                    .await?;
-                    let _ = dialogue.update(Start).await;
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get bot response: {:?}", e);
+                    // Conceding to the very first reply would end the game before any real play
+                    if let Some(opener) = reseed_opener(&bot, chat_id, &chain, &mut progress, |w| {
+                        w.len() == curr_len as usize && has_healthy_continuation(w)
+                    })
+                    .await?
+                    {
+                        let curr_char = chain_letter(&opener.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            format!(
+                                "Now give a word starting with '{}' of length {}",
+                                curr_char, curr_len
+                            ),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(WordLengthLadder {
+                                chain: vec![opener],
+                                curr_len,
+                                max_len,
+                                curr_char,
+                                direction,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = chain_letter(&word_details.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a word starting with '{}' of length {}",
+                                next_char, next_len
+                            )),
+                        )
+                        .await?;
+                        next_player(&bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(WordLengthLadder {
+                                chain,
+                                curr_len: next_len,
+                                max_len,
+                                curr_char: next_char,
+                                direction,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
+                    let reply = match &e {
+                        WordLadderError::Embedding(EmbeddingError::NoWordsForLetter(c)) => {
+                            dead_end_message(*c)
+                        }
+                        WordLadderError::NoWordsOfLength(c, len) => format!(
+                            "No {}-letter word in my vocabulary starts with '{}', so the ladder can't go on. You win this round!",
+                            len, c
+                        ),
+                        _ => say(chat_id, Phrase::BotStumped(direction.next_word())),
+                    };
+                    bot.send_message(chat_id, reply).await?;
+                    let outcome = GameOutcome::new(&progress, game, Some(Winner::Player), &chain);
+                    finish_game(&bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            warn!(
-                "Invalid word attempt '{}' in chat {}: {:?}",
-                word, chat_id, e
-            );
-            bot.send_message(
-                chat_id,
-                format!("I don't recognize '{}'. Please try another word.", word),
-            )
+            debug!(chat_id = chat_id.0, game = GAME; "Invalid word attempt '{}': {:?}", word, e);
+            reject_word(&bot, chat_id, &word, &e, &used_stems, |w| {
+                w.starts_with(curr_char) && w.len() == curr_len as usize
+            })
             .await?;
         }
     }
@@ -343,14 +870,15 @@ async fn process_player_word(
 }
 
 /// Get the bot's response word with specific length
-async fn get_bot_response(
+pub async fn get_bot_response(
     player_word: &str,
     used_words: &[String],
     target_length: usize,
+    difficulty: Difficulty,
 ) -> Result<WordInfo, WordLadderError> {
     let mut used_words = used_words.to_vec();
 
-    let last_char = match player_word.chars().last() {
+    let last_char = match chain_letter(player_word) {
         Some(c) => c,
         None => {
             return Err(WordLadderError::InvalidInput(
@@ -359,17 +887,28 @@ async fn get_bot_response(
         }
     };
 
+    // Don't search a rung the vocabulary has nothing on
+    if words_of_length(last_char, target_length)?.is_empty() {
+        return Err(WordLadderError::NoWordsOfLength(last_char, target_length));
+    }
+
     // Get a similar word that hasn't been used
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 5;
+    let mut lookup_error = None;
 
     while attempts < MAX_ATTEMPTS {
         attempts += 1;
 
         // Try to find a similar word
-        let next_word_result = get_similar_word(player_word, last_char, |x| {
-            !used_words.contains(&x.to_string()) && x.len() == target_length
-        });
+        let next_word_result = get_top_similar_words_of_length(
+            player_word,
+            last_char,
+            target_length,
+            difficulty.choices(),
+            |x| is_playable(x) && !used_words.contains(&x.to_string()),
+        )
+        .and_then(|words| difficulty.choose(words));
 
         match next_word_result {
             Ok(word) => {
@@ -382,10 +921,14 @@ async fn get_bot_response(
                         }
                         return Ok(details);
                     }
-                    Err(_) => {
+                    Err(e) => {
+                        // Running out of words because lookups failed isn't a real dead end
+                        if e.is_transient() {
+                            lookup_error = Some(e);
+                        }
                         used_words.push(word);
-                        continue
-                    }, // Try another word
+                        continue;
+                    } // Try another word
                 }
             }
             Err(e) => {
@@ -397,6 +940,9 @@ async fn get_bot_response(
         }
     }
 
+    if let Some(e) = lookup_error {
+        return Err(WordLadderError::Dictionary(e));
+    }
     Err(WordLadderError::NoValidWords(format!(
         "Could not find a valid word of length {}",
         target_length
@@ -410,19 +956,13 @@ async fn provide_hint(
     curr_char: char,
     curr_len: u8,
 ) -> ResponseResult<()> {
-    info!("Providing hint for chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Providing hint");
 
     // Get a random word starting with the current character and with correct length
-    match get_random_word(|w| w.len() == curr_len as usize, Some(curr_char)).await {
+    match get_random_word_of_length(curr_len as usize, Some(curr_char), |_| true).await {
         Ok(hint) => {
-            bot.send_message(
-                chat_id,
-                format!(
-                    "Hint: You could try a word like '{}' or something similar.",
-                    hint.word
-                ),
-            )
-            .await?;
+            bot.send_message(chat_id, hint_message(chat_id, &hint.word))
+                .await?;
         }
         Err(_) => {
             bot.send_message(
@@ -437,7 +977,8 @@ async fn provide_hint(
 }
 
 /// Skip the current turn
-async fn skip_turn(
+#[allow(clippy::too_many_arguments)]
+pub async fn skip_turn(
     bot: &Bot,
     chat_id: ChatId,
     dialogue: MyDialogue,
@@ -445,10 +986,15 @@ async fn skip_turn(
     curr_len: u8,
     max_len: u8,
     curr_char: char,
+    direction: LadderDirection,
+    mut progress: Progress,
 ) -> ResponseResult<()> {
-    info!("Player skipped turn in chat {}", chat_id);
+    debug!(chat_id = chat_id.0, game = GAME; "Player skipped turn");
+    let game = direction.game_id();
+    let next_len = direction.next_len(curr_len);
 
-    bot.send_message(chat_id, "Skipping your turn...").await?;
+    bot.send_message(chat_id, say(chat_id, Phrase::Skipping))
+        .await?;
 
     // Get list of used words
     let used_stems = chain
@@ -457,23 +1003,34 @@ async fn skip_turn(
         .collect::<Vec<String>>();
 
     // Try to get a word for the bot
-    match get_random_word(|w| w.len() == curr_len as usize, Some(curr_char)).await {
+    match get_random_word_of_length(curr_len as usize, Some(curr_char), |_| true).await {
         Ok(word) => {
-            bot.send_message(chat_id, format!("My word: {}", word.word))
-                .await?;
-            word.send_message(bot, chat_id, 0).await?;
+            announce_bot_word(bot, chat_id, Phrase::BotWord(&word.word), &word).await?;
+            begin_turn(&mut progress, chain.len());
             chain.push(word.clone());
 
-            // Get next word (one letter longer)
-            match get_bot_response(&word.word, &used_stems, curr_len as usize + 1).await {
+            // Get next word (one letter longer, or shorter going down)
+            match get_bot_response(
+                &word.word,
+                &used_stems,
+                next_len as usize,
+                get_difficulty(chat_id),
+            )
+            .await
+            {
                 Ok(next_word) => {
-                    let next_char = match next_word.word.chars().last() {
+                    let next_char = match chain_letter(&next_word.word) {
                         Some(c) => c,
                         None => {
-                            error!("Bot's word '{}' has no characters", next_word.word);
+                            error!(
+                                chat_id = chat_id.0, game = GAME;
+                                "Bot's word '{}' has no characters",
+                                next_word.word
+                            );
                             bot.send_message(chat_id, "Error in game, please try again.")
                                 .await?;
-                            let _ = dialogue.update(Start).await;
+                            let outcome = GameOutcome::new(&progress, game, None, &chain);
+                            finish_game(bot, chat_id, dialogue, outcome).await?;
                             return Ok(());
                         }
                     };
@@ -490,40 +1047,64 @@ async fn skip_turn(
                         chat_id,
                         format!(
                             "Now your turn. Give a word starting with '{}' of length {}",
-                            next_char,
-                            curr_len as usize + 1
+                            next_char, next_len
                         ),
                     )
                     .await?;
 
+                    next_player(bot, chat_id, &mut progress).await?;
                     let _ = dialogue
                         .update(WordLengthLadder {
                             chain,
-                            curr_len: curr_len + 1,
+                            curr_len: next_len,
                             max_len,
                             curr_char: next_char,
+                            direction,
+                            progress,
                         })
                         .await;
                 }
                 Err(e) => {
-                    error!("Failed to get next word: {:?}", e);
+                    error!(chat_id = chat_id.0, game = GAME; "Failed to get next word: {:?}", e);
+                    if bot_concedes(chat_id, &mut progress) {
+                        let next_char = chain_letter(&word.word).unwrap_or(curr_char);
+                        bot.send_message(
+                            chat_id,
+                            carry_on_message(&format!(
+                                "give a word starting with '{}' of length {}",
+                                next_char, next_len
+                            )),
+                        )
+                        .await?;
+                        next_player(bot, chat_id, &mut progress).await?;
+                        let _ = dialogue
+                            .update(WordLengthLadder {
+                                chain,
+                                curr_len: next_len,
+                                max_len,
+                                curr_char: next_char,
+                                direction,
+                                progress,
+                            })
+                            .await;
+                        return Ok(());
+                    }
                     bot.send_message(
                         chat_id,
-                        "I can't think of a longer word! You win this round!",
+                        say(chat_id, Phrase::BotStumped(direction.next_word())),
                     )
                     .await?;
-                    let _ = dialogue.update(Start).await;
+                    let outcome = GameOutcome::new(&progress, game, Some(Winner::Player), &chain);
+                    finish_game(bot, chat_id, dialogue, outcome).await?;
                 }
             }
         }
         Err(e) => {
-            error!("Failed to get random word for skip: {:?}", e);
-            bot.send_message(
-                chat_id,
-                "I can't think of a word either! Let's end this game.",
-            )
-            .await?;
-            let _ = dialogue.update(Start).await;
+            error!(chat_id = chat_id.0, game = GAME; "Failed to get random word for skip: {:?}", e);
+            bot.send_message(chat_id, say(chat_id, Phrase::SkipStumped))
+                .await?;
+            let outcome = GameOutcome::new(&progress, game, None, &chain);
+            finish_game(bot, chat_id, dialogue, outcome).await?;
         }
     }
 
@@ -536,28 +1117,108 @@ async fn show_score(
     chat_id: ChatId,
     chain: &[WordInfo],
     curr_len: u8,
+    direction: LadderDirection,
+    progress: &Progress,
 ) -> ResponseResult<()> {
-    bot.send_message(
-        chat_id,
-        format!("Current Progress:\nYou've reached word length: {}\nWords in ladder: {}\nKeep climbing!", 
-            curr_len, chain.len()),
-    ).await?;
+    let mut score = format!(
+        "Current Progress:\nYou've reached word length: {}\nWords in ladder: {}\n{}",
+        curr_len,
+        chain.len(),
+        match direction {
+            LadderDirection::Up => "Keep climbing!",
+            LadderDirection::Down => "Keep shrinking!",
+        }
+    );
+    for extra in score_extras(progress, chain.iter().map(|w| w.word.as_str())) {
+        score.push_str(&format!("\n{}", extra));
+    }
+    if let Some(skips) = skips_extra(progress) {
+        score.push_str(&format!("\n{}", skips));
+    }
+    bot.send_message(chat_id, score).await?;
+
+    Ok(())
+}
+
+/// Handle /rewind: take the chain back a few turns so the player can try another path. The
+/// player's next word matches the length of the word the ladder is back at.
+async fn rewind(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: MyDialogue,
+    args: &str,
+    (mut chain, max_len, direction, mut progress): (Vec<WordInfo>, u8, LadderDirection, Progress),
+) -> ResponseResult<()> {
+    let turns = match rewind_chain(&mut chain, &mut progress, args) {
+        Ok(turns) => turns,
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+    let Some((curr_len, curr_char)) = chain
+        .last()
+        .and_then(|w| Some((w.word.len() as u8, chain_letter(&w.word)?)))
+    else {
+        error!(chat_id = chat_id.0, game = GAME; "Rewound chain has no last word");
+        return Ok(());
+    };
+
+    let prompt = format!(
+        "Now give a word starting with '{}' of length {}",
+        curr_char, curr_len
+    );
+    announce_rewind(bot, chat_id, turns, &chain, &mut progress, &prompt).await?;
+
+    let _ = dialogue
+        .update(WordLengthLadder {
+            chain,
+            curr_len,
+            max_len,
+            curr_char,
+            direction,
+            progress,
+        })
+        .await;
 
     Ok(())
 }
 
 /// Show game rules
-async fn show_rules(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
-    bot.send_message(
-        chat_id,
-        "Word Ladder Rules:\n\
-        1. We start with a short word (2 letters)\n\
-        2. Each new word must start with the last letter of the previous word\n\
-        3. Word length increases by 1 with each turn\n\
-        4. The goal is to reach a word of length 8\n\
-        5. Use /hint for a hint, /skip to skip your turn, or /stop to end the game",
-    )
-    .await?;
+async fn show_rules(
+    bot: &Bot,
+    chat_id: ChatId,
+    chain: &[WordInfo],
+    curr_len: u8,
+    max_len: u8,
+    direction: LadderDirection,
+) -> ResponseResult<()> {
+    let start_len = chain.first().map_or(curr_len as usize, |w| w.word.len());
+
+    let rules = match direction {
+        LadderDirection::Up => format!(
+            "Word Ladder Rules:\n\
+            1. We started with a short word ({} letters)\n\
+            2. Each new word must start with the last letter of the previous word\n\
+            3. Word length increases by 1 with each turn (the next word needs {} letters)\n\
+            4. The goal is to reach a word of length {}\n\
+            5. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            6. Use /maxlen <letters> to move the goal up or down mid-game\n\
+            7. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            start_len, curr_len, max_len
+        ),
+        LadderDirection::Down => format!(
+            "Descending Word Ladder Rules:\n\
+            1. We started with a long word ({} letters)\n\
+            2. Each new word must start with the last letter of the previous word\n\
+            3. Word length decreases by 1 with each turn (the next word needs {} letters)\n\
+            4. The goal is to get down to a word of length {}\n\
+            5. Use /hint for a hint, /skip to skip your turn, or /stop to end the game\n\
+            6. Type words without a slash: \"stop\" is played as a word, /stop ends the game",
+            start_len, curr_len, max_len
+        ),
+    };
+    bot.send_message(chat_id, rules).await?;
 
     Ok(())
 }
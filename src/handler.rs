@@ -1,29 +1,93 @@
+use crate::achievements::show_badges;
 use crate::command::Command;
-use crate::dictionary::get_word_details;
+use crate::daily::{handle_subscribe_command, handle_unsubscribe_command};
+use crate::dictionary::{get_word_details, live_lookups_available, DictionaryError, WordInfo};
+use crate::embeddings::{
+    dimension_mismatches, is_valid_word, load_model, model_names, most_similar, select_model,
+    selected_model,
+};
 use crate::games::alphabet_sprint::start_alphabet_sprint;
-use crate::games::forbidden_letters::start_forbidden_letters;
+use crate::games::anagram_shuffle::start_anagram_shuffle;
+use crate::games::boggle::start_boggle;
+use crate::games::categories::start_categories;
+use crate::games::compound_chain::start_compound_chain;
+use crate::games::forbidden_letters::{
+    self, ask_letter_count, start_forbidden_letters, MAX_FORBIDDEN,
+};
+use crate::games::ghost::start_ghost;
+use crate::games::group::no_game_to_join;
+use crate::games::guess_word::start_guess_word;
+use crate::games::outcome::abandon_game;
+use crate::games::preview::handle_preview_command;
+use crate::games::relay::start_relay;
+use crate::games::rhyme_time::start_rhyme_time;
 use crate::games::scrambled::start_last_letter_scramble;
-use crate::games::synonym_string::start_synonym_string;
+use crate::games::synonym_string::{
+    self, ask_similarity_level, start_synonym_string, SIMILARITY_LEVELS,
+};
+use crate::games::vocab_check::{record_answer, start_vocab_check};
 use crate::games::word_chain::start_word_chain;
-use crate::games::word_ladder::start_word_ladder;
-use crate::state::MyDialogue;
-use log::{error, info, warn};
+use crate::games::word_ladder::MAX_LEN_OUTSIDE_LADDER;
+use crate::games::word_ladder::{
+    ask_ladder_length, start_word_ladder, LadderDirection, LADDER_LENGTHS,
+};
+use crate::games::{show_dead_ends, show_help, Direction};
+use crate::preferences::{
+    handle_bot_style_command, handle_compact_command, handle_define_button_command,
+    handle_difficulty_command, handle_examples_command, handle_tone_command,
+};
+use crate::rhymes::rhymes_available;
+use crate::settings::get_settings;
+use crate::state::{DialogueStorage, MyDialogue, State};
+use crate::stats::{leaderboard, player_summary, stats_summary, Ranking};
+use log::{debug, error, info, warn};
 use rand::prelude::IndexedRandom;
-use teloxide::payloads::SendMessageSetters;
+use reqwest::Url;
+use std::future::Future;
+use std::sync::Arc;
+use teloxide::payloads::{AnswerCallbackQuerySetters, SendAudioSetters, SendMessageSetters};
 use teloxide::prelude::{CallbackQuery, Message, Requester, ResponseResult};
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me, ParseMode};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Me, ParseMode};
 use teloxide::utils::command::BotCommands;
 use teloxide::Bot;
 
 /// Enum of callback data types for better type safety
 pub enum CallbackType<'a> {
     GameSelect(&'a str),
-    Definition { word: &'a str, index: usize },
+    /// Abandon the game in progress and start the given one
+    Abandon(&'a str),
+    /// Keep the game in progress after all
+    KeepPlaying,
+    /// Show the game menu, e.g. after a game ends
+    ChooseGame,
+    Definition {
+        word: &'a str,
+        index: usize,
+    },
+    ShowDefinition(&'a str),
+    /// Turn a bot word's announcement into its definition card
+    Define(&'a str),
+    /// Send the recording of a word's pronunciation
+    Pronounce(&'a str),
+    /// Whether the player knows a word in the vocabulary check
+    VocabAnswer {
+        word: &'a str,
+        known: bool,
+    },
+    /// Start Forbidden Letters with this many banned letters
+    ForbiddenCount(usize),
+    /// Start Synonym String at this index of its similarity levels
+    SynonymLevel(usize),
+    /// Start a Word Ladder going this way with words up to this many letters
+    LadderLength {
+        direction: LadderDirection,
+        max_len: u8,
+    },
     Unknown(&'a str),
 }
 
 /// Parse callback data into a structured type
-fn parse_callback(data: &str) -> CallbackType {
+fn parse_callback(data: &str) -> CallbackType<'_> {
     if data.starts_with("def_") {
         let parts: Vec<&str> = data.split('_').collect();
         if parts.len() >= 3 {
@@ -35,61 +99,216 @@ fn parse_callback(data: &str) -> CallbackType {
             }
         }
         CallbackType::Unknown(data)
+    } else if let Some(word) = data.strip_prefix("show_") {
+        CallbackType::ShowDefinition(word)
+    } else if let Some(word) = data.strip_prefix("define_") {
+        CallbackType::Define(word)
+    } else if let Some(word) = data.strip_prefix("pron_") {
+        CallbackType::Pronounce(word)
+    } else if let Some(word) = data.strip_prefix("vocab_yes_") {
+        CallbackType::VocabAnswer { word, known: true }
+    } else if let Some(word) = data.strip_prefix("vocab_no_") {
+        CallbackType::VocabAnswer { word, known: false }
+    } else if let Some(count) = data
+        .strip_prefix("forbidden_count_")
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| (1..=MAX_FORBIDDEN).contains(n))
+    {
+        CallbackType::ForbiddenCount(count)
+    } else if let Some(level) = data
+        .strip_prefix("synonym_level_")
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|&n| n < SIMILARITY_LEVELS.len())
+    {
+        CallbackType::SynonymLevel(level)
+    } else if let Some((direction, max_len)) = [LadderDirection::Up, LadderDirection::Down]
+        .into_iter()
+        .find_map(|direction| {
+            data.strip_prefix(direction.length_prefix())
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| LADDER_LENGTHS.contains(n))
+                .map(|n| (direction, n))
+        })
+    {
+        CallbackType::LadderLength { direction, max_len }
+    } else if let Some(game) = data.strip_prefix("abandon_").filter(|g| is_game_id(g)) {
+        CallbackType::Abandon(game)
+    } else if data == "keep_playing" {
+        CallbackType::KeepPlaying
+    } else if data == "choose_game" {
+        CallbackType::ChooseGame
+    } else if is_game_id(data) {
+        CallbackType::GameSelect(data)
     } else {
-        // Game selection or other callback
-        match data {
-            "word_chain" | "alphabet_sprint" | "last_letter" | "synonym_string" | "word_ladder"
-            | "forbidden_letters" => CallbackType::GameSelect(data),
-            _ => CallbackType::Unknown(data),
-        }
+        CallbackType::Unknown(data)
     }
 }
 
+/// Whether callback data is the menu id of a game
+fn is_game_id(data: &str) -> bool {
+    matches!(
+        data,
+        "word_chain"
+            | "word_chain_hotseat"
+            | "word_chain_reverse"
+            | "alphabet_sprint"
+            | "last_letter"
+            | "synonym_string"
+            | "word_ladder"
+            | "word_ladder_down"
+            | "forbidden_letters"
+            | "relay"
+            | "vocab_check"
+            | "anagram_shuffle"
+            | "categories"
+            | "ghost"
+            | "boggle"
+            | "guess_word"
+            | "compound_chain"
+            | "rhyme_time"
+    )
+}
+
 /// Handle incoming text messages
 pub async fn message_handler(bot: Bot, msg: Message, me: Me) -> ResponseResult<()> {
     if let Some(text) = msg.text() {
-        info!("Received message: {}", text);
+        debug!(chat_id = msg.chat.id.0; "Received message: {}", text);
 
         match BotCommands::parse(text, me.username()) {
             Ok(Command::Start) => {
-                info!("Start command received from user {}", msg.chat.id);
+                debug!(chat_id = msg.chat.id.0; "Start command received");
                 handle_start_command(&bot, msg.chat.id).await?;
             }
+            Ok(Command::Help) => {
+                debug!(chat_id = msg.chat.id.0; "Help command received");
+                show_help(&bot, msg.chat.id).await?;
+            }
             Ok(Command::Play) => {
-                info!("Play command received from user {}", msg.chat.id);
+                debug!(chat_id = msg.chat.id.0; "Play command received");
                 handle_play_command(&bot, msg.chat.id).await?;
             }
-            Ok(Command::Hint) => {
-                info!("Hint command received but no active game");
+            Ok(Command::Hint(_)) => {
+                debug!(chat_id = msg.chat.id.0; "Hint command received but no active game");
                 bot.send_message(
                     msg.chat.id,
                     "You need to start a game first before using the hint command. Use /start to choose a game."
                 ).await?;
             }
             Ok(Command::Skip) => {
-                info!("Skip command received but no active game");
+                debug!(chat_id = msg.chat.id.0; "Skip command received but no active game");
                 bot.send_message(
                     msg.chat.id,
                     "You need to start a game first before using the skip command. Use /start to choose a game."
                 ).await?;
             }
             Ok(Command::Score) => {
-                info!("Score command received but no active game");
+                debug!(chat_id = msg.chat.id.0; "Score command received but no active game");
                 bot.send_message(
                     msg.chat.id,
                     "You need to start a game first to check the score. Use /start to choose a game."
                 ).await?;
             }
             Ok(Command::Rules) => {
-                info!("Rules command received from user {}", msg.chat.id);
+                debug!(chat_id = msg.chat.id.0; "Rules command received");
                 handle_rules_command(&bot, msg.chat.id).await?;
             }
             Ok(Command::Stats) => {
-                info!("Stats command received from user {}", msg.chat.id);
+                debug!(chat_id = msg.chat.id.0; "Stats command received");
                 handle_stats_command(&bot, msg.chat.id).await?;
             }
+            Ok(Command::Leaderboard(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Leaderboard command received");
+                handle_leaderboard_command(&bot, &msg, &args).await?;
+            }
+            Ok(Command::Similar(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Similar command received");
+                handle_similar_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Define(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Define command received");
+                handle_define_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Badges) => {
+                debug!(chat_id = msg.chat.id.0; "Badges command received");
+                show_badges(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::BotStyle(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Bot style command received");
+                handle_bot_style_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Tone(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Tone command received");
+                handle_tone_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Difficulty(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Difficulty command received");
+                handle_difficulty_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::MaxLen(_)) => {
+                debug!(chat_id = msg.chat.id.0; "Max length command received");
+                bot.send_message(msg.chat.id, MAX_LEN_OUTSIDE_LADDER)
+                    .await?;
+            }
+            Ok(Command::Compact) => {
+                debug!(chat_id = msg.chat.id.0; "Compact command received");
+                handle_compact_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DefineButton) => {
+                debug!(chat_id = msg.chat.id.0; "Define button command received");
+                handle_define_button_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Examples) => {
+                debug!(chat_id = msg.chat.id.0; "Examples command received");
+                handle_examples_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::DeadEnds) => {
+                debug!(chat_id = msg.chat.id.0; "Dead ends command received");
+                show_dead_ends(&bot, msg.chat.id, &[]).await?;
+            }
+            Ok(Command::Subscribe) => {
+                debug!(chat_id = msg.chat.id.0; "Subscribe command received");
+                handle_subscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Unsubscribe) => {
+                debug!(chat_id = msg.chat.id.0; "Unsubscribe command received");
+                handle_unsubscribe_command(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Join) => {
+                debug!(chat_id = msg.chat.id.0; "Join command received");
+                no_game_to_join(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::Leave) => {
+                debug!(chat_id = msg.chat.id.0; "Leave command received");
+                no_game_to_join(&bot, msg.chat.id).await?;
+            }
+            Ok(Command::LoadModel(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Load model command received");
+                handle_load_model_command(&bot, &msg, &args).await?;
+            }
+            Ok(Command::Model(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Model command received");
+                handle_model_command(&bot, &msg, &args).await?;
+            }
+            Ok(Command::Preview(args)) => {
+                debug!(chat_id = msg.chat.id.0; "Preview command received");
+                handle_preview_command(&bot, msg.chat.id, &args).await?;
+            }
+            Ok(Command::Def(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "/def shows words from the game in progress. Use /start to choose a game.",
+                )
+                .await?;
+            }
+            Ok(Command::Rewind(_)) => {
+                bot.send_message(
+                    msg.chat.id,
+                    "There's no game to rewind. Use /start to choose a game.",
+                )
+                .await?;
+            }
             Ok(Command::Stop) => {
-                info!("Stop command received but no active game");
+                debug!(chat_id = msg.chat.id.0; "Stop command received but no active game");
                 bot.send_message(
                     msg.chat.id,
                     "There's no active game to stop. Use /start to choose a game.",
@@ -97,7 +316,7 @@ pub async fn message_handler(bot: Bot, msg: Message, me: Me) -> ResponseResult<(
                 .await?;
             }
             Err(_) => {
-                warn!("Unknown command received: {}", text);
+                debug!(chat_id = msg.chat.id.0; "Unknown command received: {}", text);
                 bot.send_message(
                     msg.chat.id,
                     "Command not found! Try /start to see available commands.",
@@ -112,20 +331,34 @@ pub async fn message_handler(bot: Bot, msg: Message, me: Me) -> ResponseResult<(
 /// Handle the start command
 async fn handle_start_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> ResponseResult<()> {
     bot.send_message(chat_id, "Welcome to the Kotosume Bot! Choose a game:")
-        .reply_markup(make_game_menu())
+        .reply_markup(make_game_menu(chat_id))
         .await?;
+
+    if !live_lookups_available() {
+        bot.send_message(
+            chat_id,
+            "Note: live dictionary lookups are currently unavailable, so only words I already know can be played.",
+        )
+        .await?;
+    }
     Ok(())
 }
 
 /// Handle the play command - randomly select a game to start
 async fn handle_play_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> ResponseResult<()> {
-    let games = vec![
+    let games = [
         ("word_chain", "Word Chain"),
         ("alphabet_sprint", "Alphabet Sprint"),
         ("last_letter", "Last Letter Scramble"),
         ("synonym_string", "Synonym String"),
         ("word_ladder", "Word Length Ladder"),
         ("forbidden_letters", "Forbidden Letters"),
+        ("anagram_shuffle", "Anagram Shuffle"),
+        ("categories", "Categories"),
+        ("ghost", "Ghost"),
+        ("boggle", "Boggle"),
+        ("guess_word", "Guess the Word"),
+        ("compound_chain", "Compound Chain"),
     ];
 
     let &(_game_id, game_name) = games.choose(&mut rand::rng()).unwrap();
@@ -138,77 +371,409 @@ async fn handle_play_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> Res
     // Forward to the regular start menu to select the game
     // This avoids needing to create a dialogue directly
     bot.send_message(chat_id, "Please select your game from the menu:")
-        .reply_markup(make_game_menu())
+        .reply_markup(make_game_menu(chat_id))
         .await?;
 
     Ok(())
 }
 
-/// Handle the rules command when in Start state - show available games and their rules
-async fn handle_rules_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> ResponseResult<()> {
-    bot.send_message(
-        chat_id,
-        "Kotosume Bot Games:\n\n\
+/// Overview of the games /rules shows outside a game, in MarkdownV2: everything but the bold
+/// game names is escaped
+const RULES_OVERVIEW: &str = "Kotosume Bot Games:\n\n\
         🔤 *Word Chain*: Link words where each starts with the last letter of the previous word\n\
         🏃 *Alphabet Sprint*: Provide words that all start with the same letter\n\
         🔤 *Last Letter Scramble*: Like Word Chain, but with required letters from the previous word\n\
         🔄 *Synonym String*: Chain words with similar meanings that start with the last letter of the previous word\n\
-        📏 *Word Length Ladder*: Start with short words and increase length each turn\n\
-        ❌ *Forbidden Letters*: Word chain while avoiding certain letters\n\n\
-        Use /start to select a game, then use /rules in-game for specific rules.",
-    ).parse_mode(ParseMode::MarkdownV2).await?;
+        📏 *Word Length Ladder*: Start with short words and increase length each turn, or go down from long ones\n\
+        ❌ *Forbidden Letters*: Word chain while avoiding certain letters\n\
+        🔀 *Anagram Shuffle*: Find as many words as you can in a scrambled set of letters\n\
+        🗂 *Categories*: Find as many words as you can that belong with a theme\n\
+        👻 *Ghost*: Take turns adding letters without being the one to finish a word\n\
+        🔠 *Boggle*: Find as many words as you can in a grid of letters before time runs out\n\
+        📖 *Guess the Word*: Name the word behind a definition, with letters revealed as hints\n\
+        🧩 *Compound Chain*: Chain compound words, each starting with the second half of the last one\n\
+        🎵 *Rhyme Time*: Chain rhyming words, each starting with the last letter of the previous word\n\
+        📚 *Vocabulary Check*: Say which words you know, and get an estimate of your vocabulary size\n\n\
+        Use /start to select a game, then use /rules in\\-game for specific rules\\.";
+
+/// Handle the rules command when in Start state - show available games and their rules
+async fn handle_rules_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> ResponseResult<()> {
+    bot.send_message(chat_id, RULES_OVERVIEW)
+        .parse_mode(ParseMode::MarkdownV2)
+        .await?;
 
     Ok(())
 }
 
 /// Handle the stats command - show player statistics
 async fn handle_stats_command(bot: &Bot, chat_id: teloxide::types::ChatId) -> ResponseResult<()> {
-    // Note: In a complete implementation, this would retrieve statistics from a database
+    bot.send_message(chat_id, stats_summary(chat_id)).await?;
+
+    Ok(())
+}
+
+/// Handle the leaderboard command - rank a group's players, or show a private chat's player
+/// their totals
+async fn handle_leaderboard_command(bot: &Bot, msg: &Message, args: &str) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let Some(ranking) = Ranking::parse(args) else {
+        bot.send_message(chat_id, "Usage: /leaderboard [points|wins]")
+            .await?;
+        return Ok(());
+    };
+    let text = match &msg.from {
+        Some(user) if !msg.chat.is_group() && !msg.chat.is_supergroup() => {
+            player_summary(chat_id, user.id)
+        }
+        _ => leaderboard(chat_id, ranking),
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Whether the sender of a message may run admin commands, telling them off if not
+async fn ensure_admin(bot: &Bot, msg: &Message) -> ResponseResult<bool> {
+    let is_admin = msg
+        .from
+        .as_ref()
+        .is_some_and(|user| get_settings().is_admin(user.id.0));
+    if !is_admin {
+        bot.send_message(msg.chat.id, "Sorry, only bot admins can use this command.")
+            .await?;
+    }
+    Ok(is_admin)
+}
+
+/// Handle /loadmodel: load an alternate embeddings file so chats can compare it to the default
+async fn handle_load_model_command(bot: &Bot, msg: &Message, args: &str) -> ResponseResult<()> {
+    if !ensure_admin(bot, msg).await? {
+        return Ok(());
+    }
+    let chat_id = msg.chat.id;
+    let Some((name, file)) = args.trim().split_once(char::is_whitespace) else {
+        bot.send_message(chat_id, "Usage: /loadmodel <name> <file>")
+            .await?;
+        return Ok(());
+    };
+    let (name, file) = (name.to_string(), file.trim().to_string());
+
     bot.send_message(
         chat_id,
-        "Player Statistics\n\n\
-        This feature is coming soon! In the future, you'll be able to track:\n\
-        • Games played\n\
-        • Win/loss record\n\
-        • Longest word chains\n\
-        • Favorite games\n\
-        • Vocabulary size\n\n\
-        Stay tuned for updates!",
+        format!("Loading model '{}' from {}...", name, file),
     )
     .await?;
+    // Parsing a large embeddings file takes a while, so keep it off the async workers
+    let loaded = {
+        let (name, file) = (name.clone(), file.clone());
+        tokio::task::spawn_blocking(move || load_model(&name, &file)).await
+    };
+    let reply = match loaded {
+        Ok(Ok(words)) => format!(
+            "Model '{}' loaded with {} words. Switch a chat to it with /model {}",
+            name, words, name
+        ),
+        Ok(Err(e)) => {
+            error!(chat_id = chat_id.0; "Failed to load model '{}' from {}: {}", name, file, e);
+            format!("Couldn't load model '{}': {}", name, e)
+        }
+        Err(e) => {
+            error!(chat_id = chat_id.0; "Model loading task failed: {}", e);
+            format!("Couldn't load model '{}'.", name)
+        }
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Handle /model: show or switch the embeddings model this chat compares words with
+async fn handle_model_command(bot: &Bot, msg: &Message, args: &str) -> ResponseResult<()> {
+    if !ensure_admin(bot, msg).await? {
+        return Ok(());
+    }
+    let chat_id = msg.chat.id;
+    let name = args.trim();
+
+    if name.is_empty() {
+        let loaded = model_names();
+        let mut reply = format!(
+            "This chat uses the {} model.\nLoaded alternates: {}\n\n\
+                Switch with /model <name>, or /model default to go back.",
+            selected_model(chat_id).unwrap_or_else(|| "default".to_string()),
+            if loaded.is_empty() {
+                "none".to_string()
+            } else {
+                loaded.join(", ")
+            }
+        );
+        let mismatches = dimension_mismatches();
+        if mismatches > 0 {
+            reply.push_str(&format!(
+                "\n\n{} word comparisons hit vectors of different dimensions since startup; check the logs for the words involved.",
+                mismatches
+            ));
+        }
+        bot.send_message(chat_id, reply).await?;
+        return Ok(());
+    }
+
+    let choice = if name == "default" { None } else { Some(name) };
+    let reply = match select_model(chat_id, choice) {
+        Ok(()) => {
+            info!(chat_id = chat_id.0; "Chat switched to the {} model", name);
+            format!(
+                "Synonym String and Last Letter Scramble now compare words with the {} model in this chat.",
+                name
+            )
+        }
+        Err(e) => format!("{}. Load it first with /loadmodel.", e),
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Default and maximum number of words listed by /similar
+const SIMILAR_DEFAULT: usize = 10;
+const SIMILAR_MAX: usize = 50;
+
+/// Handle the similar command - list the words closest in meaning to a word
+async fn handle_similar_command(
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+    args: &str,
+) -> ResponseResult<()> {
+    let mut parts = args.split_whitespace();
+    let word = match parts.next() {
+        Some(w) => w.to_lowercase(),
+        None => {
+            bot.send_message(chat_id, "Usage: /similar <word> [n]")
+                .await?;
+            return Ok(());
+        }
+    };
+    let n = parts
+        .next()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(SIMILAR_DEFAULT)
+        .clamp(1, SIMILAR_MAX);
+
+    if !is_valid_word(&word) {
+        bot.send_message(
+            chat_id,
+            format!(
+                "'{}' isn't in my vocabulary, so I can't find similar words.",
+                word
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match most_similar(&word, n) {
+        Ok(similar) => {
+            let lines = similar
+                .iter()
+                .enumerate()
+                .map(|(i, (w, score))| format!("{}. {} ({:.2})", i + 1, w, score))
+                .collect::<Vec<String>>()
+                .join("\n");
+            bot.send_message(
+                chat_id,
+                format!("Words most similar to '{}':\n\n{}", word, lines),
+            )
+            .await?;
+        }
+        Err(e) => {
+            error!(chat_id = chat_id.0; "Failed to find words similar to '{}': {}", word, e);
+            bot.send_message(
+                chat_id,
+                format!("Sorry, I couldn't look up words similar to '{}'.", word),
+            )
+            .await?;
+        }
+    }
 
     Ok(())
 }
 
+/// Handle /define: show a word's definition card without starting a game
+async fn handle_define_command(
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+    args: &str,
+) -> ResponseResult<()> {
+    let word = args.trim().to_lowercase();
+    if word.is_empty() {
+        bot.send_message(chat_id, "Usage: /define <word>").await?;
+        return Ok(());
+    }
+
+    match get_word_details(&word).await {
+        Ok(word_details) => word_details.send_message(bot, chat_id, 0).await,
+        Err(e) => {
+            error!(chat_id = chat_id.0; "Error retrieving word details for '{}': {:?}", word, e);
+            let reply = match e {
+                DictionaryError::NotFound(_) | DictionaryError::Unavailable(_) => e.to_string(),
+                _ => format!(
+                    "Sorry, I can't look up '{}' right now. Please try again later.",
+                    word
+                ),
+            };
+            bot.send_message(chat_id, reply).await?;
+            Ok(())
+        }
+    }
+}
+
 /// Handler for callback queries (when a game is selected or definition navigation)
 pub async fn callback_handler(
     bot: Bot,
     q: CallbackQuery,
     dialogue: MyDialogue,
+    storage: Arc<DialogueStorage>,
 ) -> ResponseResult<()> {
     if let Some(data) = &q.data {
-        info!("Received callback: {}", data);
+        let callback = parse_callback(data);
+        let msg = q.regular_message();
+        debug!(chat_id = msg.map(|m| m.chat.id.0); "Received callback: {}", data);
 
-        // Always acknowledge the callback query to stop the loading indicator
-        bot.answer_callback_query(&q.id).await?;
+        // Always acknowledge the callback query to stop the loading indicator. Definition pages
+        // do it themselves once they know whether the word still resolves, e.g. after a restart
+        let pages_definition = matches!(
+            callback,
+            CallbackType::Definition { .. } | CallbackType::Define(_)
+        );
+        if !pages_definition || msg.is_none() {
+            bot.answer_callback_query(&q.id).await?;
+        }
 
-        if let Some(msg) = q.regular_message() {
+        if let Some(msg) = msg {
             let chat_id = msg.chat.id;
 
-            match parse_callback(data) {
+            match callback {
                 CallbackType::GameSelect(game) => {
-                    info!("User selected game: {}", game);
-                    handle_game_selection(game, chat_id, bot.clone(), dialogue).await?;
+                    debug!(chat_id = chat_id.0, game = game; "User selected game");
+                    match current_game(&dialogue).await {
+                        Some(state) => confirm_abandon(&bot, chat_id, game, &state).await?,
+                        None => {
+                            handle_game_selection(game, chat_id, bot.clone(), dialogue, storage)
+                                .await?
+                        }
+                    }
+                }
+                CallbackType::Abandon(game) => {
+                    debug!(
+                        chat_id = chat_id.0, game = game;
+                        "User abandoned their game for another"
+                    );
+                    if let Some(state) = current_game(&dialogue).await {
+                        abandon_game(chat_id, &dialogue, &state).await;
+                        bot.edit_message_text(
+                            chat_id,
+                            msg.id,
+                            format!(
+                                "Abandoned your {} with {} words.",
+                                state.game_name().unwrap_or("game"),
+                                state.chain().len()
+                            ),
+                        )
+                        .await?;
+                    }
+                    handle_game_selection(game, chat_id, bot.clone(), dialogue, storage).await?;
+                }
+                CallbackType::ChooseGame => {
+                    bot.send_message(chat_id, "Choose a game:")
+                        .reply_markup(make_game_menu(chat_id))
+                        .await?;
+                }
+                CallbackType::KeepPlaying => {
+                    let reply = match current_game(&dialogue).await {
+                        Some(state) => {
+                            let mut reply = format!(
+                                "Carrying on with your {}.",
+                                state.game_name().unwrap_or("game")
+                            );
+                            if let Some(letter) = state.turn_letter() {
+                                reply.push_str(&format!(
+                                    " Your next word is one {}.",
+                                    state.turn_direction().describe(letter)
+                                ));
+                            }
+                            reply
+                        }
+                        None => "That game has already ended.".to_string(),
+                    };
+                    bot.edit_message_text(chat_id, msg.id, reply).await?;
                 }
                 CallbackType::Definition { word, index } => {
-                    info!(
+                    debug!(
+                        chat_id = chat_id.0;
                         "User navigating definition for '{}' to index {}",
                         word, index
                     );
-                    handle_definition_navigation(word, index, &bot, chat_id, msg.id).await?;
+                    handle_definition_navigation(word, index, &bot, &q.id, chat_id, msg.id).await?;
+                }
+                CallbackType::ShowDefinition(word) => {
+                    debug!(chat_id = chat_id.0; "User opened definition for '{}'", word);
+                    handle_show_definition(word, &bot, chat_id).await?;
+                }
+                CallbackType::Define(word) => {
+                    debug!(chat_id = chat_id.0; "User asked for the definition of '{}'", word);
+                    // The card replaces the announcement, then pages like any other
+                    handle_definition_navigation(word, 0, &bot, &q.id, chat_id, msg.id).await?;
+                }
+                CallbackType::Pronounce(word) => {
+                    debug!(chat_id = chat_id.0; "User asked to hear '{}'", word);
+                    handle_pronounce(word, &bot, chat_id).await?;
+                }
+                CallbackType::VocabAnswer { word, known } => {
+                    // Only the question being asked takes an answer; older buttons are ignored
+                    if let Ok(Some(State::VocabCheck {
+                        word: asked,
+                        answers,
+                        progress,
+                    })) = dialogue.get().await
+                    {
+                        if asked.word == word {
+                            bot.edit_message_reply_markup(chat_id, msg.id).await?;
+                            let answer = (asked, answers, progress);
+                            record_answer(&bot, chat_id, dialogue, answer, known).await?;
+                        }
+                    }
+                }
+                CallbackType::ForbiddenCount(count) => {
+                    debug!(chat_id = chat_id.0; "User chose {} forbidden letters", count);
+                    bot.edit_message_reply_markup(chat_id, msg.id).await?;
+                    start_with_options(
+                        &bot,
+                        chat_id,
+                        dialogue,
+                        forbidden_letters::GAME,
+                        |dialogue| start_forbidden_letters(chat_id, bot.clone(), dialogue, count),
+                    )
+                    .await?;
+                }
+                CallbackType::SynonymLevel(level) => {
+                    let (name, min_similarity) = SIMILARITY_LEVELS[level];
+                    debug!(chat_id = chat_id.0; "User chose {} Synonym String", name);
+                    bot.edit_message_reply_markup(chat_id, msg.id).await?;
+                    start_with_options(&bot, chat_id, dialogue, synonym_string::GAME, |dialogue| {
+                        start_synonym_string(chat_id, bot.clone(), dialogue, min_similarity)
+                    })
+                    .await?;
+                }
+                CallbackType::LadderLength { direction, max_len } => {
+                    debug!(chat_id = chat_id.0; "User chose a {}-letter Word Ladder", max_len);
+                    bot.edit_message_reply_markup(chat_id, msg.id).await?;
+                    start_with_options(&bot, chat_id, dialogue, direction.game_id(), |dialogue| {
+                        start_word_ladder(chat_id, bot.clone(), dialogue, direction, max_len)
+                    })
+                    .await?;
                 }
                 CallbackType::Unknown(data) => {
-                    warn!("Unknown callback data received: {}", data);
+                    warn!(chat_id = chat_id.0; "Unknown callback data received: {}", data);
                 }
             }
         }
@@ -217,22 +782,116 @@ pub async fn callback_handler(
     Ok(())
 }
 
+/// The chat's game in progress, if any
+async fn current_game(dialogue: &MyDialogue) -> Option<State> {
+    dialogue
+        .get()
+        .await
+        .ok()
+        .flatten()
+        .filter(|state| state.game_name().is_some())
+}
+
+/// Start a game once its options were picked from the keyboard it showed. An abandoned game
+/// is already gone by then, so this only asks about one started since the keyboard was sent.
+async fn start_with_options<F, Fut>(
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+    dialogue: MyDialogue,
+    game: &str,
+    start: F,
+) -> ResponseResult<()>
+where
+    F: FnOnce(MyDialogue) -> Fut,
+    Fut: Future<Output = ResponseResult<()>>,
+{
+    match current_game(&dialogue).await {
+        Some(state) => confirm_abandon(bot, chat_id, game, &state).await,
+        None => start(dialogue).await,
+    }
+}
+
+/// Ask before a game picked from the menu replaces the unfinished one. Relay matches involve
+/// another chat, so they have to be stopped explicitly instead.
+async fn confirm_abandon(
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+    game: &str,
+    state: &State,
+) -> ResponseResult<()> {
+    let name = state.game_name().unwrap_or("game");
+    if let State::Relay { .. } = state {
+        bot.send_message(
+            chat_id,
+            format!(
+                "You're in the middle of a {}. End it with /stop before starting another game.",
+                name
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("Abandon it", format!("abandon_{}", game)),
+        InlineKeyboardButton::callback("Keep playing", "keep_playing"),
+    ]]);
+    bot.send_message(
+        chat_id,
+        format!(
+            "You have an unfinished {} with {} words — abandon it?",
+            name,
+            state.chain().len()
+        ),
+    )
+    .reply_markup(keyboard)
+    .await?;
+
+    Ok(())
+}
+
 /// Handle game selection from the menu
 async fn handle_game_selection(
     game: &str,
     chat_id: teloxide::types::ChatId,
     bot: Bot,
     dialogue: MyDialogue,
+    storage: Arc<DialogueStorage>,
 ) -> ResponseResult<()> {
     match game {
-        "word_chain" => start_word_chain(chat_id, bot, dialogue).await,
+        "word_chain" => start_word_chain(chat_id, bot, dialogue, false, Direction::Forward).await,
+        "word_chain_reverse" => {
+            start_word_chain(chat_id, bot, dialogue, false, Direction::Reverse).await
+        }
+        "word_chain_hotseat" => {
+            if chat_id.is_user() {
+                start_word_chain(chat_id, bot, dialogue, true, Direction::Forward).await
+            } else {
+                bot.send_message(
+                    chat_id,
+                    "Two-player mode is only available in private chats.",
+                )
+                .await?;
+                Ok(())
+            }
+        }
         "alphabet_sprint" => start_alphabet_sprint(chat_id, bot, dialogue).await,
         "last_letter" => start_last_letter_scramble(chat_id, bot, dialogue).await,
-        "synonym_string" => start_synonym_string(chat_id, bot, dialogue).await,
-        "word_ladder" => start_word_ladder(chat_id, bot, dialogue).await,
-        "forbidden_letters" => start_forbidden_letters(chat_id, bot, dialogue).await,
+        "synonym_string" => ask_similarity_level(&bot, chat_id).await,
+        "word_ladder" => ask_ladder_length(&bot, chat_id, LadderDirection::Up).await,
+        "word_ladder_down" => ask_ladder_length(&bot, chat_id, LadderDirection::Down).await,
+        "forbidden_letters" => ask_letter_count(&bot, chat_id).await,
+        "relay" => start_relay(chat_id, bot, dialogue, storage).await,
+        "vocab_check" => start_vocab_check(chat_id, bot, dialogue).await,
+        "anagram_shuffle" => start_anagram_shuffle(chat_id, bot, dialogue).await,
+        "categories" => start_categories(chat_id, bot, dialogue).await,
+        "ghost" => start_ghost(chat_id, bot, dialogue).await,
+        "boggle" => start_boggle(chat_id, bot, dialogue).await,
+        "guess_word" => start_guess_word(chat_id, bot, dialogue).await,
+        "compound_chain" => start_compound_chain(chat_id, bot, dialogue).await,
+        "rhyme_time" => start_rhyme_time(chat_id, bot, dialogue).await,
         _ => {
-            warn!("Unrecognized game selection: {}", game);
+            warn!(chat_id = chat_id.0, game = game; "Unrecognized game selection");
             Ok(())
         }
     }
@@ -243,18 +902,48 @@ async fn handle_definition_navigation(
     word: &str,
     index: usize,
     bot: &Bot,
+    query_id: &str,
     chat_id: teloxide::types::ChatId,
     message_id: teloxide::types::MessageId,
 ) -> ResponseResult<()> {
-    match get_word_details(word).await {
+    match page_word(word, chat_id).await {
         Ok(word_details) => {
+            bot.answer_callback_query(query_id).await?;
             word_details
                 .edit_message(bot, chat_id, message_id, index)
                 .await?;
             Ok(())
         }
+        Err(notice) => {
+            bot.answer_callback_query(query_id).text(notice).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Resolve the word of a definition page tap. Callback data carries only the word and page, so
+/// a card from before a restart looks the word up again; when that fails, the card stays as it
+/// is and the tap is answered with the notice returned instead.
+async fn page_word(word: &str, chat_id: teloxide::types::ChatId) -> Result<WordInfo, String> {
+    get_word_details(word).await.map_err(|e| {
+        error!(chat_id = chat_id.0; "Error retrieving word details for '{}': {:?}", word, e);
+        format!(
+            "Sorry, I can't look up '{}' right now. Please try again later.",
+            word
+        )
+    })
+}
+
+/// Send a word's definition card as its own message, e.g. from a compact game board
+async fn handle_show_definition(
+    word: &str,
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+) -> ResponseResult<()> {
+    match get_word_details(word).await {
+        Ok(word_details) => word_details.send_message(bot, chat_id, 0).await,
         Err(e) => {
-            error!("Error retrieving word details for '{}': {:?}", word, e);
+            error!(chat_id = chat_id.0; "Error retrieving word details for '{}': {:?}", word, e);
             bot.send_message(
                 chat_id,
                 format!("Sorry, I couldn't find information for the word '{}'", word),
@@ -265,19 +954,79 @@ async fn handle_definition_navigation(
     }
 }
 
+/// Send the recording of a word's pronunciation, from the button on its definition card
+async fn handle_pronounce(
+    word: &str,
+    bot: &Bot,
+    chat_id: teloxide::types::ChatId,
+) -> ResponseResult<()> {
+    let audio = match get_word_details(word).await {
+        Ok(word_details) => word_details.audio.and_then(|audio| Url::parse(&audio).ok()),
+        Err(e) => {
+            error!(chat_id = chat_id.0; "Error retrieving word details for '{}': {:?}", word, e);
+            None
+        }
+    };
+    // Telegram fetches the file itself, so a recording it can't reach fails the request
+    let sent = match audio {
+        Some(url) => bot
+            .send_audio(chat_id, InputFile::url(url))
+            .title(word)
+            .await
+            .map_err(|e| {
+                warn!(
+                    chat_id = chat_id.0;
+                    "Couldn't send the pronunciation of '{}': {}",
+                    word, e
+                )
+            })
+            .is_ok(),
+        None => false,
+    };
+    if !sent {
+        bot.send_message(
+            chat_id,
+            format!(
+                "Sorry, I can't play the pronunciation of '{}' right now.",
+                word
+            ),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
 /// Create the inline keyboard menu with game choices
-fn make_game_menu() -> InlineKeyboardMarkup {
+fn make_game_menu(chat_id: teloxide::types::ChatId) -> InlineKeyboardMarkup {
     let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 
     // List of games with their callback data
-    let games = vec![
+    let mut games = vec![
         ("Word Chain", "word_chain"),
         ("Alphabet Sprint", "alphabet_sprint"),
         ("Last Letter Scramble", "last_letter"),
         ("Synonym String", "synonym_string"),
         ("Word Length Ladder", "word_ladder"),
         ("Forbidden Letters", "forbidden_letters"),
+        ("Word Chain (2 players)", "word_chain_hotseat"),
+        ("Reverse Word Chain", "word_chain_reverse"),
+        ("Descending Word Ladder", "word_ladder_down"),
+        ("Vocabulary Check", "vocab_check"),
+        ("Anagram Shuffle", "anagram_shuffle"),
+        ("Categories", "categories"),
+        ("Ghost", "ghost"),
+        ("Boggle", "boggle"),
+        ("Guess the Word", "guess_word"),
+        ("Compound Chain", "compound_chain"),
     ];
+    // Rhymes can only be checked with the pronouncing dictionary loaded
+    if rhymes_available() {
+        games.push(("Rhyme Time", "rhyme_time"));
+    }
+    if get_settings().relay_partner(chat_id).is_some() {
+        games.push(("Relay Match", "relay"));
+    }
 
     // Add buttons for each game (2 per row for better layout)
     for chunk in games.chunks(2) {
@@ -292,3 +1041,54 @@ fn make_game_menu() -> InlineKeyboardMarkup {
 
     InlineKeyboardMarkup::new(keyboard)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::outcome::Progress;
+    use teloxide::dispatching::dialogue::{InMemStorage, Storage};
+    use teloxide::types::ChatId;
+
+    #[tokio::test]
+    async fn page_tap_for_an_unresolvable_word_gets_a_notice() {
+        let CallbackType::Definition { word, index } = parse_callback("def_qzxvjk_2") else {
+            panic!("definition page callback not recognized");
+        };
+        assert_eq!((word, index), ("qzxvjk", 2));
+
+        crate::dictionary::init_empty_cache();
+        let notice = page_word(word, ChatId(1)).await.unwrap_err();
+        assert!(notice.contains("'qzxvjk'"));
+    }
+
+    #[test]
+    fn rules_overview_escapes_markdown() {
+        let mut chars = RULES_OVERVIEW.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => assert!(chars.next().is_some()),
+                '_' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|'
+                | '{' | '}' | '.' | '!' => panic!("unescaped '{}' in the rules overview", c),
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn abandoned_game_is_gone_before_the_new_one_asks_for_options() {
+        let chat_id = ChatId(-2221);
+        let dialogue = MyDialogue::new(InMemStorage::<State>::new().erase(), chat_id);
+        let old = State::SynonymString {
+            chain: Vec::new(),
+            curr_char: 'a',
+            min_similarity: 0.5,
+            progress: Progress::default(),
+        };
+        dialogue.update(old).await.unwrap();
+
+        // Confirming the abandon, then picking an option from the keyboard the new game shows
+        let state = current_game(&dialogue).await.unwrap();
+        abandon_game(chat_id, &dialogue, &state).await;
+        assert!(current_game(&dialogue).await.is_none());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::settings::get_settings;
+use crate::stats::recent_win_rate;
+use teloxide::prelude::ChatId;
+
+/// Recent win rate at or above which a chat only gets vague hints
+const STRONG_WIN_RATE: f64 = 0.6;
+/// Recent win rate at or below which a chat gets the whole word
+const STRUGGLING_WIN_RATE: f64 = 0.2;
+
+/// How much of the hint word a hint gives away
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HintLevel {
+    /// Only the word's length
+    Vague,
+    /// The first half of the word
+    Medium,
+    /// The whole word
+    Full,
+}
+
+impl HintLevel {
+    /// Parse a level name as used in the HINT_LEVEL setting
+    pub fn parse(s: &str) -> Option<HintLevel> {
+        match s.trim().to_lowercase().as_str() {
+            "vague" => Some(HintLevel::Vague),
+            "medium" => Some(HintLevel::Medium),
+            "full" => Some(HintLevel::Full),
+            _ => None,
+        }
+    }
+
+    /// The level for a chat: the configured override, otherwise based on its recent win rate
+    fn for_chat(chat_id: ChatId) -> HintLevel {
+        if let Some(level) = get_settings().hint_level {
+            return level;
+        }
+        match recent_win_rate(chat_id) {
+            Some(rate) if rate >= STRONG_WIN_RATE => HintLevel::Vague,
+            Some(rate) if rate <= STRUGGLING_WIN_RATE => HintLevel::Full,
+            _ => HintLevel::Medium,
+        }
+    }
+}
+
+/// Hint pointing towards `word`, giving away as much as suits the chat's skill
+pub fn hint_message(chat_id: ChatId, word: &str) -> String {
+    let len = word.chars().count();
+    match HintLevel::for_chat(chat_id) {
+        HintLevel::Vague => format!(
+            "Hint: there's a {}-letter word that fits. Think it over!",
+            len
+        ),
+        HintLevel::Medium => {
+            let shown = len.div_ceil(2);
+            let pattern = word
+                .chars()
+                .enumerate()
+                .map(|(i, c)| if i < shown { c } else { '_' })
+                .map(String::from)
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!("Hint: try a word like {}", pattern)
+        }
+        HintLevel::Full => format!(
+            "Hint: You could try a word like '{}' or something similar.",
+            word
+        ),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::settings::get_settings;
+use log::kv::{Key, Value, VisitSource};
+use log::Record;
+use std::env;
+use std::io::Write;
+
+/// Filter used when `RUST_LOG` isn't set: this crate's lifecycle events, warnings from the rest
+const DEFAULT_FILTER: &str = "warn,kotosume_bot=info";
+
+/// Set up the global logger from `RUST_LOG`, `LOG_LEVELS` and `LOG_FORMAT`
+pub fn init_logging() {
+    let settings = get_settings();
+    let mut filter = env::var("RUST_LOG").unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    if let Some(levels) = &settings.log_levels {
+        for directive in crate_directives(levels) {
+            filter.push(',');
+            filter.push_str(&directive);
+        }
+    }
+
+    let mut builder = pretty_env_logger::formatted_timed_builder();
+    if settings.log_json {
+        builder.format(|buf, record| {
+            let fields: String = record_fields(record)
+                .iter()
+                .map(|(key, value)| format!(",\"{}\":\"{}\"", json_escape(key), json_escape(value)))
+                .collect();
+            writeln!(
+                buf,
+                "{{\"ts\":\"{}\",\"level\":\"{}\",\"target\":\"{}\",\"msg\":\"{}\"{}}}",
+                buf.timestamp_millis(),
+                record.level(),
+                json_escape(record.target()),
+                json_escape(&record.args().to_string()),
+                fields
+            )
+        });
+    } else {
+        // pretty_env_logger's layout, with the record's fields (chat id, game) after the message
+        builder.format(|buf, record| {
+            let fields: String = record_fields(record)
+                .iter()
+                .map(|(key, value)| format!(" {}={}", key, value))
+                .collect();
+            writeln!(
+                buf,
+                " {} {:<5} {} > {}{}",
+                buf.timestamp_millis(),
+                buf.default_styled_level(record.level()),
+                record.target(),
+                record.args(),
+                fields
+            )
+        });
+    }
+    builder.parse_filters(&filter).init();
+}
+
+/// Structured fields attached to a log record, such as `chat_id` and `game`, in the order given
+fn record_fields(record: &Record) -> Vec<(String, String)> {
+    struct Fields(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Fields {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    let mut fields = Fields(Vec::new());
+    let _ = record.key_values().visit(&mut fields);
+    fields.0
+}
+
+/// Turn `LOG_LEVELS` entries into filter directives scoped to this crate's modules.
+/// `games=debug` becomes `kotosume_bot::games=debug`; a bare level applies to the whole crate.
+fn crate_directives(levels: &str) -> Vec<String> {
+    levels
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((module, level)) => format!(
+                "kotosume_bot::{}={}",
+                module.trim().replace('.', "::"),
+                level.trim()
+            ),
+            None => format!("kotosume_bot={}", entry),
+        })
+        .collect()
+}
+
+/// Escape a string for use inside a JSON string literal
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
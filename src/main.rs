@@ -1,32 +1,84 @@
+mod achievements;
 mod command;
+mod daily;
 mod dictionary;
 mod embeddings;
+#[cfg(test)]
+mod fake_api;
+#[cfg(test)]
+mod fake_dictionary;
+mod frequency;
 mod games;
 mod handler;
+mod hints;
+mod logging;
+mod persist;
+mod preferences;
+mod providers;
+mod rhymes;
+mod settings;
+mod spelling;
 mod state;
+mod stats;
+mod tone;
 
-use crate::dictionary::{get_cache, init_cache, save_cache};
+use crate::achievements::{init_achievements, save_achievements};
+use crate::daily::{init_subscribers, run_word_of_the_day, save_subscribers};
+use crate::dictionary::{
+    build_offline_dictionary, get_cache, init_cache, init_offline_dictionary,
+    live_lookups_available, prefetch_words, run_cache_autosave, save_cache, CACHE_PATH,
+};
+use crate::frequency::init_frequencies;
 use crate::games::alphabet_sprint::alphabet_sprint;
+use crate::games::anagram_shuffle::anagram_shuffle;
+use crate::games::boggle::boggle;
+use crate::games::categories::categories;
+use crate::games::compound_chain::compound_chain;
 use crate::games::forbidden_letters::forbidden_letters;
+use crate::games::ghost::ghost;
+use crate::games::guess_word::guess_word;
+use crate::games::relay::relay;
+use crate::games::rhyme_time::rhyme_time;
 use crate::games::scrambled::last_letter_scramble;
+use crate::games::self_play::self_play_report;
 use crate::games::synonym_string::synonym_string;
+use crate::games::turn_timer::init_turn_timers;
+use crate::games::vocab_check::vocab_check;
 use crate::games::word_chain::word_chain;
 use crate::games::word_ladder::word_ladder;
-use crate::state::State;
+use crate::games::{is_retryable_edit, record_submission};
+use crate::logging::init_logging;
+use crate::preferences::{init_preferences, save_preferences};
+use crate::rhymes::init_rhymes;
+use crate::settings::get_settings;
+use crate::state::{open_dialogue_storage, DialogueStorage, State};
+use crate::stats::{init_stats, save_stats};
 use handler::message_handler;
-use log::{error, info};
+use log::{error, info, warn};
+use reqwest::Url;
 use std::collections::HashSet;
 use std::error::Error;
-use teloxide::dispatching::dialogue::InMemStorage;
+use std::sync::Arc;
+use teloxide::dispatching::UpdateHandler;
 use teloxide::prelude::*;
-use tokio::signal;
+use teloxide::update_listeners::webhooks;
 
 type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
 
 /// Initialize environment and logging
 fn initialize_environment() -> Result<()> {
     dotenv::dotenv().ok();
-    pretty_env_logger::init();
+    init_logging();
+
+    if live_lookups_available() {
+        info!("Live dictionary lookups enabled");
+    } else {
+        warn!(
+            "Running in cache-only mode (MERRIAM_WEBSTER_API_KEY unset or CACHE_ONLY set): \
+            only words already in the cache or offline dictionary can be played"
+        );
+    }
+
     info!("Environment initialized");
     Ok(())
 }
@@ -35,88 +87,217 @@ fn initialize_environment() -> Result<()> {
 async fn initialize_cache() -> Result<()> {
     info!("Initializing cache...");
     init_cache().await;
+    init_offline_dictionary();
     info!("Cache initialized");
+
+    // Warm the cache in the background so the bot can start serving right away
+    let settings = get_settings();
+    if let Some(path) = &settings.prefetch_file {
+        tokio::spawn(async move {
+            if let Err(e) = prefetch_words(path, settings.prefetch_concurrency).await {
+                error!("Failed to pre-fetch words from {}: {}", path, e);
+            }
+        });
+    }
     Ok(())
 }
 
+/// Branches routing a message to the handler of the game in progress
+fn game_handler() -> UpdateHandler<teloxide::RequestError> {
+    dptree::entry()
+        .branch(
+            dptree::case![State::WordChain {
+                chain,
+                curr_char,
+                hotseat,
+                board,
+                direction,
+                progress
+            }]
+            .endpoint(word_chain),
+        )
+        .branch(
+            dptree::case![State::ForbiddenLetters {
+                forbidden_letters,
+                chain,
+                curr_char,
+                progress
+            }]
+            .endpoint(forbidden_letters),
+        )
+        .branch(
+            dptree::case![State::AlphabetSprint {
+                alphabet,
+                words,
+                progress
+            }]
+            .endpoint(alphabet_sprint),
+        )
+        .branch(
+            dptree::case![State::LastLetterScramble {
+                level,
+                chain,
+                curr_char,
+                progress
+            }]
+            .endpoint(last_letter_scramble),
+        )
+        .branch(
+            dptree::case![State::WordLengthLadder {
+                curr_len,
+                max_len,
+                chain,
+                curr_char,
+                direction,
+                progress
+            }]
+            .endpoint(word_ladder),
+        )
+        .branch(
+            dptree::case![State::SynonymString {
+                chain,
+                curr_char,
+                min_similarity,
+                progress
+            }]
+            .endpoint(synonym_string),
+        )
+        .branch(
+            dptree::case![State::Relay {
+                chain,
+                curr_char,
+                partner,
+                my_turn,
+                scores,
+                progress
+            }]
+            .endpoint(relay),
+        )
+        .branch(
+            dptree::case![State::VocabCheck {
+                word,
+                answers,
+                progress
+            }]
+            .endpoint(vocab_check),
+        )
+        .branch(
+            dptree::case![State::AnagramShuffle {
+                target,
+                scrambled,
+                solved,
+                progress
+            }]
+            .endpoint(anagram_shuffle),
+        )
+        .branch(
+            dptree::case![State::Categories {
+                theme,
+                threshold,
+                words,
+                progress
+            }]
+            .endpoint(categories),
+        )
+        .branch(dptree::case![State::Ghost { fragment, progress }].endpoint(ghost))
+        .branch(
+            dptree::case![State::Boggle {
+                grid,
+                words,
+                found,
+                deadline,
+                progress
+            }]
+            .endpoint(boggle),
+        )
+        .branch(
+            dptree::case![State::GuessWord {
+                target,
+                revealed,
+                attempts,
+                progress
+            }]
+            .endpoint(guess_word),
+        )
+        .branch(
+            dptree::case![State::CompoundChain {
+                chain,
+                current_tail,
+                progress
+            }]
+            .endpoint(compound_chain),
+        )
+        .branch(dptree::case![State::RhymeTime { chain, progress }].endpoint(rhyme_time))
+}
+
 /// Create and configure the bot's dispatcher
 fn create_dispatcher(
     bot: Bot,
-) -> Dispatcher<Bot, teloxide::RequestError, teloxide::dispatching::DefaultKey> {
+    storage: Arc<DialogueStorage>,
+) -> Dispatcher<Bot, teloxide::RequestError, ChatId> {
     info!("Creating dispatcher...");
 
     let handler = dptree::entry()
         .branch(
             Update::filter_message()
-                .enter_dialogue::<Message, InMemStorage<State>, State>()
+                .inspect(|msg: Message| record_submission(&msg))
+                .enter_dialogue::<Message, DialogueStorage, State>()
                 .branch(dptree::case![State::Start].endpoint(message_handler))
-                .branch(dptree::case![State::WordChain { chain, curr_char }].endpoint(word_chain))
-                .branch(
-                    dptree::case![State::ForbiddenLetters {
-                        forbidden_letters,
-                        chain,
-                        curr_char
-                    }]
-                    .endpoint(forbidden_letters),
-                )
-                .branch(
-                    dptree::case![State::AlphabetSprint { alphabet, words }]
-                        .endpoint(alphabet_sprint),
-                )
-                .branch(
-                    dptree::case![State::LastLetterScramble {
-                        level,
-                        chain,
-                        curr_char
-                    }]
-                    .endpoint(last_letter_scramble),
-                )
-                .branch(
-                    dptree::case![State::WordLengthLadder {
-                        curr_len,
-                        max_len,
-                        chain,
-                        curr_char
-                    }]
-                    .endpoint(word_ladder),
-                )
-                .branch(
-                    dptree::case![State::SynonymString { chain, curr_char }]
-                        .endpoint(synonym_string),
-                ),
+                .branch(game_handler()),
+        )
+        // Editing a rejected word replays it as the player's submission
+        .branch(
+            Update::filter_edited_message()
+                .filter(|msg: Message| is_retryable_edit(&msg))
+                .enter_dialogue::<Message, DialogueStorage, State>()
+                .branch(game_handler()),
         )
         .branch(
             Update::filter_callback_query()
-                .enter_dialogue::<CallbackQuery, InMemStorage<State>, State>()
+                .enter_dialogue::<CallbackQuery, DialogueStorage, State>()
                 .endpoint(handler::callback_handler),
         );
 
     info!("Dispatcher created");
 
     Dispatcher::builder(bot, handler)
-        .dependencies(dptree::deps![InMemStorage::<State>::new()])
+        .dependencies(dptree::deps![storage])
+        .distribution_function(update_chat)
         .enable_ctrlc_handler()
         .build()
 }
 
-/// Setup signal handler for graceful shutdown
-fn setup_shutdown_handler() -> Result<()> {
-    info!("Setting up shutdown handler...");
-
-    tokio::spawn(async move {
-        match signal::ctrl_c().await {
-            Ok(_) => {
-                info!("Shutdown signal received, saving cache...");
-                match save_cache(&get_cache(), "cache.bin") {
-                    Ok(_) => info!("Cache saved successfully before shutdown"),
-                    Err(e) => error!("Failed to save cache: {}", e),
-                }
-            }
-            Err(e) => error!("Failed to listen for shutdown signal: {}", e),
-        }
-    });
+/// Key the dispatcher queues updates by. Updates from one chat are handled one at a time and in
+/// order, so quick successive messages can't both read the same dialogue state and overwrite
+/// each other's changes. Different chats still run concurrently.
+fn update_chat(update: &Update) -> Option<ChatId> {
+    update.chat().map(|chat| chat.id)
+}
 
-    Ok(())
+/// Save the cache and per-chat data before the bot exits. The dispatcher stops on Ctrl-C in
+/// both polling and webhook mode, so this runs after it returns rather than racing it.
+fn save_before_shutdown() {
+    info!("Shutting down, saving cache...");
+    match save_cache(get_cache(), CACHE_PATH) {
+        Ok(_) => info!("Cache saved successfully before shutdown"),
+        Err(e) => error!("Failed to save cache: {}", e),
+    }
+    match save_achievements() {
+        Ok(_) => info!("Achievements saved successfully before shutdown"),
+        Err(e) => error!("Failed to save achievements: {}", e),
+    }
+    match save_preferences() {
+        Ok(_) => info!("Preferences saved successfully before shutdown"),
+        Err(e) => error!("Failed to save preferences: {}", e),
+    }
+    match save_stats() {
+        Ok(_) => info!("Stats saved successfully before shutdown"),
+        Err(e) => error!("Failed to save stats: {}", e),
+    }
+    match save_subscribers() {
+        Ok(_) => info!("Subscribers saved successfully before shutdown"),
+        Err(e) => error!("Failed to save subscribers: {}", e),
+    }
 }
 
 // Main bot setup with both message and callback handlers
@@ -124,21 +305,89 @@ fn setup_shutdown_handler() -> Result<()> {
 async fn main() -> Result<()> {
     // Initialize environment and components
     initialize_environment()?;
+
+    // `build-offline-dict <word list> <output>` builds an offline dictionary and exits
+    let args = std::env::args().collect::<Vec<String>>();
+    if let [_, command, words, output] = args.as_slice() {
+        if command == "build-offline-dict" {
+            init_cache().await;
+            if let Err(e) =
+                build_offline_dictionary(words, output, get_settings().prefetch_concurrency).await
+            {
+                error!("Failed to build offline dictionary: {}", e);
+            }
+            return Ok(());
+        }
+    }
+
+    // `self-play [rounds]` measures how long the bot's Last Letter Scramble chains run and exits
+    if let [_, command, rest @ ..] = args.as_slice() {
+        if command == "self-play" {
+            init_cache().await;
+            init_offline_dictionary();
+            let rounds = rest.first().and_then(|r| r.parse().ok()).unwrap_or(20);
+            match self_play_report(rounds) {
+                Ok(report) => println!("{}", report),
+                Err(e) => error!("Self-play failed: {}", e),
+            }
+            return Ok(());
+        }
+    }
+
     initialize_cache().await?;
+    init_achievements();
+    init_preferences();
+    init_subscribers();
+    init_stats();
+    init_frequencies();
+    init_rhymes();
     info!("Starting word game bot...");
 
     // Create the bot instance
     let bot = Bot::from_env();
 
-    // Setup graceful shutdown handler
-    setup_shutdown_handler()?;
+    // Save the cache as it grows too, so a crash doesn't lose the words fetched since startup
+    if let Some(interval) = get_settings().cache_save_interval {
+        tokio::spawn(run_cache_autosave(interval));
+    }
+
+    // Send the word of the day to subscribed chats
+    tokio::spawn(run_word_of_the_day(bot.clone()));
 
     // Create and run the dispatcher
-    let mut dispatcher = create_dispatcher(bot);
+    let storage = open_dialogue_storage().await;
+    init_turn_timers(bot.clone(), storage.clone());
+    let mut dispatcher = create_dispatcher(bot.clone(), storage);
 
-    // Start the bot and wait for it to finish
-    info!("Bot is now running!");
-    dispatcher.dispatch().await;
+    // Start the bot and wait for it to finish; either way Ctrl-C stops the dispatcher
+    match &get_settings().webhook {
+        Some(webhook) => {
+            let url: Url = webhook
+                .url
+                .as_deref()
+                .ok_or("WEBHOOK_URL must be set when BOT_MODE=webhook")?
+                .parse()?;
+            info!(
+                "Listening for webhook updates on {} ({})",
+                webhook.address, url
+            );
+            let listener =
+                webhooks::axum(bot, webhooks::Options::new(webhook.address, url)).await?;
+            info!("Bot is now running!");
+            dispatcher
+                .dispatch_with_listener(
+                    listener,
+                    LoggingErrorHandler::with_custom_text("An error from the update listener"),
+                )
+                .await;
+        }
+        None => {
+            info!("Bot is now running!");
+            dispatcher.dispatch().await;
+        }
+    }
+
+    save_before_shutdown();
 
     Ok(())
 }
@@ -148,3 +397,96 @@ pub fn contains_any(vec1: &[String], vec2: &[String]) -> bool {
     let set: HashSet<_> = vec1.iter().collect();
     vec2.iter().any(|s| set.contains(s))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_api::FakeBotApi;
+    use futures::stream;
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use teloxide::stop::mk_stop_token;
+    use teloxide::update_listeners::StatefulListener;
+
+    fn update(id: i32, kind: &str, chat_id: i64, text: &str) -> Update {
+        let message = serde_json::json!({
+            "message_id": id,
+            "date": 0,
+            "chat": { "id": chat_id, "type": "private", "first_name": "Player" },
+            "from": { "id": chat_id, "is_bot": false, "first_name": "Player" },
+            "text": text,
+        });
+        let update = match kind {
+            "callback_query" => serde_json::json!({
+                "update_id": id,
+                "callback_query": {
+                    "id": id.to_string(),
+                    "from": { "id": chat_id, "is_bot": false, "first_name": "Player" },
+                    "chat_instance": "1",
+                    "data": "def_cat_1",
+                    "message": message,
+                },
+            }),
+            kind => serde_json::json!({ "update_id": id, kind: message }),
+        };
+        serde_json::from_str(&update.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn messages_sent_in_quick_succession_are_handled_in_order() {
+        let bot = FakeBotApi::start().await.bot();
+        let handled = Arc::new(Mutex::new(Vec::<String>::new()));
+        // The first word takes longest, so a second handler running alongside would finish first
+        let handler = Update::filter_message().endpoint(
+            |msg: Message, handled: Arc<Mutex<Vec<String>>>| async move {
+                let word = msg.text().unwrap_or_default().to_string();
+                handled.lock().unwrap().push(format!("start {}", word));
+                let delay = if word == "cat" { 100 } else { 10 };
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                handled.lock().unwrap().push(format!("end {}", word));
+                respond(())
+            },
+        );
+        let mut dispatcher = Dispatcher::builder(bot, handler)
+            .dependencies(dptree::deps![handled.clone()])
+            .distribution_function(update_chat)
+            .build();
+
+        let updates = vec![
+            update(1, "message", 7, "cat"),
+            update(2, "message", 7, "tiger"),
+        ];
+        let (stop_token, _) = mk_stop_token();
+        let listener = StatefulListener::new(
+            updates,
+            |updates: &mut Vec<Update>| {
+                stream::iter(std::mem::take(updates).into_iter().map(Ok::<_, Infallible>))
+            },
+            move |_: &mut Vec<Update>| stop_token.clone(),
+        );
+        dispatcher
+            .dispatch_with_listener(listener, LoggingErrorHandler::new())
+            .await;
+
+        assert_eq!(
+            *handled.lock().unwrap(),
+            ["start cat", "end cat", "start tiger", "end tiger"]
+        );
+    }
+
+    #[test]
+    fn updates_from_one_chat_share_a_queue() {
+        let first = update(1, "message", 7, "cat");
+        let second = update(2, "message", 7, "tiger");
+        let edit = update(3, "edited_message", 7, "tigers");
+        let tap = update(4, "callback_query", 7, "cat");
+        let other_chat = update(5, "message", 8, "cat");
+
+        assert_eq!(update_chat(&first), Some(ChatId(7)));
+        for same_chat in [&second, &edit, &tap] {
+            assert_eq!(update_chat(same_chat), update_chat(&first));
+        }
+        assert_eq!(update_chat(&other_chat), Some(ChatId(8)));
+    }
+}
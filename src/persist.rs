@@ -0,0 +1,106 @@
+use bincode::{Decode, Encode};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Loads a bincode-encoded value from disk, returning `None` if the file is missing or unreadable
+pub fn load<T: Decode<()>>(file_path: &str) -> Option<T> {
+    let file = File::open(file_path).ok()?;
+    let mut reader = BufReader::new(file);
+
+    match bincode::decode_from_std_read(&mut reader, bincode::config::standard()) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("Failed to decode {}: {}", file_path, e);
+            None
+        }
+    }
+}
+
+/// Saves a value to disk with bincode
+pub fn save<T: Encode>(value: &T, file_path: &str) -> io::Result<()> {
+    let bytes =
+        bincode::encode_to_vec(value, bincode::config::standard()).map_err(io::Error::other)?;
+    write_atomically(file_path, &bytes)
+}
+
+/// Replaces a file's contents without ever leaving it half-written: the bytes go to a `.tmp`
+/// file next to it, which is renamed over the original once fully on disk. A crash mid-write
+/// leaves at worst a stray temporary file, and the original untouched.
+pub fn write_atomically(file_path: &str, bytes: &[u8]) -> io::Result<()> {
+    let tmp_path = format!("{}.tmp", file_path);
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, file_path)
+}
+
+/// Moves an unreadable file aside to `.bad`, so the next save starts clean while the old
+/// contents stay around for inspection
+pub fn quarantine(file_path: &str) {
+    let bad_path = format!("{}.bad", file_path);
+    match fs::rename(file_path, &bad_path) {
+        Ok(()) => log::warn!("Moved unreadable {} to {}", file_path, bad_path),
+        Err(e) => log::error!("Failed to move unreadable {} aside: {}", file_path, e),
+    }
+}
+
+/// Current Unix time in seconds, for timestamps kept on disk or in game state
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// An empty directory of the test's own under the system's temporary directory
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kotosume_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn cache() -> Vec<(String, u32)> {
+        vec![("apple".to_string(), 1), ("pear".to_string(), 2)]
+    }
+
+    #[test]
+    fn interrupted_write_leaves_the_saved_file_intact() {
+        let dir = scratch_dir("interrupted_write");
+        let path = dir.join("cache.bin");
+        let path = path.to_str().unwrap();
+        save(&cache(), path).unwrap();
+
+        // A crash mid-write leaves the temporary file half-written, never renamed into place
+        let bigger = vec![("banana".to_string(), 3); 100];
+        let bytes = bincode::encode_to_vec(&bigger, bincode::config::standard()).unwrap();
+        fs::write(format!("{}.tmp", path), &bytes[..bytes.len() / 2]).unwrap();
+        assert_eq!(load::<Vec<(String, u32)>>(path), Some(cache()));
+
+        // The next save writes over the stray temporary file and replaces the original
+        save(&bigger, path).unwrap();
+        assert_eq!(load::<Vec<(String, u32)>>(path), Some(bigger));
+        assert!(!fs::exists(format!("{}.tmp", path)).unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_write_leaves_the_saved_file_intact() {
+        let dir = scratch_dir("failed_write");
+        let path = dir.join("cache.bin");
+        let path = path.to_str().unwrap();
+        save(&cache(), path).unwrap();
+
+        // The temporary file can't be created where a directory is in the way
+        fs::create_dir(format!("{}.tmp", path)).unwrap();
+        assert!(write_atomically(path, b"partial").is_err());
+        assert_eq!(load::<Vec<(String, u32)>>(path), Some(cache()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
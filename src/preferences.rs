@@ -0,0 +1,447 @@
+use crate::embeddings::EmbeddingError;
+use crate::persist;
+use crate::tone::Tone;
+use bincode::{Decode, Encode};
+use rand::seq::IteratorRandom;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::Bot;
+
+/// How the bot chooses its reply word in Word Chain
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BotStyle {
+    /// The valid word closest in meaning to the player's word
+    #[default]
+    Similar,
+    /// The valid word furthest in meaning from the player's word
+    Dissimilar,
+    /// Any valid word
+    Random,
+}
+
+impl BotStyle {
+    /// All styles in display order
+    const ALL: [BotStyle; 3] = [BotStyle::Similar, BotStyle::Dissimilar, BotStyle::Random];
+
+    /// Name used in the /botstyle command
+    pub fn name(&self) -> &'static str {
+        match self {
+            BotStyle::Similar => "similar",
+            BotStyle::Dissimilar => "dissimilar",
+            BotStyle::Random => "random",
+        }
+    }
+
+    /// What the style does
+    pub fn description(&self) -> &'static str {
+        match self {
+            BotStyle::Similar => "I answer with the word closest in meaning to yours",
+            BotStyle::Dissimilar => "I answer with the word furthest in meaning from yours",
+            BotStyle::Random => "I answer with any valid word",
+        }
+    }
+
+    /// Parse a style name as typed by the player
+    fn parse(s: &str) -> Option<BotStyle> {
+        BotStyle::ALL
+            .into_iter()
+            .find(|style| style.name() == s.trim().to_lowercase())
+    }
+}
+
+/// How strong the bot's reply words are
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Difficulty {
+    /// A random pick among many close words
+    Easy,
+    /// A random pick among a few close words
+    Medium,
+    /// Always the closest word
+    #[default]
+    Hard,
+}
+
+impl Difficulty {
+    /// All difficulties in display order
+    const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    /// Name used in the /difficulty command
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// What the difficulty does
+    pub fn description(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "I answer with any of the 20 words closest to yours",
+            Difficulty::Medium => "I answer with any of the 5 words closest to yours",
+            Difficulty::Hard => "I always answer with the word closest to yours",
+        }
+    }
+
+    /// How many of the closest words the bot picks its reply from
+    pub fn choices(&self) -> usize {
+        match self {
+            Difficulty::Easy => 20,
+            Difficulty::Medium => 5,
+            Difficulty::Hard => 1,
+        }
+    }
+
+    /// The bot's reply among the closest words with their similarity, ordered most similar
+    /// first: the best one on hard, any of them otherwise
+    pub fn choose(&self, words: Vec<(String, f64)>) -> Result<String, EmbeddingError> {
+        let mut words = words.into_iter().map(|(word, _)| word);
+        let choice = match self {
+            Difficulty::Hard => words.next(),
+            Difficulty::Easy | Difficulty::Medium => words.choose(&mut rand::rng()),
+        };
+        choice.ok_or_else(|| EmbeddingError::MissingData("No words to choose from".to_string()))
+    }
+
+    /// Parse a difficulty name as typed by the player
+    fn parse(s: &str) -> Option<Difficulty> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == s.trim().to_lowercase())
+    }
+}
+
+const PREFERENCES_PATH: &str = "preferences.bin";
+static PREFERENCES: OnceLock<Mutex<HashMap<i64, ChatPreferences>>> = OnceLock::new();
+
+/// Preferences chosen by a single chat
+#[derive(Encode, Decode, Clone, Default)]
+struct ChatPreferences {
+    bot_style: BotStyle,
+    /// Show each turn on a single game message edited in place
+    compact: bool,
+    /// Show dictionary example sentences beneath definitions
+    show_examples: bool,
+    /// Personality of the bot's messages
+    tone: Tone,
+    /// Put the bot's definitions behind a button instead of sending them with every word
+    define_on_demand: bool,
+    /// How strong the bot's reply words are
+    difficulty: Difficulty,
+}
+
+/// Initializes preferences from disk if available
+pub fn init_preferences() {
+    let entries: Vec<(i64, ChatPreferences)> = persist::load(PREFERENCES_PATH).unwrap_or_default();
+    log::info!("Loaded preferences for {} chats", entries.len());
+    let _ = PREFERENCES.set(Mutex::new(entries.into_iter().collect()));
+}
+
+/// Saves preferences to disk
+pub fn save_preferences() -> std::io::Result<()> {
+    let entries: Vec<(i64, ChatPreferences)> = get_preferences()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (*k, v.clone()))
+        .collect();
+    persist::save(&entries, PREFERENCES_PATH)
+}
+
+fn get_preferences() -> &'static Mutex<HashMap<i64, ChatPreferences>> {
+    PREFERENCES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The bot reply style chosen in a chat
+pub fn get_bot_style(chat_id: ChatId) -> BotStyle {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .map(|p| p.bot_style)
+        .unwrap_or_default()
+}
+
+/// The difficulty chosen in a chat
+pub fn get_difficulty(chat_id: ChatId) -> Difficulty {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .map(|p| p.difficulty)
+        .unwrap_or_default()
+}
+
+/// The tone of the bot's messages in a chat
+pub fn get_tone(chat_id: ChatId) -> Tone {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .map(|p| p.tone)
+        .unwrap_or_default()
+}
+
+/// Whether compact mode is on in a chat
+pub fn is_compact(chat_id: ChatId) -> bool {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .is_some_and(|p| p.compact)
+}
+
+/// Whether the bot's words come with a 📖 Define button instead of their definitions in a chat
+pub fn defines_on_demand(chat_id: ChatId) -> bool {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .is_some_and(|p| p.define_on_demand)
+}
+
+/// Whether definitions come with example sentences in a chat
+pub fn shows_examples(chat_id: ChatId) -> bool {
+    get_preferences()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .is_some_and(|p| p.show_examples)
+}
+
+/// Handle /examples: toggle example sentences beneath definitions for the chat
+pub async fn handle_examples_command(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let show_examples = {
+        let mut all = get_preferences().lock().unwrap();
+        let prefs = all.entry(chat_id.0).or_default();
+        prefs.show_examples = !prefs.show_examples;
+        prefs.show_examples
+    };
+    log::info!(chat_id = chat_id.0; "Chat set example sentences to {}", show_examples);
+
+    let reply = if show_examples {
+        "Examples on: definitions now come with example sentences from the dictionary, when it has some."
+    } else {
+        "Examples off: definitions are shown without example sentences."
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Handle /definebutton: toggle definitions on demand for the chat
+pub async fn handle_define_button_command(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let define_on_demand = {
+        let mut all = get_preferences().lock().unwrap();
+        let prefs = all.entry(chat_id.0).or_default();
+        prefs.define_on_demand = !prefs.define_on_demand;
+        prefs.define_on_demand
+    };
+    log::info!(chat_id = chat_id.0; "Chat set definitions on demand to {}", define_on_demand);
+
+    let reply = if define_on_demand {
+        "Define button on: my words come with a 📖 Define button, and their definitions only show up when you tap it."
+    } else {
+        "Define button off: my words come with their definitions again."
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Handle /compact: toggle compact mode for the chat
+pub async fn handle_compact_command(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let compact = {
+        let mut all = get_preferences().lock().unwrap();
+        let prefs = all.entry(chat_id.0).or_default();
+        prefs.compact = !prefs.compact;
+        prefs.compact
+    };
+    log::info!(chat_id = chat_id.0; "Chat set compact mode to {}", compact);
+
+    let reply = if compact {
+        "Compact mode on: Word Chain turns are shown on a single message that I keep updating. \
+        Tap a word's 📖 button to read its definition."
+    } else {
+        "Compact mode off: each word, definition and prompt gets its own message again."
+    };
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Handle /botstyle: show the current style, or switch to the one given
+pub async fn handle_bot_style_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    args: &str,
+) -> ResponseResult<()> {
+    let options = BotStyle::ALL
+        .iter()
+        .map(|s| format!("• {}: {}", s.name(), s.description()))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if args.trim().is_empty() {
+        let current = get_bot_style(chat_id);
+        bot.send_message(
+            chat_id,
+            format!(
+                "Current Word Chain bot style: {}\n\nChange it with /botstyle <style>:\n{}",
+                current.name(),
+                options
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match BotStyle::parse(args) {
+        Some(style) => {
+            get_preferences()
+                .lock()
+                .unwrap()
+                .entry(chat_id.0)
+                .or_default()
+                .bot_style = style;
+            log::info!(chat_id = chat_id.0; "Chat switched bot style to {:?}", style);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Word Chain bot style set to {}: {}.",
+                    style.name(),
+                    style.description()
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Unknown style '{}'. Choose one of:\n{}",
+                    args.trim(),
+                    options
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle /tone: show the current tone, or switch to the one given
+pub async fn handle_tone_command(bot: &Bot, chat_id: ChatId, args: &str) -> ResponseResult<()> {
+    let options = Tone::ALL
+        .iter()
+        .map(|t| format!("• {}: {}", t.name(), t.description()))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if args.trim().is_empty() {
+        let current = get_tone(chat_id);
+        bot.send_message(
+            chat_id,
+            format!(
+                "Current tone: {}\n\nChange it with /tone <tone>:\n{}",
+                current.name(),
+                options
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match Tone::parse(args) {
+        Some(tone) => {
+            get_preferences()
+                .lock()
+                .unwrap()
+                .entry(chat_id.0)
+                .or_default()
+                .tone = tone;
+            log::info!(chat_id = chat_id.0; "Chat switched tone to {:?}", tone);
+            bot.send_message(
+                chat_id,
+                format!("Tone set to {}: {}.", tone.name(), tone.description()),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Unknown tone '{}'. Choose one of:\n{}",
+                    args.trim(),
+                    options
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle /difficulty: show the current difficulty, or switch to the one given
+pub async fn handle_difficulty_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    args: &str,
+) -> ResponseResult<()> {
+    let options = Difficulty::ALL
+        .iter()
+        .map(|d| format!("• {}: {}", d.name(), d.description()))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if args.trim().is_empty() {
+        let current = get_difficulty(chat_id);
+        bot.send_message(
+            chat_id,
+            format!(
+                "Current difficulty: {}\n\nChange it with /difficulty <level>:\n{}",
+                current.name(),
+                options
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match Difficulty::parse(args) {
+        Some(difficulty) => {
+            get_preferences()
+                .lock()
+                .unwrap()
+                .entry(chat_id.0)
+                .or_default()
+                .difficulty = difficulty;
+            log::info!(chat_id = chat_id.0; "Chat switched difficulty to {:?}", difficulty);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Difficulty set to {}: {}.",
+                    difficulty.name(),
+                    difficulty.description()
+                ),
+            )
+            .await?;
+        }
+        None => {
+            bot.send_message(
+                chat_id,
+                format!(
+                    "Unknown difficulty '{}'. Choose one of:\n{}",
+                    args.trim(),
+                    options
+                ),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,369 @@
+use crate::dictionary::{Def, DictionaryError};
+use crate::embeddings::is_valid_word;
+use merriam_webster_http::error::MerriamWebsterError;
+use merriam_webster_http::MerriamWebsterClient;
+use merriam_webster_model::Entry;
+use reqwest::Url;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Most example sentences kept for each definition
+const MAX_EXAMPLES: usize = 2;
+
+/// Free Dictionary API endpoint, followed by the word to look up
+const FREE_DICTIONARY_URL: &str = "https://api.dictionaryapi.dev/api/v2/entries/en";
+
+/// Which dictionary live lookups go to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProviderKind {
+    /// Merriam-Webster's Collegiate Dictionary, which needs `MERRIAM_WEBSTER_API_KEY`
+    MerriamWebster,
+    /// The free dictionaryapi.dev, which needs no key
+    FreeDictionary,
+}
+
+impl ProviderKind {
+    /// Parse a provider name as used in the DICTIONARY_PROVIDER setting
+    pub fn parse(s: &str) -> Option<ProviderKind> {
+        match s.trim().to_lowercase().as_str() {
+            "merriam-webster" | "mw" => Some(ProviderKind::MerriamWebster),
+            "free" | "dictionaryapi" => Some(ProviderKind::FreeDictionary),
+            _ => None,
+        }
+    }
+}
+
+/// What a dictionary knows about a word
+pub struct Lookup {
+    /// The word's senses, grouped by part of speech
+    pub defs: Vec<Def>,
+    /// Inflected forms and variants of the word, including the word itself where the dictionary
+    /// lists it
+    pub stems: Vec<String>,
+    /// The dictionary's headword when the word is an inflection of it, e.g. "run" for "running"
+    pub headword: Option<String>,
+    /// Address of a recording of the word's pronunciation, when the dictionary has one
+    pub audio: Option<String>,
+}
+
+/// Future returned by a dictionary lookup
+pub type LookupFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Lookup, DictionaryError>> + Send + 'a>>;
+
+/// A dictionary words can be looked up in live. Caching, the offline dictionary and word
+/// validation sit in front of it, so a provider only answers words none of those could.
+pub trait DictionaryProvider: Send + Sync {
+    /// Name of the dictionary, for logs
+    fn name(&self) -> &'static str;
+
+    /// Look a word up. A word the dictionary has no entry for is `DictionaryError::NotFound`;
+    /// failing to reach the dictionary is `DictionaryError::ApiError`.
+    fn lookup<'a>(&'a self, word: &'a str) -> LookupFuture<'a>;
+}
+
+/// Merriam-Webster's Collegiate Dictionary
+pub struct MerriamWebsterProvider(MerriamWebsterClient);
+
+impl MerriamWebsterProvider {
+    pub fn new(api_key: String) -> Self {
+        MerriamWebsterProvider(MerriamWebsterClient::new(api_key.into()))
+    }
+}
+
+impl DictionaryProvider for MerriamWebsterProvider {
+    fn name(&self) -> &'static str {
+        "Merriam-Webster"
+    }
+
+    fn lookup<'a>(&'a self, word: &'a str) -> LookupFuture<'a> {
+        Box::pin(async move {
+            let entries = self
+                .0
+                .collegiate_definition(word.into())
+                .await
+                .map_err(|e| match e {
+                    // The API answers unknown words with a list of suggestions instead of entries
+                    MerriamWebsterError::SerdeError(e) if e.is_data() => {
+                        DictionaryError::NotFound(format!("No definition found for '{}'", word))
+                    }
+                    // Anything that isn't JSON at all, such as a rate limiting or server error
+                    // page, says nothing about the word
+                    MerriamWebsterError::SerdeError(e) => DictionaryError::ApiError(format!(
+                        "Unexpected response for '{}': {}",
+                        word, e
+                    )),
+                    MerriamWebsterError::HyperError(e) => {
+                        DictionaryError::ApiError(format!("Request for '{}' failed: {}", word, e))
+                    }
+                })?;
+
+            let defs = entries
+                .iter()
+                .filter_map(|d| {
+                    let definitions = d.shortdef.as_ref()?;
+                    Some(Def {
+                        functional_label: d.fl.clone().unwrap_or_default(),
+                        definitions: definitions.iter().map(|s| s.to_string()).collect(),
+                        examples: entry_examples(d),
+                    })
+                })
+                .collect();
+
+            Ok(Lookup {
+                defs,
+                stems: entries.iter().flat_map(|d| d.meta.stems.clone()).collect(),
+                headword: canonical_headword(word, &entries),
+                audio: pronunciation_audio(word, &entries),
+            })
+        })
+    }
+}
+
+/// Collect the usage examples ("verbal illustrations") of a dictionary entry
+fn entry_examples(entry: &Entry) -> Vec<String> {
+    fn collect(value: &serde_json::Value, examples: &mut Vec<String>) {
+        let serde_json::Value::Array(items) = value else {
+            if let serde_json::Value::Object(fields) = value {
+                fields.values().for_each(|v| collect(v, examples));
+            }
+            return;
+        };
+        match items.as_slice() {
+            [serde_json::Value::String(key), serde_json::Value::Array(illustrations)]
+                if key == "vis" =>
+            {
+                examples.extend(
+                    illustrations
+                        .iter()
+                        .filter_map(|vi| vi.get("t")?.as_str())
+                        .map(strip_markup)
+                        .filter(|text| !text.is_empty()),
+                );
+            }
+            _ => items.iter().for_each(|v| collect(v, examples)),
+        }
+    }
+
+    let mut examples = Vec::new();
+    if let Ok(value) = serde_json::to_value(&entry.def) {
+        collect(&value, &mut examples);
+    }
+    examples.truncate(MAX_EXAMPLES);
+    examples
+}
+
+/// Remove Merriam-Webster formatting tokens such as `{it}`, `{wi}` and `{bc}` from text
+fn strip_markup(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        plain.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        match &rest[start + 1..start + end] {
+            "ldquo" => plain.push('“'),
+            "rdquo" => plain.push('”'),
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    plain.push_str(rest);
+    plain.trim().to_string()
+}
+
+/// Address of the MP3 recording of a word's pronunciation on Merriam-Webster's media server,
+/// preferring the entries that list `word` among their stems
+fn pronunciation_audio(word: &str, entries: &[Entry]) -> Option<String> {
+    let (for_word, others): (Vec<&Entry>, Vec<&Entry>) = entries
+        .iter()
+        .partition(|e| e.meta.stems.iter().any(|s| s.eq_ignore_ascii_case(word)));
+    for_word
+        .into_iter()
+        .chain(others)
+        .flat_map(|e| e.hwi.pronunciations.iter().flatten())
+        .find_map(|pr| pr.sound.as_ref())
+        .map(|sound| sound.to_url("en".into(), "us".into(), "mp3".into()))
+}
+
+/// Finds the headword of the first entry listing `word` among its stems.
+///
+/// Only headwords that are themselves playable single words are returned, so the
+/// recorded form can always be chained on and looked up again.
+fn canonical_headword(word: &str, entries: &[Entry]) -> Option<String> {
+    let entry = entries
+        .iter()
+        .find(|e| e.meta.stems.iter().any(|s| s.eq_ignore_ascii_case(word)))?;
+    let headword = entry.meta.id.split(':').next()?.to_lowercase();
+    playable_headword(word, headword)
+}
+
+/// `headword` if it differs from `word` and can itself be chained on and looked up again
+fn playable_headword(word: &str, headword: String) -> Option<String> {
+    if headword == word || headword.contains(char::is_whitespace) || !is_valid_word(&headword) {
+        return None;
+    }
+
+    log::debug!("Normalized '{}' to headword '{}'", word, headword);
+    Some(headword)
+}
+
+/// The free dictionaryapi.dev, for running without a Merriam-Webster key
+pub struct FreeDictionaryProvider {
+    client: reqwest::Client,
+}
+
+/// An entry in a Free Dictionary API response
+#[derive(Deserialize)]
+struct FreeEntry {
+    word: String,
+    #[serde(default)]
+    phonetics: Vec<FreePhonetic>,
+    meanings: Vec<FreeMeaning>,
+}
+
+/// A pronunciation in a Free Dictionary API entry; its audio is often an empty string
+#[derive(Deserialize)]
+struct FreePhonetic {
+    #[serde(default)]
+    audio: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FreeMeaning {
+    part_of_speech: String,
+    definitions: Vec<FreeDefinition>,
+}
+
+#[derive(Deserialize)]
+struct FreeDefinition {
+    definition: String,
+    #[serde(default)]
+    example: Option<String>,
+}
+
+impl FreeDictionaryProvider {
+    pub fn new() -> Self {
+        FreeDictionaryProvider {
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// Address of a word's entry in the Free Dictionary API, with the word percent-encoded as a
+/// single path segment so slashes, question marks and the like can't change the address
+fn free_dictionary_url(word: &str) -> Url {
+    let mut url = Url::parse(FREE_DICTIONARY_URL).expect("Free Dictionary URL is valid");
+    url.path_segments_mut()
+        .expect("Free Dictionary URL has a path")
+        .push(word);
+    url
+}
+
+impl DictionaryProvider for FreeDictionaryProvider {
+    fn name(&self) -> &'static str {
+        "Free Dictionary API"
+    }
+
+    fn lookup<'a>(&'a self, word: &'a str) -> LookupFuture<'a> {
+        Box::pin(async move {
+            let failed = |e: reqwest::Error| {
+                DictionaryError::ApiError(format!("Request for '{}' failed: {}", word, e))
+            };
+            let response = self
+                .client
+                .get(free_dictionary_url(word))
+                .send()
+                .await
+                .map_err(failed)?;
+
+            // Unknown words come back as a 404 with a "No Definitions Found" message
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Err(DictionaryError::NotFound(format!(
+                    "No definition found for '{}'",
+                    word
+                )));
+            }
+            if !status.is_success() {
+                return Err(DictionaryError::ApiError(format!(
+                    "Request for '{}' failed with status {}",
+                    word, status
+                )));
+            }
+
+            let body = response.bytes().await.map_err(failed)?;
+            let entries: Vec<FreeEntry> = serde_json::from_slice(&body).map_err(|e| {
+                DictionaryError::ApiError(format!("Unexpected response for '{}': {}", word, e))
+            })?;
+
+            let defs = entries
+                .iter()
+                .flat_map(|entry| &entry.meanings)
+                .map(|meaning| Def {
+                    functional_label: meaning.part_of_speech.clone(),
+                    definitions: meaning
+                        .definitions
+                        .iter()
+                        .map(|d| d.definition.clone())
+                        .collect(),
+                    examples: meaning
+                        .definitions
+                        .iter()
+                        .filter_map(|d| d.example.clone())
+                        .take(MAX_EXAMPLES)
+                        .collect(),
+                })
+                .filter(|def| !def.definitions.is_empty())
+                .collect();
+
+            // The API has no inflection lists, so the entries' own words stand in for stems
+            let mut stems = vec![word.to_string()];
+            for entry in &entries {
+                let entry_word = entry.word.to_lowercase();
+                if !stems.contains(&entry_word) {
+                    stems.push(entry_word);
+                }
+            }
+            let headword = entries
+                .first()
+                .and_then(|entry| playable_headword(word, entry.word.to_lowercase()));
+            let audio = entries
+                .iter()
+                .flat_map(|entry| &entry.phonetics)
+                .filter_map(|phonetic| phonetic.audio.clone())
+                .find(|audio| !audio.is_empty());
+
+            Ok(Lookup {
+                defs,
+                stems,
+                headword,
+                audio,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_dictionary_url_encodes_the_word() {
+        assert_eq!(
+            free_dictionary_url("cat").as_str(),
+            "https://api.dictionaryapi.dev/api/v2/entries/en/cat"
+        );
+        assert_eq!(
+            free_dictionary_url("and/or").as_str(),
+            "https://api.dictionaryapi.dev/api/v2/entries/en/and%2For"
+        );
+        assert_eq!(
+            free_dictionary_url("what?#x").as_str(),
+            "https://api.dictionaryapi.dev/api/v2/entries/en/what%3F%23x"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::embeddings::open_text;
+use crate::settings::get_settings;
+use itertools::Itertools;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::sync::OnceLock;
+
+/// Pronunciations from the CMU Pronouncing Dictionary, reduced to what rhyming needs
+#[derive(Default)]
+struct CmuDict {
+    /// Rhyming part of each of a word's pronunciations, e.g. "AY T" for "light"
+    endings: HashMap<String, Vec<String>>,
+    /// Words by rhyming part
+    words: HashMap<String, Vec<String>>,
+}
+
+static CMU_DICT: OnceLock<CmuDict> = OnceLock::new();
+
+/// The phones from the last stressed vowel on, without stress marks, so "AY1 T" and "AY2 T"
+/// rhyme. Words with no stressed vowel rhyme from their last vowel.
+fn rhyming_part(phones: &[&str]) -> Option<String> {
+    let start = phones
+        .iter()
+        .rposition(|p| p.ends_with(['1', '2']))
+        .or_else(|| phones.iter().rposition(|p| p.ends_with('0')))?;
+    Some(
+        phones[start..]
+            .iter()
+            .map(|p| p.trim_end_matches(|c: char| c.is_ascii_digit()))
+            .join(" "),
+    )
+}
+
+/// Loads a CMU Pronouncing Dictionary file: a word and its phones per line, e.g.
+/// `LIGHT  L AY1 T`, with alternate pronunciations listed as `LIGHT(2)`. Comment lines start
+/// with `;;;`, and newer releases end lines with `# comments`. Older releases aren't valid
+/// UTF-8 throughout, so lines are read lossily.
+fn load_cmu_dict(file_name: &str) -> io::Result<CmuDict> {
+    let reader = open_text(file_name)?;
+    let mut dict = CmuDict::default();
+
+    for line in reader.split(b'\n') {
+        let line = String::from_utf8_lossy(&line?).into_owned();
+        if line.starts_with(";;;") {
+            continue;
+        }
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let Some(entry) = fields.next() else {
+            continue;
+        };
+        let word = entry.split('(').next().unwrap_or(entry).to_lowercase();
+        let Some(ending) = rhyming_part(&fields.collect::<Vec<&str>>()) else {
+            continue;
+        };
+        let endings = dict.endings.entry(word.clone()).or_default();
+        if !endings.contains(&ending) {
+            endings.push(ending.clone());
+            dict.words.entry(ending).or_default().push(word);
+        }
+    }
+
+    Ok(dict)
+}
+
+/// Initializes the pronouncing dictionary from `CMUDICT_FILE`. Without it Rhyme Time can't
+/// be played, but every other game works as usual.
+pub fn init_rhymes() {
+    let path = &get_settings().cmudict_file;
+    let dict = match load_cmu_dict(path) {
+        Ok(dict) => {
+            info!(
+                "Loaded pronunciations of {} words from {}",
+                dict.endings.len(),
+                path
+            );
+            dict
+        }
+        Err(e) => {
+            warn!(
+                "Failed to load pronouncing dictionary {}, Rhyme Time is unavailable: {}",
+                path, e
+            );
+            CmuDict::default()
+        }
+    };
+    let _ = CMU_DICT.set(dict);
+}
+
+fn get_cmu_dict() -> &'static CmuDict {
+    CMU_DICT.get_or_init(CmuDict::default)
+}
+
+/// Whether any pronunciations were loaded
+pub fn rhymes_available() -> bool {
+    !get_cmu_dict().endings.is_empty()
+}
+
+/// Whether two different words rhyme in any of their pronunciations
+pub fn rhymes(a: &str, b: &str) -> bool {
+    let dict = get_cmu_dict();
+    a != b
+        && match (dict.endings.get(a), dict.endings.get(b)) {
+            (Some(a), Some(b)) => a.iter().any(|ending| b.contains(ending)),
+            _ => false,
+        }
+}
+
+/// Words rhyming with `word` in any of its pronunciations
+pub fn rhymes_of(word: &str) -> Vec<&'static str> {
+    let dict = get_cmu_dict();
+    dict.endings
+        .get(word)
+        .into_iter()
+        .flatten()
+        .filter_map(|ending| dict.words.get(ending))
+        .flatten()
+        .map(String::as_str)
+        .filter(|&w| w != word)
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn missing_dictionary_is_an_error_not_a_panic() {
+        let path = std::env::temp_dir().join("kotosume_no_such_cmudict.txt");
+        assert!(load_cmu_dict(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn words_rhyme_from_their_last_stressed_vowel() {
+        let path =
+            std::env::temp_dir().join(format!("kotosume_cmudict_{}.txt", std::process::id()));
+        fs::write(
+            &path,
+            ";;; comment\n\
+             LIGHT  L AY1 T\n\
+             TIGHT  T AY1 T\n\
+             DELIGHT  D IH0 L AY1 T\n\
+             LIVE  L IH1 V\n\
+             LIVE(2)  L AY1 V\n\
+             FIVE  F AY1 V # number\n",
+        )
+        .unwrap();
+        let dict = load_cmu_dict(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(dict.endings["delight"], ["AY T"]);
+        assert_eq!(dict.endings["live"], ["IH V", "AY V"]);
+        assert_eq!(dict.words["AY V"], ["live", "five"]);
+        assert_eq!(dict.words["AY T"], ["light", "tight", "delight"]);
+    }
+}
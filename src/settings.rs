@@ -0,0 +1,245 @@
+use crate::hints::HintLevel;
+use crate::providers::ProviderKind;
+use std::env;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use teloxide::types::ChatId;
+
+/// Runtime settings read from the environment
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Record inflected submissions under their dictionary headword and chain on its last letter
+    pub chain_on_headword: bool,
+    /// Accept British spellings of words the dictionary only lists under their American spelling
+    pub british_spellings: bool,
+    /// Dictionary that live lookups go to
+    pub dictionary_provider: ProviderKind,
+    /// Serve words only from the cache, without live dictionary lookups
+    pub cache_only: bool,
+    /// How long fetched word details stay cached before they're looked up again; forever when
+    /// unset
+    pub cache_ttl: Option<Duration>,
+    /// How often the word cache is saved while the bot runs; only at shutdown when unset
+    pub cache_save_interval: Option<Duration>,
+    /// Times a live lookup is tried before a failure to reach the dictionary is given up on
+    pub lookup_attempts: u32,
+    /// Wait before the first retry of a failed lookup, doubling with each retry after it
+    pub lookup_retry_delay: Duration,
+    /// Number of a word's senses its definition card pages through; all of them when unset
+    pub max_senses: Option<usize>,
+    /// Path to the word2vec embeddings file, gzip-compressed when it ends in `.gz`
+    pub embeddings_file: String,
+    /// Path to a word frequency list (`word [rank]` per line)
+    pub frequency_file: Option<String>,
+    /// Path to the CMU Pronouncing Dictionary that Rhyme Time checks rhymes with
+    pub cmudict_file: String,
+    /// Minimum number of qualifying words a constrained game needs before it starts
+    pub min_word_pool: usize,
+    /// Path to a newline-delimited word list to pre-fetch into the cache at startup
+    pub prefetch_file: Option<String>,
+    /// Maximum number of dictionary lookups in flight while pre-fetching
+    pub prefetch_concurrency: usize,
+    /// Games (by menu id) that accept any known word even without a dictionary definition
+    pub definition_optional_games: Vec<String>,
+    /// Two chats that can play relay matches against each other
+    pub relay_chats: Option<(ChatId, ChatId)>,
+    /// Let the player keep chaining after the bot runs out of words instead of ending the game
+    pub continue_after_concession: bool,
+    /// Hour of the day (UTC) the word of the day goes out to subscribers
+    pub word_of_the_day_hour: u64,
+    /// Fixed hint verbosity; when unset, hints adapt to each chat's recent results
+    pub hint_level: Option<HintLevel>,
+    /// Bundled dictionary consulted before the API, for offline play
+    pub offline_dict_path: Option<String>,
+    /// Drop hyphens and apostrophes from player words instead of keeping them
+    pub strip_word_punctuation: bool,
+    /// Time a player has to play a word before the game ends; no limit when unset
+    pub turn_timeout: Option<Duration>,
+    /// Remind the player partway through the turn timeout
+    pub turn_nudge: bool,
+    /// Skip an idle player's turn when the turn timeout runs out, instead of ending the game
+    pub turn_timeout_skip: bool,
+    /// Telegram user ids allowed to run admin commands
+    pub admin_user_ids: Vec<u64>,
+    /// Number of accepted words that wins a game against the bot; open-ended play when unset
+    pub word_target: Option<usize>,
+    /// Number of /skip uses the player gets in each chain game against the bot; unlimited when
+    /// unset
+    pub skips_per_game: Option<u32>,
+    /// How a word's points shrink the longer the player takes to play it; every word scores
+    /// full points when unset
+    pub score_decay: Option<ScoreDecay>,
+    /// Lowest Last Letter Scramble level at which the bot avoids replies ending on letters
+    /// with few words to continue from
+    pub play_to_continue_level: u8,
+    /// SQLite database that keeps each chat's game in progress across restarts; games live only
+    /// in memory when unset
+    pub dialogue_db_path: Option<String>,
+    /// Per-module log levels for this crate, e.g. `games=debug,dictionary=warn`
+    pub log_levels: Option<String>,
+    /// Write log records as JSON lines instead of human-readable text
+    pub log_json: bool,
+    /// Receive updates through a webhook instead of long polling; long polling when unset
+    pub webhook: Option<Webhook>,
+}
+
+/// Where Telegram delivers updates in webhook mode
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    /// Local address the webhook server listens on
+    pub address: SocketAddr,
+    /// Public HTTPS URL Telegram posts updates to, proxied to `address`
+    pub url: Option<String>,
+}
+
+/// Decay curve for word points: full points within the grace period, then halving every
+/// half-life
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreDecay {
+    pub grace: Duration,
+    pub half_life: Duration,
+}
+
+impl ScoreDecay {
+    /// Points for a word played `took` after the turn began, never below one
+    pub fn points(&self, full: u32, took: Duration) -> u32 {
+        let late = took.saturating_sub(self.grace).as_secs_f64();
+        let points = f64::from(full) * 0.5f64.powf(late / self.half_life.as_secs_f64());
+        (points.round() as u32).max(1)
+    }
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+impl Settings {
+    /// Build settings from environment variables, falling back to defaults
+    fn from_env() -> Self {
+        let dictionary_provider = env::var("DICTIONARY_PROVIDER")
+            .ok()
+            .and_then(|provider| ProviderKind::parse(&provider))
+            .unwrap_or(ProviderKind::MerriamWebster);
+        // Merriam-Webster can't be reached without a key, so the bot only serves cached words
+        let missing_key = dictionary_provider == ProviderKind::MerriamWebster
+            && env::var("MERRIAM_WEBSTER_API_KEY").is_err();
+
+        Settings {
+            chain_on_headword: env_flag("CHAIN_ON_HEADWORD", false),
+            british_spellings: env_flag("BRITISH_SPELLINGS", false),
+            dictionary_provider,
+            cache_only: env_flag("CACHE_ONLY", missing_key),
+            cache_ttl: Some(env_number("CACHE_TTL_SECS", 0))
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            cache_save_interval: Some(env_number("CACHE_SAVE_INTERVAL_SECS", 300))
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            lookup_attempts: env_number("LOOKUP_ATTEMPTS", 3).max(1),
+            lookup_retry_delay: Duration::from_millis(env_number("LOOKUP_RETRY_DELAY_MS", 500)),
+            max_senses: Some(env_number("MAX_SENSES", 0)).filter(|&max| max > 0),
+            embeddings_file: env::var("EMBEDDINGS_FILE")
+                .unwrap_or_else(|_| "word2vec.txt".to_string()),
+            frequency_file: env::var("FREQUENCY_FILE").ok(),
+            cmudict_file: env::var("CMUDICT_FILE").unwrap_or_else(|_| "cmudict.txt".to_string()),
+            min_word_pool: env_number("MIN_WORD_POOL", 50),
+            prefetch_file: env::var("PREFETCH_FILE").ok(),
+            prefetch_concurrency: env_number("PREFETCH_CONCURRENCY", 4).max(1),
+            definition_optional_games: env::var("DEFINITION_OPTIONAL_GAMES")
+                .map(|games| {
+                    games
+                        .split(',')
+                        .map(|g| g.trim().to_lowercase())
+                        .filter(|g| !g.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            relay_chats: env::var("RELAY_CHAT_IDS").ok().and_then(|ids| {
+                let (a, b) = ids.split_once(',')?;
+                Some((
+                    ChatId(a.trim().parse().ok()?),
+                    ChatId(b.trim().parse().ok()?),
+                ))
+            }),
+            continue_after_concession: env_flag("CONTINUE_AFTER_CONCESSION", false),
+            word_of_the_day_hour: env_number("WORD_OF_THE_DAY_HOUR", 9).min(23),
+            hint_level: env::var("HINT_LEVEL")
+                .ok()
+                .and_then(|level| HintLevel::parse(&level)),
+            offline_dict_path: env::var("OFFLINE_DICT_PATH").ok(),
+            strip_word_punctuation: env_flag("STRIP_WORD_PUNCTUATION", false),
+            turn_timeout: Some(env_number("TURN_TIMEOUT_SECS", 0))
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            turn_nudge: env_flag("TURN_NUDGE", true),
+            turn_timeout_skip: env_flag("TURN_TIMEOUT_SKIP", false),
+            admin_user_ids: env::var("ADMIN_USER_IDS")
+                .map(|ids| {
+                    ids.split(',')
+                        .filter_map(|id| id.trim().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            word_target: Some(env_number("WORD_TARGET", 0)).filter(|&target| target > 0),
+            skips_per_game: Some(env_number("SKIPS_PER_GAME", 3)).filter(|&skips| skips > 0),
+            score_decay: Some(env_number("SCORE_DECAY_HALF_LIFE_SECS", 0))
+                .filter(|&secs| secs > 0)
+                .map(|half_life| ScoreDecay {
+                    grace: Duration::from_secs(env_number("SCORE_DECAY_GRACE_SECS", 5)),
+                    half_life: Duration::from_secs(half_life),
+                }),
+            play_to_continue_level: env_number("PLAY_TO_CONTINUE_LEVEL", 2),
+            dialogue_db_path: env::var("DIALOGUE_DB_PATH").ok(),
+            log_levels: env::var("LOG_LEVELS").ok(),
+            log_json: env::var("LOG_FORMAT").is_ok_and(|format| format.trim() == "json"),
+            webhook: env::var("BOT_MODE")
+                .is_ok_and(|mode| mode.trim().eq_ignore_ascii_case("webhook"))
+                .then(|| Webhook {
+                    address: env_number("WEBHOOK_ADDR", SocketAddr::from(([0, 0, 0, 0], 8443))),
+                    url: env::var("WEBHOOK_URL").ok(),
+                }),
+        }
+    }
+
+    /// The chat a relay match from `chat_id` is played against, if relaying is set up for it
+    pub fn relay_partner(&self, chat_id: ChatId) -> Option<ChatId> {
+        match self.relay_chats? {
+            (a, b) if a == chat_id && b != chat_id => Some(b),
+            (a, b) if b == chat_id && a != chat_id => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Whether a Telegram user may run admin commands
+    pub fn is_admin(&self, user_id: u64) -> bool {
+        self.admin_user_ids.contains(&user_id)
+    }
+
+    /// Whether a player's word must have a dictionary definition in the given game
+    pub fn require_definition(&self, game: &str) -> bool {
+        !self.definition_optional_games.iter().any(|g| g == game)
+    }
+}
+
+/// Gets a reference to the global settings
+pub fn get_settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::from_env)
+}
+
+/// Reads a boolean flag from the environment ("1", "true", "yes" or "on" enable it)
+fn env_flag(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(value) => matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_) => default,
+    }
+}
+
+/// Reads a number from the environment, falling back to `default` when unset or invalid
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
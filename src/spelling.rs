@@ -0,0 +1,89 @@
+/// British spellings that don't follow a suffix rule, with their American forms
+const IRREGULAR: [(&str, &str); 24] = [
+    ("aeroplane", "airplane"),
+    ("ageing", "aging"),
+    ("aluminium", "aluminum"),
+    ("cheque", "check"),
+    ("draught", "draft"),
+    ("enrol", "enroll"),
+    ("fulfil", "fulfill"),
+    ("grey", "gray"),
+    ("jewellery", "jewelry"),
+    ("judgement", "judgment"),
+    ("kerb", "curb"),
+    ("manoeuvre", "maneuver"),
+    ("mould", "mold"),
+    ("moustache", "mustache"),
+    ("plough", "plow"),
+    ("practise", "practice"),
+    ("programme", "program"),
+    ("pyjamas", "pajamas"),
+    ("sceptic", "skeptic"),
+    ("sceptical", "skeptical"),
+    ("skilful", "skillful"),
+    ("smoulder", "smolder"),
+    ("storey", "story"),
+    ("tyre", "tire"),
+];
+
+/// British word endings and their American forms
+const SUFFIXES: [(&str, &str); 32] = [
+    ("isations", "izations"),
+    ("isation", "ization"),
+    ("ising", "izing"),
+    ("ised", "ized"),
+    ("ises", "izes"),
+    ("ise", "ize"),
+    ("ysing", "yzing"),
+    ("ysed", "yzed"),
+    ("yses", "yzes"),
+    ("yse", "yze"),
+    ("ourable", "orable"),
+    ("ouring", "oring"),
+    ("oured", "ored"),
+    ("ourite", "orite"),
+    ("ourites", "orites"),
+    ("ourful", "orful"),
+    ("ours", "ors"),
+    ("our", "or"),
+    ("tres", "ters"),
+    ("tred", "tered"),
+    ("tre", "ter"),
+    ("bres", "bers"),
+    ("bre", "ber"),
+    ("ogues", "ogs"),
+    ("ogue", "og"),
+    ("ences", "enses"),
+    ("ence", "ense"),
+    ("elled", "eled"),
+    ("elling", "eling"),
+    ("eller", "eler"),
+    ("ellers", "elers"),
+    ("yre", "ire"),
+];
+
+/// Words shorter than this aren't respelled by suffix: "hour" and "sure" are no more British
+/// than American
+const MIN_RESPELL_LEN: usize = 5;
+
+/// American spellings a British-spelled word might be listed under, most likely first, e.g.
+/// "color" for "colour" or "organize" for "organise". Empty for words with no British ending.
+pub fn american_spellings(word: &str) -> Vec<String> {
+    let mut spellings = Vec::new();
+    if let Some((_, american)) = IRREGULAR.iter().find(|(british, _)| *british == word) {
+        spellings.push(american.to_string());
+    }
+
+    if word.len() >= MIN_RESPELL_LEN {
+        for (british, american) in SUFFIXES {
+            if let Some(stem) = word.strip_suffix(british) {
+                let respelled = format!("{}{}", stem, american);
+                if !spellings.contains(&respelled) {
+                    spellings.push(respelled);
+                }
+            }
+        }
+    }
+
+    spellings
+}
@@ -1,25 +1,116 @@
 use crate::dictionary::WordInfo;
+use crate::games::outcome::{Progress, Winner};
+use crate::games::word_ladder::LadderDirection;
+use crate::games::{
+    alphabet_sprint, anagram_shuffle, categories, chain_letter, compound_chain, forbidden_letters,
+    rhyme_time, scrambled, synonym_string, word_chain, word_ladder, Direction,
+};
+use crate::settings::get_settings;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
 use std::fmt;
-use teloxide::dispatching::dialogue::InMemStorage;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use teloxide::dispatching::dialogue::serializer::Json;
+use teloxide::dispatching::dialogue::{
+    ErasedStorage, InMemStorage, SqliteStorage, SqliteStorageError, Storage,
+};
 use teloxide::prelude::Dialogue;
+use teloxide::types::{ChatId, MessageId};
+
+/// Where every chat's dialogue state is kept: SQLite or memory, depending on the settings
+pub type DialogueStorage = ErasedStorage<State>;
 
 /// Type alias for dialogues with our state machine
-pub type MyDialogue = Dialogue<State, InMemStorage<State>>;
+pub type MyDialogue = Dialogue<State, DialogueStorage>;
 // pub type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 
+/// Turn tracking for two players sharing one device (hotseat mode)
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Hotseat {
+    /// Index (0 or 1) of the player whose turn it is
+    pub current: usize,
+    /// Words played by each player
+    pub scores: [usize; 2],
+}
+
+impl Hotseat {
+    /// Display number (1 or 2) of the player whose turn it is
+    pub fn player(&self) -> usize {
+        self.current + 1
+    }
+
+    /// Credit the current player with a word and pass the turn
+    pub fn record_word(&mut self) {
+        self.scores[self.current] += 1;
+        self.pass();
+    }
+
+    /// Pass the turn to the other player
+    pub fn pass(&mut self) {
+        self.current = 1 - self.current;
+    }
+
+    /// Current leader, or a tie
+    pub fn winner(&self) -> Winner {
+        match self.scores[0].cmp(&self.scores[1]) {
+            std::cmp::Ordering::Greater => Winner::Seat(1),
+            std::cmp::Ordering::Less => Winner::Seat(2),
+            std::cmp::Ordering::Equal => Winner::Tie,
+        }
+    }
+
+    /// Announcement of the current leader or a tie
+    pub fn result(&self) -> String {
+        match self.scores[0].cmp(&self.scores[1]) {
+            std::cmp::Ordering::Greater => "Player 1 wins!".to_string(),
+            std::cmp::Ordering::Less => "Player 2 wins!".to_string(),
+            std::cmp::Ordering::Equal => "It's a tie!".to_string(),
+        }
+    }
+}
+
 /// Game state machine representing different game modes and their state
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub enum State {
     /// Initial state, no active game
     #[default]
     Start,
 
     /// Word Chain game: players continue a chain where each word starts with the last letter of the previous
+    /// (or, reversed, ends with the first letter of the previous)
     WordChain {
         /// List of words in the current chain
         chain: Vec<WordInfo>,
         /// Current character that the next word must start with
         curr_char: char,
+        /// Two-player turn tracking when playing hotseat instead of against the bot
+        hotseat: Option<Hotseat>,
+        /// Game message edited in place every turn when compact mode is on
+        board: Option<MessageId>,
+        /// Whether words link start-to-last-letter or, reversed, end-to-first-letter
+        direction: Direction,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Relay match: Word Chain against another chat, with the bot passing words between them
+    Relay {
+        /// List of words in the current chain
+        chain: Vec<WordInfo>,
+        /// Current character that the next word must start with
+        curr_char: char,
+        /// The opposing chat
+        partner: ChatId,
+        /// Whether this chat plays the next word
+        my_turn: bool,
+        /// Words played by this chat and by the partner chat
+        scores: [usize; 2],
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
 
     /// Alphabet Sprint: players provide words starting with a specific letter
@@ -28,6 +119,9 @@ pub enum State {
         alphabet: char,
         /// Words already provided for the current letter
         words: Vec<WordInfo>,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
 
     /// Last Letter Scramble: words must start with last letter of previous word plus scrambling rules
@@ -38,6 +132,9 @@ pub enum State {
         chain: Vec<WordInfo>,
         /// Current character that the next word must start with
         curr_char: char,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
 
     /// Synonym String: words must be synonyms or related to the previous word
@@ -46,18 +143,31 @@ pub enum State {
         chain: Vec<WordInfo>,
         /// Current character that the next word must start with
         curr_char: char,
+        /// How similar in meaning each word must be to the previous one
+        #[serde(default = "default_min_similarity")]
+        min_similarity: f64,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
 
     /// Word Length Ladder: words increase or decrease in length progressively
     WordLengthLadder {
         /// Current word length requirement
         curr_len: u8,
-        /// Maximum word length in this ladder
+        /// Word length that completes the ladder: the longest rung going up, the shortest going down
         max_len: u8,
         /// List of words in the current chain
         chain: Vec<WordInfo>,
         /// Current character that the next word must start with
         curr_char: char,
+        /// Whether words grow or shrink each turn; ladders stored before descending ones existed
+        /// climb
+        #[serde(default)]
+        direction: LadderDirection,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
 
     /// Forbidden Letters: words must not contain certain letters
@@ -68,22 +178,317 @@ pub enum State {
         chain: Vec<WordInfo>,
         /// Current character that the next word must start with
         curr_char: char,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Vocabulary Check: the player says which words of increasing rarity they know
+    VocabCheck {
+        /// Word the player is being asked about
+        word: WordInfo,
+        /// Whether the player knew each word asked so far, in order
+        answers: Vec<bool>,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Anagram Shuffle: players find as many words as they can from a scrambled set of letters
+    AnagramShuffle {
+        /// Word the current letters were drawn from
+        target: WordInfo,
+        /// The current letters, in the scrambled order shown to the player
+        scrambled: String,
+        /// Words found so far, across every set of letters
+        solved: Vec<String>,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Categories: players find as many words as they can that belong with a theme
+    Categories {
+        /// Word naming the current theme
+        theme: String,
+        /// Minimum similarity between a word and the theme
+        threshold: f64,
+        /// Words found so far, across every theme
+        words: Vec<WordInfo>,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Ghost: players take turns adding letters without being the one to finish a word
+    Ghost {
+        /// Letters added so far, always the start of some word
+        fragment: String,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Boggle: players trace as many words as they can through a grid of letters before time
+    /// runs out
+    Boggle {
+        /// Rows of the letter grid
+        grid: Vec<Vec<char>>,
+        /// Words the grid holds, listed once when it was dealt for hints and the summary.
+        /// Empty for rounds saved before the list was kept.
+        #[serde(default)]
+        words: Vec<String>,
+        /// Words found so far
+        found: Vec<String>,
+        /// Unix time in seconds the round ends
+        deadline: u64,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Guess the Word: players name the word behind a definition
+    GuessWord {
+        /// Word whose definition is shown
+        target: WordInfo,
+        /// Letters of the word revealed by hints, from the start
+        revealed: usize,
+        /// Wrong guesses so far
+        attempts: u8,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
     },
+
+    /// Compound Chain: players and the bot chain compound words, each starting with the second
+    /// half of the one before
+    CompoundChain {
+        /// List of words in the current chain
+        chain: Vec<WordInfo>,
+        /// Word the next compound must start with
+        current_tail: String,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+
+    /// Rhyme Time: players and the bot chain words that start with the last letter of the word
+    /// before and rhyme with it
+    RhymeTime {
+        /// List of words in the current chain
+        chain: Vec<WordInfo>,
+        /// How the game is going, for scoring and timing it
+        #[serde(default)]
+        progress: Progress,
+    },
+}
+
+/// Synonym String threshold for games stored before players picked one
+fn default_min_similarity() -> f64 {
+    synonym_string::DEFAULT_MIN_SIMILARITY
+}
+
+impl State {
+    /// Menu id of the game in progress, if it's one the turn timer applies to
+    pub fn timed_game(&self) -> Option<&'static str> {
+        match self {
+            State::WordChain {
+                direction: Direction::Reverse,
+                ..
+            } => Some(word_chain::REVERSE_GAME),
+            State::WordChain { .. } => Some(word_chain::GAME),
+            State::AlphabetSprint { .. } => Some(alphabet_sprint::GAME),
+            State::LastLetterScramble { .. } => Some(scrambled::GAME),
+            State::SynonymString { .. } => Some(synonym_string::GAME),
+            State::WordLengthLadder {
+                direction: LadderDirection::Down,
+                ..
+            } => Some(word_ladder::DOWN_GAME),
+            State::WordLengthLadder { .. } => Some(word_ladder::GAME),
+            State::ForbiddenLetters { .. } => Some(forbidden_letters::GAME),
+            State::AnagramShuffle { .. } => Some(anagram_shuffle::GAME),
+            State::Categories { .. } => Some(categories::GAME),
+            State::CompoundChain { .. } => Some(compound_chain::GAME),
+            State::RhymeTime { .. } => Some(rhyme_time::GAME),
+            // Relay turns alternate between chats, so one chat's silence isn't a timeout
+            State::Start | State::Relay { .. } => None,
+            // The check, Ghost and Guess the Word aren't races, so they're never timed
+            State::VocabCheck { .. } | State::Ghost { .. } | State::GuessWord { .. } => None,
+            // The whole round is timed instead, by its own clock
+            State::Boggle { .. } => None,
+        }
+    }
+
+    /// Display name of the game in progress
+    pub fn game_name(&self) -> Option<&'static str> {
+        match self {
+            State::Start => None,
+            State::WordChain {
+                hotseat: Some(_), ..
+            } => Some("two-player Word Chain"),
+            State::WordChain {
+                direction: Direction::Reverse,
+                ..
+            } => Some("Reverse Word Chain"),
+            State::WordChain { .. } => Some("Word Chain"),
+            State::Relay { .. } => Some("relay match"),
+            State::AlphabetSprint { .. } => Some("Alphabet Sprint"),
+            State::LastLetterScramble { .. } => Some("Last Letter Scramble"),
+            State::SynonymString { .. } => Some("Synonym String"),
+            State::WordLengthLadder {
+                direction: LadderDirection::Down,
+                ..
+            } => Some("Descending Word Ladder"),
+            State::WordLengthLadder { .. } => Some("Word Ladder"),
+            State::ForbiddenLetters { .. } => Some("Forbidden Letters"),
+            State::VocabCheck { .. } => Some("Vocabulary Check"),
+            State::AnagramShuffle { .. } => Some("Anagram Shuffle"),
+            State::Categories { .. } => Some("Categories"),
+            State::Ghost { .. } => Some("Ghost"),
+            State::Boggle { .. } => Some("Boggle"),
+            State::GuessWord { .. } => Some("Guess the Word"),
+            State::CompoundChain { .. } => Some("Compound Chain"),
+            State::RhymeTime { .. } => Some("Rhyme Time"),
+        }
+    }
+
+    /// Letter the player's next word must start with (end with, in a reverse Word Chain)
+    pub fn turn_letter(&self) -> Option<char> {
+        match self {
+            State::Start
+            | State::VocabCheck { .. }
+            | State::AnagramShuffle { .. }
+            | State::Categories { .. }
+            | State::Ghost { .. }
+            | State::Boggle { .. }
+            | State::GuessWord { .. } => None,
+            State::AlphabetSprint { alphabet, .. } => Some(*alphabet),
+            State::CompoundChain { current_tail, .. } => current_tail.chars().next(),
+            State::RhymeTime { chain, .. } => chain.last().and_then(|w| chain_letter(&w.word)),
+            State::WordChain { curr_char, .. }
+            | State::Relay { curr_char, .. }
+            | State::LastLetterScramble { curr_char, .. }
+            | State::SynonymString { curr_char, .. }
+            | State::WordLengthLadder { curr_char, .. }
+            | State::ForbiddenLetters { curr_char, .. } => Some(*curr_char),
+        }
+    }
+
+    /// How the player's next word has to use the turn letter
+    pub fn turn_direction(&self) -> Direction {
+        match self {
+            State::WordChain { direction, .. } => *direction,
+            _ => Direction::Forward,
+        }
+    }
+
+    /// How the game in progress is going, if there is one
+    pub fn progress(&self) -> Option<&Progress> {
+        match self {
+            State::Start => None,
+            State::WordChain { progress, .. }
+            | State::Relay { progress, .. }
+            | State::AlphabetSprint { progress, .. }
+            | State::LastLetterScramble { progress, .. }
+            | State::SynonymString { progress, .. }
+            | State::WordLengthLadder { progress, .. }
+            | State::ForbiddenLetters { progress, .. }
+            | State::VocabCheck { progress, .. }
+            | State::AnagramShuffle { progress, .. }
+            | State::Categories { progress, .. }
+            | State::Ghost { progress, .. }
+            | State::Boggle { progress, .. }
+            | State::GuessWord { progress, .. }
+            | State::CompoundChain { progress, .. }
+            | State::RhymeTime { progress, .. } => Some(progress),
+        }
+    }
+
+    /// How the game in progress is going, to update in place
+    pub fn progress_mut(&mut self) -> Option<&mut Progress> {
+        match self {
+            State::Start => None,
+            State::WordChain { progress, .. }
+            | State::Relay { progress, .. }
+            | State::AlphabetSprint { progress, .. }
+            | State::LastLetterScramble { progress, .. }
+            | State::SynonymString { progress, .. }
+            | State::WordLengthLadder { progress, .. }
+            | State::ForbiddenLetters { progress, .. }
+            | State::VocabCheck { progress, .. }
+            | State::AnagramShuffle { progress, .. }
+            | State::Categories { progress, .. }
+            | State::Ghost { progress, .. }
+            | State::Boggle { progress, .. }
+            | State::GuessWord { progress, .. }
+            | State::CompoundChain { progress, .. }
+            | State::RhymeTime { progress, .. } => Some(progress),
+        }
+    }
+
+    /// Words played so far in the game
+    pub fn chain(&self) -> &[WordInfo] {
+        match self {
+            State::Start
+            | State::VocabCheck { .. }
+            | State::AnagramShuffle { .. }
+            | State::Ghost { .. }
+            | State::Boggle { .. }
+            | State::GuessWord { .. } => &[],
+            State::AlphabetSprint { words, .. } | State::Categories { words, .. } => words,
+            State::WordChain { chain, .. }
+            | State::Relay { chain, .. }
+            | State::LastLetterScramble { chain, .. }
+            | State::SynonymString { chain, .. }
+            | State::WordLengthLadder { chain, .. }
+            | State::ForbiddenLetters { chain, .. }
+            | State::CompoundChain { chain, .. }
+            | State::RhymeTime { chain, .. } => chain,
+        }
+    }
 }
 
 impl fmt::Display for State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             State::Start => write!(f, "No active game"),
-            State::WordChain { curr_char, chain } => {
+            State::WordChain {
+                curr_char,
+                chain,
+                hotseat,
+                direction,
+                ..
+            } => {
                 write!(
                     f,
-                    "Word Chain - Next letter: '{}', Chain length: {}",
-                    curr_char,
+                    "Word Chain - Next word {}, Chain length: {}",
+                    direction.describe(*curr_char),
                     chain.len()
+                )?;
+                if let Some(seat) = hotseat {
+                    write!(f, ", Player {}'s turn", seat.player())?;
+                }
+                Ok(())
+            }
+            State::Relay {
+                curr_char,
+                chain,
+                partner,
+                my_turn,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Relay Match vs chat {} - Next letter: '{}', Chain length: {}, {}",
+                    partner,
+                    curr_char,
+                    chain.len(),
+                    if *my_turn { "your turn" } else { "their turn" }
                 )
             }
-            State::AlphabetSprint { alphabet, words } => {
+            State::AlphabetSprint {
+                alphabet, words, ..
+            } => {
                 write!(
                     f,
                     "Alphabet Sprint - Current letter: '{}', Words: {}",
@@ -95,6 +500,7 @@ impl fmt::Display for State {
                 level,
                 curr_char,
                 chain,
+                ..
             } => {
                 write!(
                     f,
@@ -104,10 +510,16 @@ impl fmt::Display for State {
                     chain.len()
                 )
             }
-            State::SynonymString { curr_char, chain } => {
+            State::SynonymString {
+                curr_char,
+                chain,
+                min_similarity,
+                ..
+            } => {
                 write!(
                     f,
-                    "Synonym String - Next letter: '{}', Chain length: {}",
+                    "Synonym String - Minimum similarity: {:.2}, Next letter: '{}', Chain length: {}",
+                    min_similarity,
                     curr_char,
                     chain.len()
                 )
@@ -117,14 +529,17 @@ impl fmt::Display for State {
                 max_len,
                 curr_char,
                 chain,
+                direction,
+                ..
             } => {
-                write!(f, "Word Length Ladder - Current length: {}, Max length: {}, Next letter: '{}', Chain length: {}", 
-                       curr_len, max_len, curr_char, chain.len())
+                write!(f, "Word Length Ladder ({:?}) - Current length: {}, Target length: {}, Next letter: '{}', Chain length: {}", 
+                       direction, curr_len, max_len, curr_char, chain.len())
             }
             State::ForbiddenLetters {
                 forbidden_letters,
                 curr_char,
                 chain,
+                ..
             } => {
                 write!(
                     f,
@@ -134,6 +549,134 @@ impl fmt::Display for State {
                     chain.len()
                 )
             }
+            State::VocabCheck { word, answers, .. } => {
+                write!(
+                    f,
+                    "Vocabulary Check - Word: '{}', Answered: {}",
+                    word.word,
+                    answers.len()
+                )
+            }
+            State::AnagramShuffle {
+                scrambled, solved, ..
+            } => {
+                write!(
+                    f,
+                    "Anagram Shuffle - Letters: '{}', Words found: {}",
+                    scrambled,
+                    solved.len()
+                )
+            }
+            State::Categories { theme, words, .. } => {
+                write!(
+                    f,
+                    "Categories - Theme: '{}', Words found: {}",
+                    theme,
+                    words.len()
+                )
+            }
+            State::Ghost { fragment, .. } => {
+                write!(f, "Ghost - Letters so far: '{}'", fragment)
+            }
+            State::Boggle { found, .. } => {
+                write!(f, "Boggle - Words found: {}", found.len())
+            }
+            State::GuessWord {
+                revealed, attempts, ..
+            } => {
+                write!(
+                    f,
+                    "Guess the Word - Letters revealed: {}, Wrong guesses: {}",
+                    revealed, attempts
+                )
+            }
+            State::CompoundChain {
+                current_tail,
+                chain,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Compound Chain - Next start: '{}', Chain length: {}",
+                    current_tail,
+                    chain.len()
+                )
+            }
+            State::RhymeTime { chain, .. } => {
+                write!(
+                    f,
+                    "Rhyme Time - Last word: '{}', Chain length: {}",
+                    chain.last().map_or("", |w| w.word.as_str()),
+                    chain.len()
+                )
+            }
+        }
+    }
+}
+
+/// SQLite storage that drops a chat's stored state when it can no longer be read, e.g. after an
+/// update changed a game's fields, so the chat starts fresh instead of having every update fail
+struct FreshOnUnreadable(Arc<SqliteStorage<Json>>);
+
+/// Future returned by storage operations
+type StorageFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+impl Storage<State> for FreshOnUnreadable {
+    type Error = SqliteStorageError<serde_json::Error>;
+
+    fn remove_dialogue(self: Arc<Self>, chat_id: ChatId) -> StorageFuture<Result<(), Self::Error>> {
+        Storage::<State>::remove_dialogue(self.0.clone(), chat_id)
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: State,
+    ) -> StorageFuture<Result<(), Self::Error>> {
+        self.0.clone().update_dialogue(chat_id, dialogue)
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> StorageFuture<Result<Option<State>, Self::Error>> {
+        Box::pin(async move {
+            match self.0.clone().get_dialogue(chat_id).await {
+                Err(SqliteStorageError::SerdeError(e)) => {
+                    warn!(
+                        "Discarding unreadable game state of chat {}: {}",
+                        chat_id, e
+                    );
+                    let _ = Storage::<State>::remove_dialogue(self.0.clone(), chat_id).await;
+                    Ok(None)
+                }
+                result => result,
+            }
+        })
+    }
+}
+
+/// Open the dialogue storage: the SQLite database at `DIALOGUE_DB_PATH` so games survive a
+/// restart, or memory when it's unset or can't be opened. Chats with nothing stored, such as
+/// ones that were mid-game before persistence was turned on, simply start at the menu.
+///
+/// States are stored as JSON rather than bincode: they're small, the serializer needs no extra
+/// teloxide feature, and rows stay readable with the sqlite3 shell when debugging a chat.
+pub async fn open_dialogue_storage() -> Arc<DialogueStorage> {
+    let Some(path) = &get_settings().dialogue_db_path else {
+        return InMemStorage::<State>::new().erase();
+    };
+    match SqliteStorage::open(path, Json).await {
+        Ok(storage) => {
+            info!("Keeping games in progress in {}", path);
+            Arc::new(FreshOnUnreadable(storage)).erase()
+        }
+        Err(e) => {
+            error!(
+                "Failed to open dialogue database {}, keeping games in memory: {}",
+                path, e
+            );
+            InMemStorage::<State>::new().erase()
         }
     }
 }
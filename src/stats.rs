@@ -0,0 +1,400 @@
+use crate::persist;
+use bincode::{Decode, Encode};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use teloxide::prelude::{ChatId, Requester, ResponseResult};
+use teloxide::types::UserId;
+use teloxide::Bot;
+
+/// Number of most recent games a chat's skill is judged on
+const RECENT_GAMES: usize = 10;
+/// Games a chat must finish before its skill is judged at all
+const MIN_GAMES: usize = 3;
+/// Daily streak lengths announced to the chat
+const STREAK_MILESTONES: [u32; 6] = [3, 7, 14, 30, 100, 365];
+/// Every this many days of a streak bank a streak freeze
+const FREEZE_EVERY: u32 = 7;
+/// Most streak freezes a chat can bank at once
+const MAX_FREEZES: u32 = 2;
+/// Players listed on a leaderboard
+const LEADERBOARD_SIZE: usize = 10;
+
+const STATS_PATH: &str = "stats.bin";
+static STATS: OnceLock<Mutex<HashMap<i64, ChatStats>>> = OnceLock::new();
+/// Each player's results in each chat, keyed by chat and user id
+static PLAYERS: OnceLock<Mutex<HashMap<(i64, u64), PlayerStats>>> = OnceLock::new();
+
+/// Every chat's stats followed by every player's, as kept on disk
+type StatsFile = (Vec<(i64, ChatStats)>, Vec<((i64, u64), PlayerStats)>);
+
+/// Results of the games a chat played against the bot
+#[derive(Encode, Decode, Clone, Default)]
+struct ChatStats {
+    games_played: u32,
+    wins: u32,
+    /// Whether each of the latest games was won, oldest first
+    recent: Vec<bool>,
+    /// Day number (days since the Unix epoch) a game was last started
+    last_day: u64,
+    /// Consecutive days with a game started, up to `last_day`
+    streak: u32,
+    /// Longest daily streak so far
+    best_streak: u32,
+    /// Banked streak freezes, each covering one missed day
+    freezes: u32,
+    /// Most words in one game's chain, the bot's included
+    longest_chain: u32,
+    /// Words the chat played across all its games
+    words_played: u32,
+}
+
+/// Results of the games one player in a chat played against the bot, for its leaderboard
+#[derive(Encode, Decode, Clone, Default)]
+struct PlayerStats {
+    /// Display name as of the player's latest game
+    name: String,
+    games_played: u32,
+    wins: u32,
+    /// Words the player had accepted
+    words_played: u32,
+    /// Points those words scored: a point per letter plus a bonus for rarer words
+    points: u32,
+}
+
+/// How a leaderboard ranks players
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Ranking {
+    Points,
+    Wins,
+}
+
+impl Ranking {
+    /// Parse a /leaderboard argument; points when it's empty
+    pub fn parse(arg: &str) -> Option<Ranking> {
+        match arg.trim().to_lowercase().as_str() {
+            "" | "points" | "score" => Some(Ranking::Points),
+            "wins" | "won" => Some(Ranking::Wins),
+            _ => None,
+        }
+    }
+}
+
+/// What happened to a chat's daily streak when it started a game
+#[derive(Default)]
+struct StreakChange {
+    /// The streak grew to this length today
+    extended: Option<u32>,
+    /// Freezes spent covering missed days
+    freezes_used: u32,
+    /// A freeze was banked today
+    freeze_earned: bool,
+    /// Length of a streak that was just lost
+    lost: Option<u32>,
+}
+
+impl ChatStats {
+    /// Updates the daily streak for a game started on `today`, spending banked freezes on
+    /// missed days when there are enough of them
+    fn touch_day(&mut self, today: u64) -> StreakChange {
+        let mut change = StreakChange::default();
+        if self.last_day == today {
+            return change;
+        }
+
+        let missed = today.saturating_sub(self.last_day + 1);
+        if self.streak > 0 && missed <= u64::from(self.freezes) {
+            let missed = missed as u32;
+            self.freezes -= missed;
+            change.freezes_used = missed;
+            self.streak += 1;
+        } else {
+            if self.streak > 1 {
+                change.lost = Some(self.streak);
+            }
+            self.streak = 1;
+        }
+        self.last_day = today;
+        self.best_streak = self.best_streak.max(self.streak);
+        change.extended = Some(self.streak);
+
+        if self.streak.is_multiple_of(FREEZE_EVERY) && self.freezes < MAX_FREEZES {
+            self.freezes += 1;
+            change.freeze_earned = true;
+        }
+        change
+    }
+
+    /// The streak as it stands on `today`. It only counts while it's still alive: played today
+    /// or yesterday, or with enough freezes to cover the days missed since.
+    fn live_streak(&self, today: u64) -> u32 {
+        let missed = today.saturating_sub(self.last_day + 1);
+        if missed <= u64::from(self.freezes) {
+            self.streak
+        } else {
+            0
+        }
+    }
+}
+
+/// Current day number since the Unix epoch
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or_default()
+}
+
+/// Initializes stats from disk if available
+pub fn init_stats() {
+    let (chats, players) = read_stats();
+    log::info!(
+        "Loaded stats for {} chats and {} players",
+        chats.len(),
+        players.len()
+    );
+    let _ = STATS.set(Mutex::new(chats.into_iter().collect()));
+    let _ = PLAYERS.set(Mutex::new(players.into_iter().collect()));
+}
+
+/// Reads the stats file. Files written before players had stats of their own hold only the
+/// chats' stats, so the players' are read only when there's more to the file.
+fn read_stats() -> StatsFile {
+    let Ok(bytes) = std::fs::read(STATS_PATH) else {
+        return StatsFile::default();
+    };
+    let config = bincode::config::standard();
+    let (chats, read) = match bincode::decode_from_slice(&bytes, config) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            log::error!("Failed to decode {}: {}", STATS_PATH, e);
+            return StatsFile::default();
+        }
+    };
+    if read == bytes.len() {
+        log::info!(
+            "Upgrading {} from the format without player stats",
+            STATS_PATH
+        );
+        return (chats, Vec::new());
+    }
+    match bincode::decode_from_slice(&bytes[read..], config) {
+        Ok((players, _)) => (chats, players),
+        Err(e) => {
+            log::error!("Failed to decode the player stats in {}: {}", STATS_PATH, e);
+            (chats, Vec::new())
+        }
+    }
+}
+
+/// Saves stats to disk
+pub fn save_stats() -> std::io::Result<()> {
+    let chats = get_stats()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (*k, v.clone()))
+        .collect();
+    let players = get_players()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (*k, v.clone()))
+        .collect();
+    let entries: StatsFile = (chats, players);
+    persist::save(&entries, STATS_PATH)
+}
+
+fn get_stats() -> &'static Mutex<HashMap<i64, ChatStats>> {
+    STATS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_players() -> &'static Mutex<HashMap<(i64, u64), PlayerStats>> {
+    PLAYERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Records a finished game against the bot: whether it was won, how long its chain grew and
+/// how many of the words were the player's
+pub fn record_game(chat_id: ChatId, won: bool, chain_len: usize, player_words: usize) {
+    let mut all = get_stats().lock().unwrap();
+    let stats = all.entry(chat_id.0).or_default();
+    stats.games_played += 1;
+    stats.wins += u32::from(won);
+    stats.longest_chain = stats.longest_chain.max(chain_len as u32);
+    stats.words_played += player_words as u32;
+    stats.recent.push(won);
+    if stats.recent.len() > RECENT_GAMES {
+        stats.recent.remove(0);
+    }
+}
+
+/// Records one player's part in a finished game against the bot: whether it was won, and the
+/// words they had accepted and the points those scored
+pub fn record_player_game(
+    chat_id: ChatId,
+    (user_id, name): (UserId, &str),
+    won: bool,
+    words: usize,
+    points: u32,
+) {
+    let mut all = get_players().lock().unwrap();
+    let stats = all.entry((chat_id.0, user_id.0)).or_default();
+    stats.name = name.to_string();
+    stats.games_played += 1;
+    stats.wins += u32::from(won);
+    stats.words_played += words as u32;
+    stats.points += points;
+}
+
+/// Records that a chat started a game today and announces streak news: milestones, banked
+/// or spent freezes and lost streaks
+pub async fn on_game_started(bot: &Bot, chat_id: ChatId) -> ResponseResult<()> {
+    let (change, freezes) = {
+        let mut all = get_stats().lock().unwrap();
+        let stats = all.entry(chat_id.0).or_default();
+        (stats.touch_day(today()), stats.freezes)
+    };
+
+    let mut messages = Vec::new();
+    if let Some(lost) = change.lost {
+        messages.push(format!(
+            "Your {}-day streak ended. Today starts a new one!",
+            lost
+        ));
+    }
+    if change.freezes_used > 0 {
+        messages.push(format!(
+            "❄️ {} kept your streak alive while you were away.",
+            if change.freezes_used == 1 {
+                "A streak freeze".to_string()
+            } else {
+                format!("{} streak freezes", change.freezes_used)
+            }
+        ));
+    }
+    if let Some(streak) = change.extended.filter(|s| STREAK_MILESTONES.contains(s)) {
+        log::info!(chat_id = chat_id.0; "Chat {} reached a {}-day streak", chat_id, streak);
+        messages.push(format!(
+            "🔥 {}-day streak! You've played {} days in a row.",
+            streak, streak
+        ));
+    }
+    if change.freeze_earned {
+        messages.push(format!(
+            "❄️ You banked a streak freeze ({}/{}). It keeps your streak going if you miss a day.",
+            freezes, MAX_FREEZES
+        ));
+    }
+
+    for message in messages {
+        bot.send_message(chat_id, message).await?;
+    }
+    Ok(())
+}
+
+/// The chat's daily streak as it stands today, counting the days its freezes covered
+pub fn current_streak(chat_id: ChatId) -> u32 {
+    get_stats()
+        .lock()
+        .unwrap()
+        .get(&chat_id.0)
+        .map(|stats| stats.live_streak(today()))
+        .unwrap_or_default()
+}
+
+/// A chat's results and daily streak, for /stats
+pub fn stats_summary(chat_id: ChatId) -> String {
+    let all = get_stats().lock().unwrap();
+    let stats = all.get(&chat_id.0).cloned().unwrap_or_default();
+    let streak = stats.live_streak(today());
+    let win_rate = if stats.games_played > 0 {
+        format!(
+            " ({:.0}%)",
+            f64::from(stats.wins) / f64::from(stats.games_played) * 100.0
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        "Player Statistics\n\n\
+        Games played: {}\n\
+        Wins: {}{}\n\
+        Losses: {}\n\
+        Longest chain: {} words\n\
+        Words played: {}\n\
+        Daily streak: {} day(s) (best: {})\n\
+        Streak freezes banked: {}/{}",
+        stats.games_played,
+        stats.wins,
+        win_rate,
+        stats.games_played - stats.wins,
+        stats.longest_chain,
+        stats.words_played,
+        streak,
+        stats.best_streak,
+        stats.freezes,
+        MAX_FREEZES
+    )
+}
+
+/// The chat's players, best first by the given ranking, for /leaderboard. Ties go to the
+/// player with more of the other measure.
+pub fn leaderboard(chat_id: ChatId, ranking: Ranking) -> String {
+    let all = get_players().lock().unwrap();
+    let mut players = all
+        .iter()
+        .filter(|((chat, _), _)| *chat == chat_id.0)
+        .map(|(_, stats)| stats)
+        .collect::<Vec<&PlayerStats>>();
+    if players.is_empty() {
+        return "No one's on the leaderboard yet — finish a game to get on it!".to_string();
+    }
+    players.sort_by_key(|p| match ranking {
+        Ranking::Points => std::cmp::Reverse((p.points, p.wins)),
+        Ranking::Wins => std::cmp::Reverse((p.wins, p.points)),
+    });
+
+    let mut board = format!(
+        "🏆 Leaderboard by {}\n",
+        match ranking {
+            Ranking::Points => "points",
+            Ranking::Wins => "wins",
+        }
+    );
+    for (rank, player) in players.iter().take(LEADERBOARD_SIZE).enumerate() {
+        board.push_str(&format!(
+            "\n{}. {} — {} points, {} wins in {} games ({} words)",
+            rank + 1,
+            player.name,
+            player.points,
+            player.wins,
+            player.games_played,
+            player.words_played
+        ));
+    }
+    board
+}
+
+/// A player's totals in a private chat, where there's no one to rank them against
+pub fn player_summary(chat_id: ChatId, user_id: UserId) -> String {
+    let all = get_players().lock().unwrap();
+    match all.get(&(chat_id.0, user_id.0)) {
+        Some(stats) => format!(
+            "Leaderboards are for group chats — here it's just you and me. Your totals: {} points from {} words, and {} wins in {} games.",
+            stats.points, stats.words_played, stats.wins, stats.games_played
+        ),
+        None => "Leaderboards are for group chats — here it's just you and me. Finish a game to start your totals, or add me to a group to compete with friends.".to_string(),
+    }
+}
+
+/// Share of recent games the chat won, or `None` until it has played enough to tell
+pub fn recent_win_rate(chat_id: ChatId) -> Option<f64> {
+    let all = get_stats().lock().unwrap();
+    let recent = &all.get(&chat_id.0)?.recent;
+    if recent.len() < MIN_GAMES {
+        return None;
+    }
+    let wins = recent.iter().filter(|&&won| won).count();
+    Some(wins as f64 / recent.len() as f64)
+}
@@ -0,0 +1,163 @@
+use crate::preferences::get_tone;
+use bincode::{Decode, Encode};
+use teloxide::prelude::ChatId;
+
+/// The bot's personality, chosen per chat with /tone
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Tone {
+    /// Plain, to-the-point messages
+    #[default]
+    Neutral,
+    /// Cheerful messages with a few emoji
+    Playful,
+    /// A rival who trash-talks a little
+    Competitive,
+}
+
+impl Tone {
+    /// All tones in display order
+    pub const ALL: [Tone; 3] = [Tone::Neutral, Tone::Playful, Tone::Competitive];
+
+    /// Name used in the /tone command
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tone::Neutral => "neutral",
+            Tone::Playful => "playful",
+            Tone::Competitive => "competitive",
+        }
+    }
+
+    /// What the tone sounds like
+    pub fn description(&self) -> &'static str {
+        match self {
+            Tone::Neutral => "plain and to the point",
+            Tone::Playful => "cheerful, with the odd emoji",
+            Tone::Competitive => "a rival who wants to win",
+        }
+    }
+
+    /// Parse a tone name as typed by the player
+    pub fn parse(s: &str) -> Option<Tone> {
+        Tone::ALL
+            .into_iter()
+            .find(|tone| tone.name() == s.trim().to_lowercase())
+    }
+}
+
+/// A message the games send often enough to be worth phrasing per tone
+pub enum Phrase<'a> {
+    /// The word a game opens with
+    FirstWord(&'a str),
+    /// The bot's reply word
+    BotWord(&'a str),
+    /// Prompt for the player's next word
+    YourTurn(char),
+    /// Prompt for the player's next word in a reverse chain
+    YourTurnEnding(char),
+    /// The player's word starts with the wrong letter
+    MustStartWith(char),
+    /// The player's word ends with the wrong letter in a reverse chain
+    MustEndWith(char),
+    /// The player's word (or a form of it) was already played
+    AlreadyUsed,
+    /// The player skipped their turn
+    Skipping,
+    /// The bot has no word after a skip either, so the game ends
+    SkipStumped,
+    /// The bot ran out of words and the player wins; says what kind of word it couldn't find
+    BotStumped(&'a str),
+    /// The player stopped the named game
+    GameStopped(&'a str),
+    /// Offer to play again after a game ends
+    AnotherRound,
+}
+
+impl Phrase<'_> {
+    /// The phrase worded in the given tone
+    pub fn in_tone(&self, tone: Tone) -> String {
+        use Phrase::*;
+        use Tone::*;
+
+        match (self, tone) {
+            (FirstWord(word), Neutral) => format!("First word: {}", word),
+            (FirstWord(word), Playful) => format!("Let's go! 🎈 First word: {}", word),
+            (FirstWord(word), Competitive) => format!("I'll open. First word: {}", word),
+
+            (BotWord(word), Neutral) => format!("My word: {}", word),
+            (BotWord(word), Playful) => format!("Ooh, I've got one: {} ✨", word),
+            (BotWord(word), Competitive) => format!("Top that: {}", word),
+
+            (YourTurn(c), Neutral) => format!("Now give a word starting with '{}'", c),
+            (YourTurn(c), Playful) => format!("Your turn! Something starting with '{}' 🤔", c),
+            (YourTurn(c), Competitive) => {
+                format!("Your move. A word starting with '{}' — if you can.", c)
+            }
+
+            (YourTurnEnding(c), Neutral) => format!("Now give a word ending with '{}'", c),
+            (YourTurnEnding(c), Playful) => {
+                format!("Your turn! Something ending with '{}' 🤔", c)
+            }
+            (YourTurnEnding(c), Competitive) => {
+                format!("Your move. A word ending with '{}' — if you can.", c)
+            }
+
+            (MustEndWith(c), Neutral) => format!("Your word must end with '{}'", c),
+            (MustEndWith(c), Playful) => format!("Oops! Your word needs to end with '{}' 🙈", c),
+            (MustEndWith(c), Competitive) => format!("Nice try. It has to end with '{}'.", c),
+
+            (MustStartWith(c), Neutral) => format!("Your word must start with '{}'", c),
+            (MustStartWith(c), Playful) => {
+                format!("Oops! Your word needs to start with '{}' 🙈", c)
+            }
+            (MustStartWith(c), Competitive) => format!("Nice try. It has to start with '{}'.", c),
+
+            (AlreadyUsed, Neutral) => "That word (or a form of it) has already been used.".into(),
+            (AlreadyUsed, Playful) => {
+                "Déjà vu! That word (or a form of it) was already played 🔁".into()
+            }
+            (AlreadyUsed, Competitive) => {
+                "Already played that one (or a form of it). No recycling.".into()
+            }
+
+            (Skipping, Neutral) => "Skipping your turn...".into(),
+            (Skipping, Playful) => "No worries, I'll take this one! ⏭️".into(),
+            (Skipping, Competitive) => "Passing already? I'll take the turn.".into(),
+
+            (SkipStumped, Neutral) => "I can't think of a word either! Let's end this game.".into(),
+            (SkipStumped, Playful) => "Hmm, I'm stumped too! 🤷 Let's call it a game.".into(),
+            (SkipStumped, Competitive) => {
+                "Neither of us has a word. Call it a draw — this time.".into()
+            }
+
+            (BotStumped(what), Neutral) => {
+                format!("I can't think of {}! You win this round!", what)
+            }
+            (BotStumped(what), Playful) => {
+                format!("I can't think of {}... you win this round! 🎉", what)
+            }
+            (BotStumped(what), Competitive) => {
+                format!(
+                    "I can't find {}. Fine, this round is yours. I want a rematch.",
+                    what
+                )
+            }
+
+            (GameStopped(game), Neutral) => format!("{} game stopped. Thanks for playing!", game),
+            (GameStopped(game), Playful) => {
+                format!("{} game over! Thanks for playing 💛", game)
+            }
+            (GameStopped(game), Competitive) => format!("{} stopped. Quitting while ahead?", game),
+
+            (AnotherRound, Neutral) => "Fancy another round?".into(),
+            (AnotherRound, Playful) => "That was fun! Another round? 🎲".into(),
+            (AnotherRound, Competitive) => {
+                "Think you can beat me again? Play another round.".into()
+            }
+        }
+    }
+}
+
+/// A phrase worded in the tone chosen in a chat
+pub fn say(chat_id: ChatId, phrase: Phrase) -> String {
+    phrase.in_tone(get_tone(chat_id))
+}